@@ -4,13 +4,19 @@ use std::time::Duration;
 use tracing::{debug, info, instrument, warn};
 
 use super::error::ApiError;
-use super::types::{AlgoliaItem, Comment, Feed, HnItem, Story};
+use super::types::{
+    AlgoliaHit, AlgoliaItem, AlgoliaSearchResponse, Comment, CustomFeed, Feed, HnItem, Story, User,
+};
 use crate::storage::{StorableComment, StorableStory, Storage};
 use crate::time::now_unix;
 
 const DEFAULT_FIREBASE_API: &str = "https://hacker-news.firebaseio.com/v0";
 const DEFAULT_ALGOLIA_API: &str = "https://hn.algolia.com/api/v1";
 const PAGE_SIZE: usize = 30;
+/// Number of search hits requested for custom feeds.
+const SEARCH_HITS: usize = 100;
+/// Most parents followed looking for a comment's story.
+const MAX_THREAD_DEPTH: usize = 200;
 
 /// Stories with their fetch timestamp from storage.
 pub struct FetchedStories {
@@ -78,6 +84,9 @@ impl HnClient {
     }
 
     pub async fn fetch_feed_ids(&self, feed: Feed) -> Result<Vec<u64>, ApiError> {
+        if let Feed::Custom(def) = feed {
+            return self.search_story_ids(def).await;
+        }
         let endpoint = feed
             .endpoint()
             .expect("fetch_feed_ids called on feed without API endpoint");
@@ -85,9 +94,65 @@ impl HnClient {
         self.get_json(&url).await
     }
 
+    /// Resolves a custom feed to story IDs, newest first.
+    ///
+    /// Query feeds issue a single search. Domain feeds search each domain's URLs
+    /// separately and merge the results, dropping hits whose URL only mentions
+    /// the domain somewhere in its path.
+    async fn search_story_ids(&self, def: &CustomFeed) -> Result<Vec<u64>, ApiError> {
+        let hits = if def.domains.is_empty() {
+            self.search(def, &def.query, false).await?
+        } else {
+            let mut hits = Vec::new();
+            for domain in &def.domains {
+                let domain_hits = self.search(def, domain, true).await?;
+                hits.extend(domain_hits.into_iter().filter(|hit| {
+                    hit.url
+                        .as_deref()
+                        .is_some_and(|url| url_matches_domain(url, domain))
+                }));
+            }
+            hits.sort_by_key(|hit| std::cmp::Reverse(hit.created_at_i));
+            hits
+        };
+        let mut seen = std::collections::HashSet::new();
+        Ok(hits
+            .into_iter()
+            .filter_map(|hit| hit.object_id.parse().ok())
+            .filter(|id| seen.insert(*id))
+            .collect())
+    }
+
+    async fn search(
+        &self,
+        def: &CustomFeed,
+        query: &str,
+        url_only: bool,
+    ) -> Result<Vec<AlgoliaHit>, ApiError> {
+        let mut params = vec![
+            ("query", query.to_string()),
+            ("tags", def.tags().to_string()),
+            ("hitsPerPage", SEARCH_HITS.to_string()),
+        ];
+        if let Some(min_points) = def.min_points {
+            params.push(("numericFilters", format!("points>={min_points}")));
+        }
+        if url_only {
+            params.push(("restrictSearchableAttributes", "url".to_string()));
+        }
+        let base = format!("{}/search_by_date", self.algolia_api);
+        let url = reqwest::Url::parse_with_params(&base, &params)
+            .map_err(|e| ApiError::Parse(e.to_string()))?;
+        let response: AlgoliaSearchResponse = self.get_json(url.as_str()).await?;
+        Ok(response.hits)
+    }
+
+    fn item_url(&self, id: u64) -> String {
+        format!("{}/item/{}.json", self.firebase_api, id)
+    }
+
     async fn fetch_item(&self, id: u64) -> Result<HnItem, ApiError> {
-        let url = format!("{}/item/{}.json", self.firebase_api, id);
-        self.get_json(&url).await
+        self.get_json(&self.item_url(id)).await
     }
 
     async fn fetch_algolia_item(&self, id: u64) -> Result<AlgoliaItem, ApiError> {
@@ -188,6 +253,52 @@ impl HnClient {
         Ok(stories)
     }
 
+    /// Finds the story an item belongs to, trying Algolia first then walking
+    /// up from a comment on Firebase. Returns the story, and the comment's id
+    /// when `id` isn't the story itself.
+    pub async fn fetch_thread(&self, id: u64) -> Result<Option<(Story, Option<u64>)>, ApiError> {
+        let cached = self.storage.get_fresh_story(id).await.ok().flatten();
+        let root = if cached.is_some() {
+            id
+        } else {
+            match self.fetch_algolia_item(id).await {
+                Ok(item) => item.story_id.unwrap_or(item.id),
+                Err(e) => {
+                    warn!(source = "algolia", error = %e, "fetch failed, falling back to Firebase");
+                    let Some(root) = self.find_story_firebase(id).await? else {
+                        return Ok(None);
+                    };
+                    root
+                }
+            }
+        };
+        let story = self.fetch_stories_by_ids(&[root], false).await?.pop();
+        Ok(story.map(|story| (story, (id != root).then_some(id))))
+    }
+
+    /// Walks up from an item to its story, one request per parent. Gives up
+    /// after [`MAX_THREAD_DEPTH`] parents, or if an item is missing.
+    async fn find_story_firebase(&self, id: u64) -> Result<Option<u64>, ApiError> {
+        let mut root = id;
+        let mut item: Option<HnItem> = self.get_json(&self.item_url(id)).await?;
+        for _ in 0..MAX_THREAD_DEPTH {
+            let Some(parent) = item.map(|item| item.parent) else {
+                return Ok(None);
+            };
+            let Some(parent) = parent else {
+                return Ok(Some(root));
+            };
+            root = parent;
+            item = self.get_json(&self.item_url(parent)).await?;
+        }
+        Ok(None)
+    }
+
+    pub async fn fetch_user(&self, name: &str) -> Result<Option<User>, ApiError> {
+        let url = format!("{}/user/{}.json", self.firebase_api, name);
+        self.get_json(&url).await
+    }
+
     /// Fetches comments for a story, trying Algolia first then falling back to Firebase.
     #[instrument(skip(self, story), fields(story_id = story.id))]
     pub async fn fetch_comments_flat(
@@ -201,9 +312,9 @@ impl HnClient {
             && let Ok(Some((cached, fetched_at))) = self.storage.get_fresh_comments(story.id).await
         {
             info!(count = cached.len(), source = "cache", "loaded comments");
-            let comments: Vec<Comment> = cached.into_iter().map(std::convert::Into::into).collect();
+            // Cached comments come back in the order they were saved, which is thread order
             return Ok(FetchedComments {
-                comments: order_cached_comments(comments, &story.kids),
+                comments: cached.into_iter().map(std::convert::Into::into).collect(),
                 fetched_at,
             });
         }
@@ -285,6 +396,18 @@ impl HnClient {
     }
 }
 
+/// Returns true if the URL's host is `domain` or one of its subdomains.
+fn url_matches_domain(url: &str, domain: &str) -> bool {
+    let host = url
+        .split("://")
+        .nth(1)
+        .and_then(|rest| rest.split('/').next())
+        .unwrap_or("")
+        .to_ascii_lowercase();
+    let domain = domain.to_ascii_lowercase();
+    host == domain || host.ends_with(&format!(".{domain}"))
+}
+
 fn find_parent_id(comments: &[Comment], comment_id: u64) -> Option<u64> {
     comments
         .iter()
@@ -344,13 +467,6 @@ pub fn build_comment_tree(
     build_tree(items, root_kids, |item| &item.kids, Comment::from_item)
 }
 
-/// Orders cached comments into DFS tree order using stored kids arrays.
-fn order_cached_comments(cached: Vec<Comment>, root_kids: &[u64]) -> Vec<Comment> {
-    let by_id: HashMap<u64, Comment> = cached.into_iter().map(|c| (c.id, c)).collect();
-
-    build_tree(by_id, root_kids, |c| &c.kids, |c, _depth| Some(c))
-}
-
 /// Flattens nested Algolia response into DFS-ordered comments.
 fn flatten_algolia_tree(item: &AlgoliaItem, depth: usize) -> Vec<Comment> {
     let mut comments = Vec::new();
@@ -548,9 +664,8 @@ mod tests {
 
         // Cached path: load from storage and rebuild tree
         let cached = storage.get_comments(story_id).await.unwrap();
-        let cached_as_comments: Vec<Comment> =
+        let cached_comments: Vec<Comment> =
             cached.into_iter().map(std::convert::Into::into).collect();
-        let cached_comments = order_cached_comments(cached_as_comments, &story_kids);
 
         // Both paths must produce identical results
         assert_eq!(
@@ -629,9 +744,8 @@ mod tests {
         storage.save_comments(story_id, &storable).await.unwrap();
 
         let cached = storage.get_comments(story_id).await.unwrap();
-        let cached_as_comments: Vec<Comment> =
+        let cached_comments: Vec<Comment> =
             cached.into_iter().map(std::convert::Into::into).collect();
-        let cached_comments = order_cached_comments(cached_as_comments, &story_kids);
 
         // Verify DFS order: 10, 11, 20, 21, 22
         let expected_order = vec![10, 11, 20, 21, 22];
@@ -993,4 +1107,182 @@ mod tests {
             assert_eq!(comments[1].depth, 1);
         }
     }
+
+    mod threads {
+        use super::*;
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        async fn mount_item(server: &MockServer, item: serde_json::Value) {
+            let id = item["id"].as_u64().unwrap();
+            Mock::given(method("GET"))
+                .and(path(format!("/item/{id}.json")))
+                .respond_with(ResponseTemplate::new(200).set_body_json(item))
+                .mount(server)
+                .await;
+        }
+
+        /// Verifies that a nested comment resolves to its story.
+        #[tokio::test]
+        async fn test_comment_resolves_to_its_story() {
+            let server = MockServer::start().await;
+            mount_item(
+                &server,
+                serde_json::json!({"id": 10, "type": "story", "title": "Root", "by": "op", "kids": [11]}),
+            )
+            .await;
+            mount_item(
+                &server,
+                serde_json::json!({"id": 11, "type": "comment", "parent": 10, "text": "a", "kids": [12]}),
+            )
+            .await;
+            mount_item(
+                &server,
+                serde_json::json!({"id": 12, "type": "comment", "parent": 11, "text": "b"}),
+            )
+            .await;
+            let client = HnClient::with_api_urls(test_storage(), &server.uri(), &server.uri());
+
+            let (story, comment) = client.fetch_thread(12).await.unwrap().unwrap();
+            assert_eq!(story.id, 10);
+            assert_eq!(story.title, "Root");
+            assert_eq!(comment, Some(12));
+
+            let (story, comment) = client.fetch_thread(10).await.unwrap().unwrap();
+            assert_eq!(story.id, 10);
+            assert_eq!(comment, None);
+        }
+
+        /// Verifies that a missing item isn't an error.
+        #[tokio::test]
+        async fn test_missing_item_has_no_thread() {
+            let server = MockServer::start().await;
+            Mock::given(method("GET"))
+                .and(path("/item/5.json"))
+                .respond_with(ResponseTemplate::new(200).set_body_string("null"))
+                .mount(&server)
+                .await;
+            let client = HnClient::with_api_urls(test_storage(), &server.uri(), &server.uri());
+
+            assert!(client.fetch_thread(5).await.unwrap().is_none());
+        }
+
+        /// Verifies that Algolia finds the story without walking the parents.
+        #[tokio::test]
+        async fn test_algolia_names_the_story() {
+            let firebase = MockServer::start().await;
+            let algolia = MockServer::start().await;
+            mount_item(
+                &firebase,
+                serde_json::json!({"id": 10, "type": "story", "title": "Root", "by": "op", "kids": [11]}),
+            )
+            .await;
+            Mock::given(method("GET"))
+                .and(path("/items/12"))
+                .respond_with(ResponseTemplate::new(200).set_body_json(
+                    serde_json::json!({"id": 12, "type": "comment", "story_id": 10, "children": []}),
+                ))
+                .mount(&algolia)
+                .await;
+            let client = HnClient::with_api_urls(test_storage(), &firebase.uri(), &algolia.uri());
+
+            let (story, comment) = client.fetch_thread(12).await.unwrap().unwrap();
+            assert_eq!(story.id, 10);
+            assert_eq!(comment, Some(12));
+        }
+
+        /// Verifies that a walk with no end gives up instead of taking the
+        /// last comment for the story.
+        #[tokio::test]
+        async fn test_endless_parents_have_no_thread() {
+            let server = MockServer::start().await;
+            mount_item(
+                &server,
+                serde_json::json!({"id": 7, "type": "comment", "parent": 7, "text": "loop"}),
+            )
+            .await;
+            let client = HnClient::with_api_urls(test_storage(), &server.uri(), &server.uri());
+
+            assert!(client.fetch_thread(7).await.unwrap().is_none());
+        }
+    }
+
+    mod custom_feeds {
+        use super::*;
+        use crate::test_utils::custom_feed;
+        use wiremock::matchers::{method, path, query_param};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        fn hit(id: u64, url: &str, created_at: u64) -> serde_json::Value {
+            serde_json::json!({"objectID": id.to_string(), "url": url, "created_at_i": created_at})
+        }
+
+        #[test]
+        fn test_url_matches_domain() {
+            assert!(url_matches_domain("https://lwn.net/Articles/1/", "lwn.net"));
+            assert!(url_matches_domain("https://www.LWN.net/", "lwn.net"));
+            assert!(!url_matches_domain(
+                "https://example.com/lwn.net",
+                "lwn.net"
+            ));
+            assert!(!url_matches_domain("https://notlwn.net/", "lwn.net"));
+        }
+
+        #[tokio::test]
+        async fn test_query_feed_uses_search_filters() {
+            let algolia_server = MockServer::start().await;
+            Mock::given(method("GET"))
+                .and(path("/search_by_date"))
+                .and(query_param("query", "rust"))
+                .and(query_param("tags", "story"))
+                .and(query_param("numericFilters", "points>=50"))
+                .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                    "hits": [hit(3, "https://a.com", 30), hit(1, "https://b.com", 10)]
+                })))
+                .mount(&algolia_server)
+                .await;
+            let client = HnClient::with_api_urls(test_storage(), "", &algolia_server.uri());
+            let def = CustomFeed {
+                min_points: Some(50),
+                ..custom_feed("Rust", "rust")
+            };
+            let feeds = Feed::custom(vec![def]);
+            let ids = client.fetch_feed_ids(feeds[0]).await.unwrap();
+            assert_eq!(ids, vec![3, 1]);
+        }
+
+        #[tokio::test]
+        async fn test_domain_feed_merges_and_filters_hits() {
+            let algolia_server = MockServer::start().await;
+            Mock::given(method("GET"))
+                .and(path("/search_by_date"))
+                .and(query_param("query", "lwn.net"))
+                .and(query_param("restrictSearchableAttributes", "url"))
+                .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                    "hits": [
+                        hit(1, "https://lwn.net/Articles/1/", 10),
+                        hit(2, "https://example.com/about-lwn.net", 20),
+                    ]
+                })))
+                .mount(&algolia_server)
+                .await;
+            Mock::given(method("GET"))
+                .and(path("/search_by_date"))
+                .and(query_param("query", "jvns.ca"))
+                .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                    "hits": [hit(3, "https://jvns.ca/blog/", 30), hit(1, "https://lwn.net/Articles/1/", 10)]
+                })))
+                .mount(&algolia_server)
+                .await;
+            let client = HnClient::with_api_urls(test_storage(), "", &algolia_server.uri());
+            let def = CustomFeed {
+                domains: vec!["lwn.net".to_string(), "jvns.ca".to_string()],
+                ..custom_feed("Blogs", "")
+            };
+            let feeds = Feed::custom(vec![def]);
+            let ids = client.fetch_feed_ids(feeds[0]).await.unwrap();
+            // Newest first, off-domain hit dropped, duplicates removed
+            assert_eq!(ids, vec![3, 1]);
+        }
+    }
 }
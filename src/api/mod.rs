@@ -4,4 +4,4 @@ mod types;
 
 pub use client::HnClient;
 pub use error::ApiError;
-pub use types::{Comment, Feed, Story};
+pub use types::{Comment, CustomFeed, Feed, HnLink, Story, User, user_url};
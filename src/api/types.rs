@@ -1,4 +1,6 @@
-use serde::Deserialize;
+use std::borrow::Cow;
+
+use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Deserialize)]
 pub struct HnItem {
@@ -15,7 +17,6 @@ pub struct HnItem {
     pub descendants: Option<u32>,
     #[serde(default)]
     pub kids: Vec<u64>,
-    #[allow(dead_code)]
     pub parent: Option<u64>,
     pub deleted: Option<bool>,
     pub dead: Option<bool>,
@@ -31,11 +32,29 @@ pub struct AlgoliaItem {
     pub created_at_i: Option<u64>,
     #[serde(rename = "type")]
     pub item_type: Option<String>,
+    /// The story a comment belongs to, or a story's own id.
+    pub story_id: Option<u64>,
     #[serde(default)]
     pub children: Vec<Self>,
 }
 
-#[derive(Debug, Clone)]
+/// Algolia API response for `/search` and `/search_by_date`.
+#[derive(Debug, Deserialize)]
+pub struct AlgoliaSearchResponse {
+    #[serde(default)]
+    pub hits: Vec<AlgoliaHit>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AlgoliaHit {
+    #[serde(rename = "objectID")]
+    pub object_id: String,
+    pub url: Option<String>,
+    #[serde(default)]
+    pub created_at_i: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Story {
     pub id: u64,
     pub title: String,
@@ -49,6 +68,60 @@ pub struct Story {
     pub favorited_at: Option<u64>,
 }
 
+/// URL to a user's HN profile page.
+pub fn user_url(name: &str) -> String {
+    format!("https://news.ycombinator.com/user?id={name}")
+}
+
+/// Firebase API response for /user/{id}
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct User {
+    pub id: String,
+    pub created: u64,
+    pub karma: i64,
+    /// Self-description, as HTML.
+    pub about: Option<String>,
+    #[serde(default)]
+    pub submitted: Vec<u64>,
+}
+
+/// A link to a page on HN that can be shown in the app instead of a browser.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HnLink {
+    /// A story or comment, by item id.
+    Item(u64),
+    /// A user's profile, submissions or comments.
+    User(String),
+}
+
+impl HnLink {
+    /// Recognizes `news.ycombinator.com/item?id=…` and `user?id=…` style URLs.
+    /// An item URL with a `#id` fragment points at that comment in the thread.
+    pub fn parse(url: &str) -> Option<Self> {
+        let rest = url
+            .strip_prefix("https://")
+            .or_else(|| url.strip_prefix("http://"))?;
+        let rest = rest.strip_prefix("www.").unwrap_or(rest);
+        let (page, query) = rest
+            .strip_prefix("news.ycombinator.com/")?
+            .split_once('?')?;
+        let (query, fragment) = query.split_once('#').unwrap_or((query, ""));
+        let id = query
+            .split('&')
+            .find_map(|param| param.strip_prefix("id="))
+            .filter(|id| !id.is_empty())?;
+        match page {
+            "item" => fragment
+                .parse()
+                .or_else(|_| id.parse())
+                .ok()
+                .map(Self::Item),
+            "user" | "submitted" | "threads" => Some(Self::User(id.to_string())),
+            _ => None,
+        }
+    }
+}
+
 impl Story {
     pub fn from_item(item: HnItem) -> Option<Self> {
         Some(Self {
@@ -135,6 +208,56 @@ impl Comment {
     }
 }
 
+/// A user-defined feed backed by an Algolia search, declared in settings:
+///
+/// ```toml
+/// [[feeds]]
+/// name = "Rust"
+/// query = "rust"
+/// min_points = 50
+///
+/// [[feeds]]
+/// name = "Blogs"
+/// domains = ["lwn.net", "jvns.ca"]
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CustomFeed {
+    pub name: String,
+    /// Full-text search query.
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub query: String,
+    /// Algolia tag filter, e.g. `"story"` or `"(story,show_hn)"`. Defaults to `"story"`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tags: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_points: Option<u32>,
+    /// Only include stories linking to these domains (or their subdomains).
+    /// Can't be combined with `query`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub domains: Vec<String>,
+}
+
+impl CustomFeed {
+    pub fn tags(&self) -> &str {
+        self.tags.as_deref().unwrap_or("story")
+    }
+
+    /// Hash of what the feed searches for. FNV-1a rather than `DefaultHasher`
+    /// so the value stays the same across builds.
+    fn fingerprint(&self) -> u64 {
+        let definition = format!(
+            "{}\n{}\n{:?}\n{}",
+            self.query,
+            self.tags(),
+            self.min_points,
+            self.domains.join(",")
+        );
+        definition.bytes().fold(0xcbf2_9ce4_8422_2325, |hash, b| {
+            (hash ^ u64::from(b)).wrapping_mul(0x0100_0000_01b3)
+        })
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
 pub enum Feed {
     Favorites,
@@ -145,12 +268,20 @@ pub enum Feed {
     Ask,
     Show,
     Jobs,
+    Custom(&'static CustomFeed),
 }
 
 impl Feed {
+    /// Builds feeds for user-defined searches. The definitions are loaded once
+    /// at startup and leaked so that `Feed` stays `Copy`.
+    pub fn custom(defs: Vec<CustomFeed>) -> Vec<Self> {
+        let defs: &'static [CustomFeed] = Box::leak(defs.into_boxed_slice());
+        defs.iter().map(Self::Custom).collect()
+    }
+
     pub const fn endpoint(self) -> Option<&'static str> {
         match self {
-            Self::Favorites => None,
+            Self::Favorites | Self::Custom(_) => None,
             Self::Top => Some("topstories"),
             Self::New => Some("newstories"),
             Self::Best => Some("beststories"),
@@ -169,6 +300,37 @@ impl Feed {
             Self::Ask => "Ask",
             Self::Show => "Show",
             Self::Jobs => "Jobs",
+            Self::Custom(def) => def.name.as_str(),
+        }
+    }
+
+    /// Returns true if `name` refers to this feed by key ("favorites") or
+    /// label ("Favs"), ignoring case. Used to reference feeds from settings.
+    pub fn matches_name(self, name: &str) -> bool {
+        let name = name.trim();
+        self.key().eq_ignore_ascii_case(name) || self.label().eq_ignore_ascii_case(name)
+    }
+
+    /// Stable identifier used to refer to the feed from settings and saved state.
+    pub fn key(self) -> Cow<'static, str> {
+        match self {
+            Self::Favorites => "favorites".into(),
+            Self::Top => "top".into(),
+            Self::New => "new".into(),
+            Self::Best => "best".into(),
+            Self::Ask => "ask".into(),
+            Self::Show => "show".into(),
+            Self::Jobs => "jobs".into(),
+            Self::Custom(def) => format!("custom:{}", def.name).into(),
+        }
+    }
+
+    /// Identifier for the feed's cached stories and sort. For custom feeds it
+    /// includes the definition, so editing a feed doesn't serve stale results.
+    pub fn cache_key(self) -> Cow<'static, str> {
+        match self {
+            Self::Custom(def) => format!("custom:{}:{:016x}", def.name, def.fingerprint()).into(),
+            _ => self.key(),
         }
     }
 
@@ -261,6 +423,33 @@ mod tests {
         assert_eq!(wrap(7, 7), 0); // After last -> first
     }
 
+    #[test]
+    fn test_hn_link_parsing() {
+        assert_eq!(
+            HnLink::parse("https://news.ycombinator.com/item?id=123"),
+            Some(HnLink::Item(123))
+        );
+        assert_eq!(
+            HnLink::parse("http://www.news.ycombinator.com/item?id=123&p=2"),
+            Some(HnLink::Item(123))
+        );
+        assert_eq!(
+            HnLink::parse("https://news.ycombinator.com/item?id=123#456"),
+            Some(HnLink::Item(456))
+        );
+        assert_eq!(
+            HnLink::parse("https://news.ycombinator.com/user?id=pg"),
+            Some(HnLink::User("pg".to_string()))
+        );
+        assert_eq!(
+            HnLink::parse("https://news.ycombinator.com/threads?id=dang"),
+            Some(HnLink::User("dang".to_string()))
+        );
+        assert_eq!(HnLink::parse("https://news.ycombinator.com/news"), None);
+        assert_eq!(HnLink::parse("https://news.ycombinator.com/item?id="), None);
+        assert_eq!(HnLink::parse("https://example.com/item?id=123"), None);
+    }
+
     #[test]
     fn test_comment_from_valid_item() {
         let item = make_item(Some("Hello world"), false, false);
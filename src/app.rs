@@ -1,11 +1,12 @@
+use std::cell::{Ref, RefCell};
 use std::collections::VecDeque;
 use std::path::PathBuf;
 use std::sync::Arc;
-use std::time::Instant;
+use std::time::{Duration, Instant};
 
 use tokio::sync::mpsc;
 
-use crate::api::{ApiError, Comment, Feed, HnClient, Story};
+use crate::api::{ApiError, Comment, Feed, HnClient, HnLink, Story, User, user_url};
 pub use crate::storage::StorySort;
 
 impl StorySort {
@@ -27,11 +28,47 @@ impl StorySort {
         }
     }
 }
-use crate::comment_tree::CommentTree;
-use crate::settings::{self, Settings};
-use crate::storage::Storage;
-use crate::theme::{ResolvedTheme, Theme, all_themes};
+use crate::command::{self, CommandLine};
+use crate::comment_reads::CommentReads;
+use crate::comment_tree::{CommentSort, CommentTree};
+use crate::highlight::Highlights;
+use crate::hints::{HintAction, HintMatch, Hints};
+use crate::hyperlink::LinkAreas;
+use crate::keys::{Keymaps, PENDING_TIMEOUT, PendingKeys};
+use crate::mouse::{ClickTarget, DOUBLE_CLICK, HitAreas};
+use crate::nav::{NavEntry, NavStack};
+use crate::search::{self, Search, SearchDirection, SearchPrompt, Searched};
+use crate::settings::{self, Settings, TabSettings};
+use crate::storage::{CommentViewState, Storage};
+use crate::theme::{ResolvedTheme, Theme, all_themes, by_name};
 use crate::time::{Clock, now_unix};
+use crate::views::comments::LayoutCache;
+use crate::views::html::{code_blocks, links, strip_html};
+use crate::views::spinner::SPINNER_INTERVAL;
+
+/// `app_state` key holding the key of the last active feed.
+pub const LAST_FEED_KEY: &str = "last_feed";
+
+/// `app_state` key holding the command line history, one entry per line.
+pub const COMMAND_HISTORY_KEY: &str = "command_history";
+
+/// Terminal width from which stories and comments are shown side by side.
+pub const DEFAULT_SPLIT_MIN_WIDTH: u16 = 160;
+
+/// How long the story selection has to settle before its comments are previewed.
+const PREVIEW_DELAY: Duration = Duration::from_millis(250);
+
+/// The spinner keeps turning at least this long so quick loads still show it.
+const MIN_SPINNER_DURATION: Duration = Duration::from_millis(500);
+
+/// How long a flash message stays in the status bar.
+const FLASH_DURATION: Duration = Duration::from_secs(2);
+
+/// How often the running task timers in the debug pane are redrawn.
+const DEBUG_REDRAW: Duration = Duration::from_millis(100);
+
+/// Longest the screen goes without a redraw, to age relative timestamps.
+const IDLE_REDRAW: Duration = Duration::from_mins(1);
 
 pub struct StoriesResult {
     pub generation: u64,
@@ -47,6 +84,12 @@ pub struct CommentsResult {
     pub task_id: u64,
     pub result: Result<Vec<Comment>, ApiError>,
     pub fetched_at: Option<u64>,
+    /// Comment sort picked for this story before (from DB).
+    pub stored_sort: Option<CommentSort>,
+    /// Where the thread was left last time (from DB).
+    pub stored_view: Option<CommentViewState>,
+    /// Ids of the comments read before (from DB).
+    pub read_comments: Vec<u64>,
 }
 
 pub struct SortedStoriesResult {
@@ -60,6 +103,29 @@ pub enum AsyncResult {
     MoreStories(StoriesResult),
     Comments(CommentsResult),
     SortedStories(SortedStoriesResult),
+    Item(ItemResult),
+    User(UserResult),
+}
+
+pub struct ItemResult {
+    pub id: u64,
+    pub task_id: u64,
+    /// The item's story, and the item itself if it's a comment.
+    pub result: Result<Option<(Story, Option<u64>)>, ApiError>,
+}
+
+/// An item being looked up by id, and the view it was requested from.
+#[derive(Debug)]
+pub struct ItemLookup {
+    pub id: u64,
+    pub task_id: u64,
+    pub from: View,
+}
+
+pub struct UserResult {
+    pub name: String,
+    pub task_id: u64,
+    pub result: Result<Option<User>, ApiError>,
 }
 
 #[derive(Debug)]
@@ -153,7 +219,6 @@ impl LoadState {
     }
 
     pub fn should_show_spinner(&self) -> bool {
-        const MIN_SPINNER_DURATION: std::time::Duration = std::time::Duration::from_millis(500);
         self.loading_start
             .is_some_and(|start| self.loading || start.elapsed() < MIN_SPINNER_DURATION)
     }
@@ -172,11 +237,30 @@ pub enum View {
     #[default]
     Stories,
     Comments {
-        story_id: u64,
-        story_title: String,
-        story_index: usize,
-        story_scroll: usize,
+        story: Story,
     },
+    User {
+        name: String,
+    },
+}
+
+/// Comments previewed next to the story list in the split layout.
+#[derive(Debug, Default)]
+pub struct Preview {
+    /// Story whose comments are previewed, loaded or loading. Once they're
+    /// opened, the open thread.
+    pub story_id: Option<u64>,
+    pub loading: bool,
+    pub error: Option<String>,
+    /// Kept apart from the open thread's, so a preview loading in the
+    /// background never mixes into it. Moved over when the thread is opened.
+    pub comment_tree: CommentTree,
+    pub fetched_at: Option<u64>,
+    /// Story the selection moved to, and when, until the preview catches up.
+    pending: Option<(u64, Instant)>,
+    /// Comment selected when the previewed thread was last left, to select
+    /// again when it's opened.
+    resume: Option<u64>,
 }
 
 /// State for the theme picker popup.
@@ -230,15 +314,25 @@ pub enum Message {
     ExpandThread,
     CollapseThread,
     GoToParent,
+    // Jumps between comments
+    NextTopLevel,
+    PrevTopLevel,
+    NextByOp,
+    NextBySameAuthor,
     Back,
+    Forward,
     Quit,
     Refresh,
     ToggleHelp,
     ToggleDebug,
     SwitchFeed(Feed),
+    /// Switch to the feed in this tab, counting from 0.
+    SwitchFeedTab(usize),
     NextFeed,
     PrevFeed,
+    SwitchPane,
     UpdateViewportHeight(u16),
+    UpdateViewportWidth(u16),
     // Theme picker
     OpenThemePicker,
     CloseThemePicker,
@@ -253,19 +347,110 @@ pub enum Message {
     ToggleStoryFavorite,
     // Sorting
     CycleSortOrder,
+    CycleCommentSort,
+    ToggleFlatComments,
+    ZoomComment,
+    ToggleAncestors,
+    ResetCommentView,
+    NextUnread,
+    ToggleUnreadOnly,
     // Context menu
     OpenContextMenu,
     CloseContextMenu,
     ContextMenuUp,
     ContextMenuDown,
     ConfirmContextMenu,
+    // Command line
+    OpenCommandLine,
+    CloseCommandLine,
+    CommandLineInput(char),
+    CommandLineBackspace,
+    CommandLineComplete,
+    CommandLineHistoryPrev,
+    CommandLineHistoryNext,
+    SubmitCommandLine,
+    OpenItem(u64),
+    SetSortOrder(StorySort),
+    SetTheme(String),
+    OpenUserProfile(String),
+    // Links in comments
+    OpenLink,
+    FollowLink(String),
+    // Code blocks in comments
+    CycleCodeBlock,
+    // Search
+    OpenSearch(SearchDirection),
+    SearchInput(char),
+    SearchBackspace,
+    SubmitSearch,
+    CancelSearch,
+    SearchNext,
+    SearchPrev,
+    // Link hints
+    ShowHints(HintAction),
+    HintInput(char),
+    HintBackspace,
+    CancelHints,
+    // Mouse
+    Click(ClickTarget),
+    // Key sequences and counts
+    PendingKeys(PendingKeys),
+    ClearPendingKeys,
+    Repeat(u16, Box<Self>),
+}
+
+impl Message {
+    /// Messages a count prefix repeats, e.g. `5j`. Others ignore the count.
+    pub const fn is_repeatable(&self) -> bool {
+        matches!(
+            self,
+            Self::SelectNext
+                | Self::SelectPrev
+                | Self::PageDown
+                | Self::PageUp
+                | Self::NextFeed
+                | Self::PrevFeed
+                | Self::GoToParent
+                | Self::NextTopLevel
+                | Self::PrevTopLevel
+                | Self::NextByOp
+                | Self::NextBySameAuthor
+                | Self::SearchNext
+                | Self::SearchPrev
+        )
+    }
 }
 
 pub struct App {
     pub view: View,
+    // Views to go back and forward to
+    pub nav: NavStack,
     pub feed: Feed,
     pub stories: Vec<Story>,
+    // Bumped whenever the stories or their order change
+    pub stories_revision: u64,
+    // Feed tabs, in display order
+    pub feeds: Vec<Feed>,
+    // Keybindings per input context, including user overrides
+    pub keymaps: Keymaps,
+    // Incomplete key sequence or count, and when it was last extended
+    pub pending_keys: Option<(PendingKeys, Instant)>,
+    // `:` command prompt and previously run commands, oldest first
+    pub command_line: Option<CommandLine>,
+    pub command_history: Vec<String>,
+    // `/` and `?` search in the current view
+    pub search: Search,
+    // Labelled links while picking one to open, copy or preview
+    pub hints: Option<Hints>,
+    // Code block picked with `c` in a comment, for `y` to copy
+    pub code_block: Option<(u64, usize)>,
     pub comment_tree: CommentTree,
+    // Comment to select once its thread has loaded, from a link to it
+    pub focus_comment: Option<u64>,
+    // Item being looked up by id, so only the latest lookup is shown
+    pub pending_item: Option<ItemLookup>,
+    // Profile shown in the user view
+    pub user: Option<User>,
     pub selected_index: usize,
     pub load: LoadState,
     pub should_quit: bool,
@@ -282,6 +467,10 @@ pub struct App {
     pub debug: DebugState,
     // Viewport tracking for dynamic story loading
     pub viewport_height: Option<u16>,
+    // Split layout: width it kicks in at (0 disables it), and the comment preview
+    pub viewport_width: Option<u16>,
+    pub split_min_width: u16,
+    pub preview: Preview,
     // Theme picker popup
     pub theme_picker: Option<ThemePicker>,
     // Config directory for persisting settings
@@ -293,8 +482,32 @@ pub struct App {
     pub comments_fetched_at: Option<u64>,
     // Sorting state
     pub story_sort: StorySort,
+    // Comment sort for stories that haven't had one picked
+    pub comment_sort: CommentSort,
+    // Comments read as a timeline, oldest first, instead of a tree
+    pub flat_comments: bool,
+    // Comment shown as the root of the thread, with only its replies
+    pub comment_zoom: Option<u64>,
+    // Panel above the comments showing what the selected one replies to
+    pub show_ancestors: bool,
+    // Comments read so far, and whether the ones read are left out
+    pub comment_reads: CommentReads,
+    pub unread_only: bool,
     // Context menu popup
     pub context_menu: Option<ContextMenu>,
+    // User-defined highlight rules from settings
+    pub highlights: Highlights,
+    // Click targets from the last render, and the last click for double-clicks
+    pub hit_areas: RefCell<HitAreas>,
+    pub last_click: Option<(ClickTarget, Instant)>,
+    // Links from the last render, made clickable in the terminal
+    pub link_areas: RefCell<LinkAreas>,
+    // Set when state changes and the screen needs drawing again
+    pub dirty: bool,
+    // Comment text wrapped by earlier frames
+    pub comment_layout: RefCell<LayoutCache>,
+    // Comments drawn in the last frame, counted as read once it's shown
+    pub comments_on_screen: RefCell<Vec<u64>>,
 }
 
 impl App {
@@ -303,9 +516,22 @@ impl App {
         let client = HnClient::new(storage);
         Self {
             view: View::default(),
+            nav: NavStack::default(),
             feed: Feed::default(),
             stories: Vec::new(),
+            stories_revision: 0,
+            feeds: Feed::all().to_vec(),
+            keymaps: Keymaps::default(),
+            pending_keys: None,
+            command_line: None,
+            command_history: Vec::new(),
+            search: Search::default(),
+            hints: None,
+            code_block: None,
             comment_tree: CommentTree::new(),
+            focus_comment: None,
+            pending_item: None,
+            user: None,
             selected_index: 0,
             load: LoadState::new(),
             should_quit: false,
@@ -319,22 +545,41 @@ impl App {
             generation: 0,
             debug: DebugState::new(),
             viewport_height: None,
+            viewport_width: None,
+            split_min_width: DEFAULT_SPLIT_MIN_WIDTH,
+            preview: Preview::default(),
             theme_picker: None,
             config_dir,
             flash_message: None,
             stories_fetched_at: None,
             comments_fetched_at: None,
             story_sort: StorySort::default(),
+            comment_sort: CommentSort::default(),
+            flat_comments: false,
+            comment_zoom: None,
+            show_ancestors: false,
+            comment_reads: CommentReads::default(),
+            unread_only: false,
             context_menu: None,
+            highlights: Highlights::default(),
+            hit_areas: RefCell::default(),
+            last_click: None,
+            link_areas: RefCell::default(),
+            dirty: true,
+            comment_layout: RefCell::default(),
+            comments_on_screen: RefCell::default(),
         }
     }
 
     pub fn handle_async_result(&mut self, result: AsyncResult) {
+        self.dirty = true;
         match result {
             AsyncResult::Stories(r) => self.handle_stories_result(r),
             AsyncResult::MoreStories(r) => self.handle_more_stories_result(r),
             AsyncResult::Comments(r) => self.handle_comments_result(r),
             AsyncResult::SortedStories(r) => self.handle_sorted_stories_result(r),
+            AsyncResult::Item(r) => self.handle_item_result(r),
+            AsyncResult::User(r) => self.handle_user_result(r),
         }
     }
 
@@ -354,6 +599,7 @@ impl App {
         match r.result {
             Ok(stories) => {
                 self.stories = stories;
+                self.stories_revision += 1;
                 self.stories_fetched_at = r.fetched_at;
                 self.load.set_loading(false);
                 self.selected_index = 0;
@@ -399,6 +645,7 @@ impl App {
                     self.load.has_more = false;
                 } else {
                     self.stories.extend(stories);
+                    self.stories_revision += 1;
                     self.load.current_page += 1;
                     // Re-apply sort from DB if active
                     if self.story_sort != StorySort::Position {
@@ -421,10 +668,33 @@ impl App {
     }
 
     fn handle_comments_result(&mut self, r: CommentsResult) {
-        let is_current =
-            matches!(&self.view, View::Comments { story_id, .. } if *story_id == r.story_id);
+        self.comment_reads.load(r.read_comments.iter().copied());
+        let is_current = matches!(&self.view, View::Comments { story } if story.id == r.story_id);
+        let is_preview = self.view == View::Stories && self.preview.story_id == Some(r.story_id);
+        if is_preview {
+            self.handle_preview_result(r);
+            return;
+        }
         if !is_current {
-            self.debug.end_task(r.task_id, "discarded (wrong view)");
+            // Comments for a view left while they loaded are kept for going back
+            if let Some(entry) = self.nav.loading_entry_mut(r.story_id) {
+                entry.loading = false;
+                if let Ok(comments) = r.result {
+                    entry
+                        .comment_tree
+                        .set_sort(r.stored_sort.unwrap_or(self.comment_sort));
+                    entry.comment_tree.set(comments);
+                    let collapsed = r.stored_view.map(|v| v.collapsed).unwrap_or_default();
+                    entry.comment_tree.collapse_each(&collapsed);
+                    entry.comments_fetched_at = r.fetched_at;
+                }
+                self.debug.end_task(r.task_id, "completed (in history)");
+            } else if self.preview.story_id == Some(r.story_id) {
+                // Previewed from the list before going somewhere else
+                self.handle_preview_result(r);
+            } else {
+                self.debug.end_task(r.task_id, "discarded (wrong view)");
+            }
             return;
         }
         self.debug.end_task(
@@ -437,13 +707,67 @@ impl App {
         );
         match r.result {
             Ok(comments) => {
+                // A refresh keeps the thread as it is; otherwise pick it up
+                // where it was left
+                let resume = if self.comment_tree.is_empty() {
+                    r.stored_view
+                } else {
+                    self.comment_view_state().map(|(_, state)| state)
+                };
+                self.comment_tree
+                    .set_sort(r.stored_sort.unwrap_or(self.comment_sort));
                 self.comment_tree.set(comments);
+                // A zoomed comment deleted since stops being zoomed
+                if self.zoom_root().is_none() {
+                    self.comment_zoom = None;
+                }
+                self.comment_reads.settle(self.comment_tree.comments());
                 self.comments_fetched_at = r.fetched_at;
                 self.load.set_loading(false);
+                self.preview.loading = false;
+                if let Some(state) = resume {
+                    self.resume_comment_view(&state);
+                }
+                self.focus_linked_comment();
+                self.mark_selected_comment_read();
             }
             Err(e) => {
                 self.load.set_error(e.user_message());
                 self.load.set_loading(false);
+                self.preview.loading = false;
+                if e.is_fatal() {
+                    self.should_quit = true;
+                }
+            }
+        }
+    }
+
+    /// Comments for the split layout's preview pane. Errors stay in the pane
+    /// so they don't replace the story list.
+    fn handle_preview_result(&mut self, r: CommentsResult) {
+        self.debug.end_task(
+            r.task_id,
+            if r.result.is_ok() {
+                "completed"
+            } else {
+                "failed"
+            },
+        );
+        self.preview.loading = false;
+        match r.result {
+            Ok(comments) => {
+                let tree = &mut self.preview.comment_tree;
+                tree.set_sort(r.stored_sort.unwrap_or(self.comment_sort));
+                tree.set(comments);
+                self.comment_reads.settle(tree.comments());
+                self.preview.fetched_at = r.fetched_at;
+                // The selection waits for the thread to be opened
+                let state = r.stored_view.unwrap_or_default();
+                tree.collapse_each(&state.collapsed);
+                self.preview.resume = state.selected;
+            }
+            Err(e) => {
+                self.preview.error = Some(e.user_message());
                 if e.is_fatal() {
                     self.should_quit = true;
                 }
@@ -458,6 +782,7 @@ impl App {
         }
         if let Ok(stories) = r.result {
             self.stories = stories;
+            self.stories_revision += 1;
             if let Some(fetched_at) = r.fetched_at {
                 self.stories_fetched_at = Some(fetched_at);
             }
@@ -466,29 +791,16 @@ impl App {
 
     #[allow(clippy::needless_pass_by_value)] // Elm architecture: update takes ownership of message
     pub fn update(&mut self, msg: Message) {
+        self.dirty = true;
         self.load.clear_error();
+        if !matches!(msg, Message::PendingKeys(_)) {
+            self.pending_keys = None;
+        }
 
         match msg {
-            Message::SelectNext => {
-                self.select_next();
-                if self.should_load_more() {
-                    self.load_more();
-                }
-            }
+            Message::SelectNext | Message::SelectLast | Message::PageDown => self.move_down(&msg),
             Message::SelectPrev => self.select_prev(),
             Message::SelectFirst => self.select_first(),
-            Message::SelectLast => {
-                self.select_last();
-                if self.should_load_more() {
-                    self.load_more();
-                }
-            }
-            Message::PageDown => {
-                self.page_down();
-                if self.should_load_more() {
-                    self.load_more();
-                }
-            }
             Message::PageUp => self.page_up(),
             Message::OpenUrl => self.open_url(),
             Message::OpenStoryUrl => self.open_story_url(),
@@ -497,25 +809,31 @@ impl App {
             Message::ExpandComment => self.expand_comment(),
             Message::CollapseComment => self.collapse_comment(),
             Message::GoToParent => self.go_to_parent(),
+            Message::NextTopLevel => self.next_toplevel(),
+            Message::PrevTopLevel => self.prev_toplevel(),
+            Message::NextByOp => self.next_by_op(),
+            Message::NextBySameAuthor => self.next_by_same_author(),
             Message::ExpandSubtree => self.expand_subtree(),
             Message::CollapseSubtree => self.collapse_subtree(),
             Message::ExpandThread => self.expand_thread(),
             Message::CollapseThread => self.collapse_thread(),
             Message::Back => self.go_back(),
+            Message::Forward => self.go_forward(),
             Message::Quit => self.should_quit = true,
             Message::Refresh => self.refresh(),
             Message::ToggleHelp => self.help_overlay = !self.help_overlay,
             Message::ToggleDebug => self.debug.toggle(),
             Message::SwitchFeed(feed) => self.switch_feed(feed),
-            Message::NextFeed => self.cycle_feed(1),
-            Message::PrevFeed => self.cycle_feed(-1),
-            Message::UpdateViewportHeight(height) => {
-                let old_height = self.viewport_height;
-                self.viewport_height = Some(height);
-                if old_height.is_none_or(|h| height > h) && self.should_fill_viewport() {
-                    self.load_more();
+            Message::SwitchFeedTab(tab) => {
+                if let Some(&feed) = self.feeds.get(tab) {
+                    self.switch_feed(feed);
                 }
             }
+            Message::NextFeed => self.cycle_feed(1),
+            Message::PrevFeed => self.cycle_feed(-1),
+            Message::SwitchPane => self.switch_pane(),
+            Message::UpdateViewportWidth(width) => self.viewport_width = Some(width),
+            Message::UpdateViewportHeight(height) => self.update_viewport_height(height),
             Message::OpenThemePicker => self.open_theme_picker(),
             Message::CloseThemePicker => self.close_theme_picker(),
             Message::ConfirmThemePicker => self.confirm_theme_picker(),
@@ -526,11 +844,83 @@ impl App {
             Message::ToggleFavorite => self.toggle_favorite(),
             Message::ToggleStoryFavorite => self.toggle_story_favorite(),
             Message::CycleSortOrder => self.cycle_sort_order(),
+            Message::CycleCommentSort => self.cycle_comment_sort(),
+            Message::ToggleFlatComments => self.toggle_flat_comments(),
+            Message::ZoomComment => self.zoom_comment(),
+            Message::ToggleAncestors => self.show_ancestors = !self.show_ancestors,
+            Message::ResetCommentView => self.reset_comment_view(),
+            Message::NextUnread => self.next_unread(),
+            Message::ToggleUnreadOnly => self.toggle_unread_only(),
             Message::OpenContextMenu => self.open_context_menu(),
             Message::CloseContextMenu => self.close_context_menu(),
             Message::ContextMenuUp => self.context_menu_up(),
             Message::ContextMenuDown => self.context_menu_down(),
             Message::ConfirmContextMenu => self.confirm_context_menu(),
+            Message::OpenCommandLine => self.command_line = Some(CommandLine::default()),
+            Message::CloseCommandLine => self.command_line = None,
+            Message::CommandLineInput(_)
+            | Message::CommandLineBackspace
+            | Message::CommandLineComplete
+            | Message::CommandLineHistoryPrev
+            | Message::CommandLineHistoryNext => self.edit_command_line(&msg),
+            Message::SubmitCommandLine => self.submit_command_line(),
+            Message::OpenItem(id) => self.open_item(id),
+            Message::SetSortOrder(sort) => self.set_sort_order(sort),
+            Message::SetTheme(name) => self.set_theme(&name),
+            Message::OpenUserProfile(name) => self.show_user(name),
+            Message::OpenLink => self.open_link(),
+            Message::CycleCodeBlock => self.cycle_code_block(),
+            Message::FollowLink(url) => self.follow_link(&url),
+            Message::OpenSearch(direction) => self.open_search(direction),
+            Message::SearchInput(_) | Message::SearchBackspace => self.edit_search(&msg),
+            Message::ShowHints(action) => self.show_hints(action),
+            Message::HintInput(_) | Message::HintBackspace => self.edit_hints(&msg),
+            Message::CancelHints => self.hints = None,
+            Message::SubmitSearch => self.submit_search(),
+            Message::CancelSearch => self.cancel_search(),
+            Message::SearchNext => self.search_next(self.search.direction),
+            Message::SearchPrev => self.search_next(self.search.direction.reversed()),
+            Message::Click(target) => self.click(target),
+            Message::PendingKeys(pending) => {
+                self.pending_keys = Some((pending, Instant::now()));
+            }
+            Message::ClearPendingKeys => {}
+            Message::Repeat(count, msg) => self.repeat(count, &msg),
+        }
+        self.mark_selected_comment_read();
+    }
+
+    fn repeat(&mut self, count: u16, msg: &Message) {
+        // Feeds are counted through first, so only the last one loads
+        match msg {
+            Message::NextFeed => self.cycle_feed(i32::from(count)),
+            Message::PrevFeed => self.cycle_feed(-i32::from(count)),
+            _ => {
+                for _ in 0..count {
+                    self.update(msg.clone());
+                }
+            }
+        }
+    }
+
+    /// Moves that can reach the end of the loaded stories fetch more.
+    fn move_down(&mut self, msg: &Message) {
+        match msg {
+            Message::SelectNext => self.select_next(),
+            Message::SelectLast => self.select_last(),
+            Message::PageDown => self.page_down(),
+            _ => return,
+        }
+        if self.should_load_more() {
+            self.load_more();
+        }
+    }
+
+    fn update_viewport_height(&mut self, height: u16) {
+        let old_height = self.viewport_height;
+        self.viewport_height = Some(height);
+        if old_height.is_none_or(|h| height > h) && self.should_fill_viewport() {
+            self.load_more();
         }
     }
 
@@ -599,16 +989,252 @@ impl App {
     }
 
     fn cycle_sort_order(&mut self) {
-        if !matches!(self.view, View::Stories) || self.stories.is_empty() {
+        self.set_sort_order(self.story_sort.next());
+    }
+
+    fn set_sort_order(&mut self, sort: StorySort) {
+        if !matches!(self.view, View::Stories) {
+            self.flash("stories can only be sorted from the list");
+            return;
+        }
+        if self.stories.is_empty() {
             return;
         }
-        self.story_sort = self.story_sort.next();
+        self.story_sort = sort;
         self.spawn_sorted_stories_fetch();
         self.spawn_save_feed_sort();
         self.selected_index = 0;
         self.scroll_offset = 0;
     }
 
+    /// Sort the comments the next way, keeping the selected comment selected.
+    /// The sort is remembered for the story, and for stories opened later.
+    fn cycle_comment_sort(&mut self) {
+        let View::Comments { story } = &self.view else {
+            return;
+        };
+        let story_id = story.id;
+        let selected = self.selected_comment().map(|c| c.id);
+        let sort = self.comment_tree.sort().next();
+        self.comment_tree.set_sort(sort);
+        self.comment_sort = sort;
+        let visible = self.visible_comment_indices();
+        let comments = self.comment_tree.comments();
+        self.selected_index = visible
+            .iter()
+            .position(|&i| Some(comments[i].id) == selected)
+            .unwrap_or(0);
+        self.flash(&format!("comments sorted by {}", sort.label()));
+        self.spawn_save_comment_sort(story_id, sort);
+    }
+
+    /// Switch between the tree and the timeline, staying on the same comment.
+    fn toggle_flat_comments(&mut self) {
+        if !matches!(self.view, View::Comments { .. }) {
+            return;
+        }
+        let selected = self.actual_comment_index(self.selected_index);
+        self.flat_comments = !self.flat_comments;
+        self.selected_index = 0;
+        if let Some(index) = selected {
+            self.select_search_position(index);
+        }
+        self.flash(if self.flat_comments {
+            "comments as a timeline"
+        } else {
+            "comments as a tree"
+        });
+    }
+
+    /// Show the selected comment as the root of the thread, so its replies
+    /// are indented from the left edge. On the root, go back to the thread.
+    fn zoom_comment(&mut self) {
+        if !matches!(self.view, View::Comments { .. }) {
+            return;
+        }
+        let Some(index) = self.actual_comment_index(self.selected_index) else {
+            return;
+        };
+        let comment = &self.comment_tree.comments()[index];
+        if self.comment_zoom == Some(comment.id) {
+            self.unzoom();
+        } else if comment.kids.is_empty() {
+            self.flash("no replies to zoom into");
+        } else {
+            let id = comment.id;
+            self.comment_zoom = Some(id);
+            self.comment_tree.expand(id);
+            self.select_search_position(index);
+        }
+    }
+
+    /// Go back from a zoomed comment to the whole thread, staying on the same
+    /// comment. Returns whether the view was zoomed.
+    fn unzoom(&mut self) -> bool {
+        if self.zoom_root().is_none() {
+            return false;
+        }
+        let selected = self.actual_comment_index(self.selected_index);
+        self.comment_zoom = None;
+        if let Some(index) = selected {
+            self.select_search_position(index);
+        }
+        true
+    }
+
+    /// The comments the selected one is nested under, top-level first, for
+    /// the ancestor panel. Empty while the panel is hidden. When zoomed, the
+    /// chain stops at the zoomed comment, as the breadcrumb names the rest.
+    pub fn selected_ancestors(&self) -> Vec<&Comment> {
+        if !self.show_ancestors || !matches!(self.view, View::Comments { .. }) {
+            return Vec::new();
+        }
+        let visible = self.visible_comment_indices();
+        let tree = &self.comment_tree;
+        let parent = |index| tree.find_parent_visible_index(&visible, index);
+        let mut ancestors: Vec<&Comment> =
+            std::iter::successors(parent(self.selected_index), |&i| parent(i))
+                .map(|i| &tree.comments()[visible[i]])
+                .collect();
+        ancestors.reverse();
+        ancestors
+    }
+
+    /// The open thread's story id, and where it is in it: the collapsed
+    /// comments and the one selected.
+    pub fn comment_view_state(&self) -> Option<(u64, CommentViewState)> {
+        let View::Comments { story } = &self.view else {
+            return None;
+        };
+        if self.comment_tree.is_empty() {
+            return None;
+        }
+        let state = CommentViewState {
+            collapsed: self.comment_tree.collapsed(),
+            selected: self.selected_comment().map(|c| c.id),
+        };
+        Some((story.id, state))
+    }
+
+    /// Collapse and select comments the way the thread was left.
+    fn resume_comment_view(&mut self, state: &CommentViewState) {
+        self.comment_tree.collapse_each(&state.collapsed);
+        self.selected_index = 0;
+        if let Some(id) = state.selected {
+            self.focus_comment = self.focus_comment.or(Some(id));
+        }
+    }
+
+    /// Open every comment and go back to the top, forgetting where the
+    /// thread was left.
+    fn reset_comment_view(&mut self) {
+        let View::Comments { story } = &self.view else {
+            return;
+        };
+        let story_id = story.id;
+        self.comment_zoom = None;
+        self.comment_tree.expand_all();
+        self.selected_index = 0;
+        self.scroll_offset = 0;
+        self.flash("thread reset");
+        let storage = self.client.storage().clone();
+        tokio::spawn(async move {
+            let _ = storage.clear_comment_view_state(story_id).await;
+        });
+    }
+
+    /// Count the selected comment as read, saving reads in batches.
+    fn mark_selected_comment_read(&mut self) {
+        let selected = self.selected_comment().map(|c| c.id);
+        self.mark_comments_read(selected);
+    }
+
+    /// Count the comments drawn in the last frame as read.
+    pub fn mark_shown_comments_read(&mut self) {
+        let shown = self.comments_on_screen.take();
+        self.mark_comments_read(shown);
+    }
+
+    fn mark_comments_read(&mut self, ids: impl IntoIterator<Item = u64>) {
+        let View::Comments { story } = &self.view else {
+            return;
+        };
+        let story_id = story.id;
+        for id in ids {
+            if let Some((story_id, ids)) = self.comment_reads.mark(story_id, id) {
+                self.spawn_mark_comments_read(story_id, ids);
+            }
+        }
+    }
+
+    /// Select the next comment not read yet, starting over from the top
+    /// after the last one.
+    fn next_unread(&mut self) {
+        if !matches!(self.view, View::Comments { .. }) {
+            return;
+        }
+        // Collapsed replies count too, so go through the whole thread
+        let mut order: Vec<usize> = if self.flat_comments {
+            self.comment_tree.chronological_indices()
+        } else {
+            (0..self.comment_tree.len()).collect()
+        };
+        if let Some(root) = self.zoom_root() {
+            let subtree = self.comment_tree.subtree(root);
+            order.retain(|i| subtree.contains(i));
+        }
+        let start = self
+            .actual_comment_index(self.selected_index)
+            .and_then(|index| order.iter().position(|&i| i == index))
+            .map_or(0, |position| position + 1);
+        let comments = self.comment_tree.comments();
+        let next = order[start..]
+            .iter()
+            .chain(&order[..start])
+            .copied()
+            .find(|&i| !self.comment_reads.is_read(comments[i].id));
+        match next {
+            Some(index) => self.select_search_position(index),
+            None => self.flash("no unread comments"),
+        }
+    }
+
+    /// Leave out comments read by now, keeping those with unread replies
+    /// for context, or show them all again.
+    fn toggle_unread_only(&mut self) {
+        if !matches!(self.view, View::Comments { .. }) {
+            return;
+        }
+        let selected = self.actual_comment_index(self.selected_index);
+        self.unread_only = !self.unread_only;
+        if self.unread_only {
+            self.comment_reads.settle(self.comment_tree.comments());
+            if self.visible_comment_indices().is_empty() {
+                self.unread_only = false;
+                self.flash("no unread comments");
+                return;
+            }
+        }
+        self.selected_index = 0;
+        if let Some(index) = selected {
+            self.select_search_position(index);
+        }
+        self.flash(if self.unread_only {
+            "unread comments only"
+        } else {
+            "all comments"
+        });
+    }
+
+    /// Actual index of the comment the view is zoomed into.
+    pub fn zoom_root(&self) -> Option<usize> {
+        let id = self.comment_zoom?;
+        if !matches!(self.view, View::Comments { .. }) {
+            return None;
+        }
+        self.comment_tree.comments().iter().position(|c| c.id == id)
+    }
+
     fn open_theme_picker(&mut self) {
         let themes = all_themes();
         let current_name = &self.theme.name;
@@ -630,6 +1256,19 @@ impl App {
     }
 
     fn confirm_theme_picker(&mut self) {
+        self.save_theme_setting();
+        self.theme_picker = None;
+    }
+
+    fn set_theme(&mut self, name: &str) {
+        if let Some(theme) = by_name(name) {
+            self.theme = theme.into();
+            self.save_theme_setting();
+        }
+    }
+
+    /// Persist the current theme to settings.toml.
+    fn save_theme_setting(&mut self) {
         if let Some(config_dir) = &self.config_dir {
             let path = settings::settings_path(config_dir);
             match Settings::load(&path) {
@@ -653,7 +1292,6 @@ impl App {
                 }
             }
         }
-        self.theme_picker = None;
     }
 
     fn theme_picker_up(&mut self) {
@@ -675,7 +1313,49 @@ impl App {
     }
 
     pub fn visible_comment_indices(&self) -> Vec<usize> {
-        self.comment_tree.visible_indices()
+        self.listed_comment_indices(false)
+    }
+
+    /// Actual indices of the comments listed, in the order shown. With
+    /// `collapsed`, replies hidden under collapsed comments are included.
+    fn listed_comment_indices(&self, collapsed: bool) -> Vec<usize> {
+        let tree = self.shown_comment_tree();
+        let mut indices = if self.flat_comments {
+            tree.chronological_indices()
+        } else if collapsed {
+            (0..tree.len()).collect()
+        } else {
+            tree.visible_indices()
+        };
+        if let Some(root) = self.zoom_root() {
+            let subtree = tree.subtree(root);
+            indices.retain(|i| subtree.contains(i));
+        }
+        if self.unread_only {
+            // The tree keeps read comments that unread replies are nested under
+            let unread = |c: &Comment| !self.comment_reads.was_seen(c.id);
+            let keep = if self.flat_comments {
+                tree.comments().iter().map(unread).collect()
+            } else {
+                tree.leading_to(unread)
+            };
+            indices.retain(|&i| keep[i]);
+        }
+        indices
+    }
+
+    /// The comments on screen: the open thread's, or the preview's next to
+    /// the story list.
+    pub const fn shown_comment_tree(&self) -> &CommentTree {
+        match self.view {
+            View::Stories => &self.preview.comment_tree,
+            View::Comments { .. } | View::User { .. } => &self.comment_tree,
+        }
+    }
+
+    /// Comments shown as a tree, where replies can be expanded and collapsed.
+    const fn in_comment_tree(&self) -> bool {
+        matches!(self.view, View::Comments { .. }) && !self.flat_comments
     }
 
     fn actual_comment_index(&self, visible_index: usize) -> Option<usize> {
@@ -688,7 +1368,7 @@ impl App {
     }
 
     fn expand_comment(&mut self) {
-        if let View::Comments { .. } = self.view
+        if self.in_comment_tree()
             && let Some(comment) = self.selected_comment()
             && !comment.kids.is_empty()
         {
@@ -712,12 +1392,13 @@ impl App {
             let has_children = !comment.kids.is_empty();
             let is_expanded = self.comment_tree.is_expanded(id);
             // If expanded with children, collapse but stay on comment
-            if has_children && is_expanded {
+            if has_children && is_expanded && !self.flat_comments {
                 self.comment_tree.collapse(id);
                 return;
             }
-            // Otherwise navigate up: to parent if nested, back to stories if top-level
-            if depth == 0 {
+            // Otherwise navigate up: to parent if nested, back to stories if
+            // top-level, or out of the zoom from its root
+            if depth == 0 || self.comment_zoom == Some(id) {
                 self.go_back();
             } else {
                 let visible = self.visible_comment_indices();
@@ -743,8 +1424,77 @@ impl App {
         }
     }
 
-    fn expand_subtree(&mut self) {
+    fn next_toplevel(&mut self) {
         if let View::Comments { .. } = self.view {
+            let visible = self.visible_comment_indices();
+            let comments = self.comment_tree.comments();
+            if let Some(next) =
+                (self.selected_index + 1..visible.len()).find(|&i| comments[visible[i]].depth == 0)
+            {
+                self.selected_index = next;
+            }
+        }
+    }
+
+    /// Inside a thread, go to its top-level comment first, like `[[` in vim.
+    fn prev_toplevel(&mut self) {
+        if let View::Comments { .. } = self.view
+            && let Some(comment) = self.selected_comment()
+        {
+            let from = if comment.depth == 0 {
+                self.selected_index.checked_sub(1)
+            } else {
+                Some(self.selected_index)
+            };
+            let visible = self.visible_comment_indices();
+            if let Some((prev, _)) =
+                from.and_then(|i| self.comment_tree.find_toplevel_ancestor(&visible, i))
+            {
+                self.selected_index = prev;
+            }
+        }
+    }
+
+    fn next_by_op(&mut self) {
+        if let View::Comments { story } = &self.view {
+            let op = story.by.clone();
+            self.next_comment_by(&op);
+        }
+    }
+
+    fn next_by_same_author(&mut self) {
+        if let View::Comments { .. } = self.view
+            && let Some(comment) = self.selected_comment()
+        {
+            let author = comment.by.clone();
+            self.next_comment_by(&author);
+        }
+    }
+
+    /// Select the next comment by `author` in the order listed, opening
+    /// collapsed replies to show it.
+    fn next_comment_by(&mut self, author: &str) {
+        let Some(current) = self.actual_comment_index(self.selected_index) else {
+            return;
+        };
+        let listed = self.listed_comment_indices(true);
+        let after = listed
+            .iter()
+            .position(|&i| i == current)
+            .map_or(0, |n| n + 1);
+        let comments = self.comment_tree.comments();
+        let next = listed[after..]
+            .iter()
+            .copied()
+            .find(|&i| !author.is_empty() && comments[i].by == author);
+        match next {
+            Some(index) => self.select_search_position(index),
+            None => self.flash(&format!("no more comments by {author}")),
+        }
+    }
+
+    fn expand_subtree(&mut self) {
+        if self.in_comment_tree() {
             let Some(start_idx) = self.actual_comment_index(self.selected_index) else {
                 return;
             };
@@ -753,7 +1503,7 @@ impl App {
     }
 
     fn collapse_subtree(&mut self) {
-        if let View::Comments { .. } = self.view {
+        if self.in_comment_tree() {
             let visible = self.visible_comment_indices();
             let Some((ancestor_visible_idx, ancestor_actual_idx)) = self
                 .comment_tree
@@ -768,13 +1518,13 @@ impl App {
     }
 
     fn expand_thread(&mut self) {
-        if let View::Comments { .. } = self.view {
+        if self.in_comment_tree() {
             self.comment_tree.expand_all();
         }
     }
 
     fn collapse_thread(&mut self) {
-        if let View::Comments { .. } = self.view {
+        if self.in_comment_tree() {
             self.comment_tree.collapse_all();
         }
     }
@@ -782,7 +1532,13 @@ impl App {
     fn item_count(&self) -> usize {
         match self.view {
             View::Stories => self.stories.len(),
+            View::Comments { .. }
+                if self.flat_comments || self.comment_zoom.is_some() || self.unread_only =>
+            {
+                self.visible_comment_indices().len()
+            }
             View::Comments { .. } => self.comment_tree.visible_count(),
+            View::User { .. } => 0,
         }
     }
 
@@ -836,13 +1592,17 @@ impl App {
                     let _ = open::that(comment.hn_url());
                 }
             }
+            View::User { name } => {
+                let _ = open::that(user_url(name));
+            }
         }
     }
 
     fn open_story_url(&mut self) {
         let story = match &self.view {
             View::Stories => self.stories.get(self.selected_index),
-            View::Comments { story_index, .. } => self.stories.get(*story_index),
+            View::Comments { story } => Some(story),
+            View::User { .. } => None,
         };
         if let Some(story) = story {
             let id = story.id;
@@ -869,17 +1629,29 @@ impl App {
                 }
             }
             View::Comments { .. } => {
-                if let Some(comment) = self.selected_comment() {
-                    self.copy_to_clipboard(&comment.hn_url(), "link");
+                let Some(comment) = self.selected_comment() else {
+                    return;
+                };
+                let code = self
+                    .selected_code_block()
+                    .and_then(|(_, block)| code_blocks(&comment.text).into_iter().nth(block));
+                match code {
+                    Some(code) => self.copy_to_clipboard(&code, "code block"),
+                    None => self.copy_to_clipboard(&comment.hn_url(), "link"),
                 }
             }
+            View::User { name } => {
+                let url = user_url(name);
+                self.copy_to_clipboard(&url, "link");
+            }
         }
     }
 
     fn copy_story_url(&mut self) {
         let story = match &self.view {
             View::Stories => self.stories.get(self.selected_index),
-            View::Comments { story_index, .. } => self.stories.get(*story_index),
+            View::Comments { story } => Some(story),
+            View::User { .. } => None,
         };
         if let Some(story) = story {
             self.copy_to_clipboard(&story.content_url(), "url");
@@ -897,10 +1669,49 @@ impl App {
         self.flash_message = Some((message.to_string(), Instant::now()));
     }
 
+    /// The pending key sequence or count for the status bar, e.g. "5g".
+    pub fn pending_keys_text(&self) -> Option<String> {
+        self.pending_keys
+            .as_ref()
+            .map(|(pending, _)| pending.display())
+    }
+
     pub fn flash_text(&self) -> Option<&str> {
-        self.flash_message.as_ref().and_then(|(msg, time)| {
-            (time.elapsed() < std::time::Duration::from_secs(2)).then_some(msg.as_str())
-        })
+        self.flash_message
+            .as_ref()
+            .and_then(|(msg, time)| (time.elapsed() < FLASH_DURATION).then_some(msg.as_str()))
+    }
+
+    /// When the screen changes next without any input: the spinner turning,
+    /// a flash message running out, task timers in the debug pane ticking
+    /// and relative timestamps ageing.
+    pub fn redraw_deadline(&self, now: Instant) -> Instant {
+        let spinner = self
+            .load
+            .should_show_spinner()
+            .then(|| now + SPINNER_INTERVAL);
+        let flash = self
+            .flash_message
+            .as_ref()
+            .map(|(_, shown)| *shown + FLASH_DURATION)
+            .filter(|&end| end > now);
+        let debug = (self.debug.visible && !self.debug.running_tasks.is_empty())
+            .then(|| now + DEBUG_REDRAW);
+        [spinner, flash, debug]
+            .into_iter()
+            .flatten()
+            .fold(now + IDLE_REDRAW, Instant::min)
+    }
+
+    /// When a pending key sequence times out or the selected story's
+    /// preview is due, whichever comes first.
+    pub fn timer_deadline(&self) -> Option<Instant> {
+        let keys = self
+            .pending_keys
+            .as_ref()
+            .map(|(_, since)| *since + PENDING_TIMEOUT);
+        let preview = self.preview.pending.map(|(_, since)| since + PREVIEW_DELAY);
+        keys.into_iter().chain(preview).min()
     }
 
     fn mark_story_read(&mut self, id: u64) {
@@ -916,34 +1727,703 @@ impl App {
         if self.view == View::Stories
             && let Some(story) = self.stories.get(self.selected_index).cloned()
         {
-            let story_index = self.selected_index;
-            let story_scroll = self.scroll_offset;
-            self.mark_story_read(story.id);
-            self.view = View::Comments {
-                story_id: story.id,
-                story_title: story.title.clone(),
-                story_index,
-                story_scroll,
-            };
-            self.load.set_loading(true);
-            self.comment_tree.clear();
-            self.selected_index = 0;
-            self.scroll_offset = 0;
-            self.spawn_comments_fetch(story, false);
+            self.show_comments(story);
         }
     }
 
-    fn go_back(&mut self) {
-        if let View::Comments {
-            story_index,
-            story_scroll,
-            ..
-        } = self.view
-        {
-            self.view = View::Stories;
-            self.comment_tree.clear();
-            self.selected_index = story_index;
-            self.scroll_offset = story_scroll;
+    /// Switch to the comments view for `story`. Going back returns to the
+    /// current view as it was.
+    fn show_comments(&mut self, story: Story) {
+        self.spawn_save_comment_view();
+        self.spawn_save_comment_reads();
+        self.nav.push(self.nav_entry());
+        self.focus_comment = None;
+        self.comment_zoom = None;
+        self.mark_story_read(story.id);
+        self.view = View::Comments {
+            story: story.clone(),
+        };
+        self.search = Search::default();
+        self.hints = None;
+        self.selected_index = 0;
+        self.scroll_offset = 0;
+        // The split layout may already be previewing these comments
+        if self.preview.story_id == Some(story.id) && self.preview.error.is_none() {
+            // Otherwise the thread is open already and the tree is its own
+            if self.preview.loading || !self.preview.comment_tree.is_empty() {
+                self.comment_tree = std::mem::take(&mut self.preview.comment_tree);
+                self.comments_fetched_at = self.preview.fetched_at;
+            }
+            self.load.set_loading(self.preview.loading);
+            if let Some(id) = self.preview.resume.take() {
+                self.focus_comment = Some(id);
+                self.focus_linked_comment();
+            }
+            return;
+        }
+        self.preview = Preview {
+            story_id: Some(story.id),
+            ..Preview::default()
+        };
+        self.load.set_loading(true);
+        self.comment_tree.clear();
+        self.spawn_comments_fetch(story, false);
+    }
+
+    /// Switch to the comments for `story` with `comment` selected, as soon
+    /// as it's loaded.
+    fn show_thread(&mut self, story: Story, comment: Option<u64>) {
+        self.show_comments(story);
+        self.focus_comment = comment;
+        if !self.load.loading {
+            self.focus_linked_comment();
+        }
+    }
+
+    /// Select the comment a link pointed at, expanding its ancestors.
+    fn focus_linked_comment(&mut self) {
+        let Some(id) = self.focus_comment.take() else {
+            return;
+        };
+        if let Some(position) = self.comment_tree.comments().iter().position(|c| c.id == id) {
+            self.select_search_position(position);
+        }
+    }
+
+    /// Switch to the profile for the user `name`. Going back returns to the
+    /// current view.
+    fn show_user(&mut self, name: String) {
+        if matches!(&self.view, View::User { name: current } if *current == name) {
+            return;
+        }
+        self.spawn_save_comment_view();
+        self.spawn_save_comment_reads();
+        self.nav.push(self.nav_entry());
+        self.view = View::User { name: name.clone() };
+        self.search = Search::default();
+        self.hints = None;
+        self.selected_index = 0;
+        self.scroll_offset = 0;
+        self.load_user(name);
+    }
+
+    fn load_user(&mut self, name: String) {
+        if self.user.as_ref().is_some_and(|user| user.id == name) {
+            self.load.set_loading(false);
+            return;
+        }
+        self.user = None;
+        self.load.set_loading(true);
+        self.spawn_user_fetch(name);
+    }
+
+    fn handle_user_result(&mut self, r: UserResult) {
+        if !matches!(&self.view, View::User { name } if *name == r.name) {
+            self.debug.end_task(r.task_id, "discarded (wrong view)");
+            return;
+        }
+        self.debug.end_task(
+            r.task_id,
+            if r.result.is_ok() {
+                "completed"
+            } else {
+                "failed"
+            },
+        );
+        self.load.set_loading(false);
+        match r.result {
+            Ok(Some(user)) => self.user = Some(user),
+            Ok(None) => self.load.set_error(format!("no user named {}", r.name)),
+            Err(e) => {
+                self.load.set_error(e.user_message());
+                if e.is_fatal() {
+                    self.should_quit = true;
+                }
+            }
+        }
+    }
+
+    /// Follow the first link in the selected comment.
+    fn open_link(&mut self) {
+        let url = self
+            .selected_comment()
+            .and_then(|comment| links(&comment.text).into_iter().next());
+        match url {
+            Some(url) => self.follow_link(&url),
+            None if self.selected_comment().is_some() => self.flash("no links in this comment"),
+            None => {}
+        }
+    }
+
+    /// Pick the next code block in the selected comment, and after the last
+    /// go back to none.
+    fn cycle_code_block(&mut self) {
+        if !matches!(self.view, View::Comments { .. }) {
+            return;
+        }
+        let Some(comment) = self.selected_comment() else {
+            return;
+        };
+        let id = comment.id;
+        let count = code_blocks(&comment.text).len();
+        if count == 0 {
+            self.flash("no code blocks in this comment");
+            return;
+        }
+        let next = match self.selected_code_block() {
+            None => Some(0),
+            Some((_, block)) => Some(block + 1).filter(|&next| next < count),
+        };
+        self.code_block = next.map(|block| (id, block));
+    }
+
+    /// The code block picked in the selected comment, if the selection
+    /// hasn't moved on since.
+    pub fn selected_code_block(&self) -> Option<(u64, usize)> {
+        if !matches!(self.view, View::Comments { .. }) {
+            return None;
+        }
+        let selected = self.selected_comment()?.id;
+        self.code_block.filter(|&(id, _)| id == selected)
+    }
+
+    /// Label the links in the comments on screen to pick one.
+    fn show_hints(&mut self, action: HintAction) {
+        if !matches!(self.view, View::Comments { .. }) {
+            return;
+        }
+        let links = self.visible_links();
+        if links.is_empty() {
+            self.flash("no links on screen");
+            return;
+        }
+        self.hints = Some(Hints::new(action, links));
+    }
+
+    /// Links in the comments drawn in the last frame, as comment id and URL.
+    fn visible_links(&self) -> Vec<(u64, String)> {
+        let mut shown: Vec<usize> = self
+            .hit_areas
+            .borrow()
+            .targets()
+            .filter_map(|target| match target {
+                ClickTarget::Comment(index) => Some(index),
+                _ => None,
+            })
+            .collect();
+        shown.sort_unstable();
+        shown.dedup();
+        shown
+            .into_iter()
+            .filter_map(|index| self.actual_comment_index(index))
+            .filter_map(|i| self.shown_comment_tree().get(i))
+            .flat_map(|comment| {
+                links(&comment.text)
+                    .into_iter()
+                    .map(|url| (comment.id, url))
+            })
+            .collect()
+    }
+
+    fn edit_hints(&mut self, msg: &Message) {
+        let Some(hints) = &mut self.hints else {
+            return;
+        };
+        let result = match msg {
+            Message::HintInput(c) => hints.narrow(*c),
+            _ => match hints.typed.pop() {
+                Some(_) => HintMatch::Pending,
+                None => HintMatch::None,
+            },
+        };
+        let action = hints.action;
+        match result {
+            HintMatch::Pending => {}
+            HintMatch::Chosen(url) => {
+                self.hints = None;
+                match action {
+                    HintAction::Open => self.follow_link(&url),
+                    HintAction::Copy => self.copy_to_clipboard(&url, "link"),
+                    HintAction::Preview => self.flash(&url),
+                }
+            }
+            HintMatch::None => self.hints = None,
+        }
+    }
+
+    /// Open a link from a comment: threads, comments and profiles on HN are
+    /// shown in the app, anything else in the browser.
+    fn follow_link(&mut self, url: &str) {
+        match HnLink::parse(url) {
+            Some(HnLink::Item(id)) => self.open_item(id),
+            Some(HnLink::User(name)) => self.show_user(name),
+            None => {
+                let _ = open::that(url);
+            }
+        }
+    }
+
+    /// Whether the terminal is wide enough to show the story list and the
+    /// comments side by side.
+    pub fn is_split(&self) -> bool {
+        self.split_min_width > 0
+            && self
+                .viewport_width
+                .is_some_and(|width| width >= self.split_min_width)
+    }
+
+    /// Index of the story in the list: the selected one, or where the list
+    /// was left to open comments.
+    pub fn story_index(&self) -> usize {
+        match self.view {
+            View::Stories => self.selected_index,
+            View::Comments { .. } | View::User { .. } => {
+                self.nav.root().map_or(0, |entry| entry.selected_index)
+            }
+        }
+    }
+
+    /// Title of the story whose comments are shown, whether open or previewed.
+    pub fn comments_title(&self) -> Option<&str> {
+        self.comments_story().map(|s| s.title.as_str())
+    }
+
+    /// The story whose comments are shown, open or previewed.
+    pub fn comments_story(&self) -> Option<&Story> {
+        match &self.view {
+            View::Comments { story } => Some(story),
+            View::User { .. } => None,
+            View::Stories => {
+                let id = self.preview.story_id?;
+                self.stories.iter().find(|s| s.id == id)
+            }
+        }
+    }
+
+    /// Move focus between the story list and the comments in the split layout.
+    fn switch_pane(&mut self) {
+        if !self.is_split() {
+            return;
+        }
+        match self.view {
+            View::Stories => self.open_comments(),
+            View::Comments { .. } | View::User { .. } => self.go_to_stories(),
+        }
+    }
+
+    /// Preview the selected story's comments in the split layout once the
+    /// selection has stayed put for a moment. Called after every event and
+    /// when [`App::timer_deadline`] passes.
+    pub fn update_preview(&mut self, now: Instant) {
+        if !self.is_split() || self.view != View::Stories {
+            return;
+        }
+        let Some(id) = self.stories.get(self.selected_index).map(|s| s.id) else {
+            return;
+        };
+        if self.preview.story_id == Some(id) {
+            self.preview.pending = None;
+            return;
+        }
+        match self.preview.pending {
+            Some((pending, since)) if pending == id => {
+                if now.duration_since(since) >= PREVIEW_DELAY {
+                    let story = self.stories[self.selected_index].clone();
+                    self.load_preview(story);
+                    self.dirty = true;
+                }
+            }
+            _ => self.preview.pending = Some((id, now)),
+        }
+    }
+
+    fn load_preview(&mut self, story: Story) {
+        self.preview = Preview {
+            story_id: Some(story.id),
+            loading: true,
+            ..Preview::default()
+        };
+        self.spawn_comments_fetch(story, false);
+    }
+
+    /// Open the comments for an item id, fetching the story if it isn't in
+    /// the current list. From the list, the story is selected first. A
+    /// comment opens its thread with the comment selected.
+    fn open_item(&mut self, id: u64) {
+        if let View::Comments { story } = &self.view
+            && self.comment_tree.comments().iter().any(|c| c.id == id)
+        {
+            let story = story.clone();
+            self.show_thread(story, Some(id));
+            return;
+        }
+        let Some(index) = self.stories.iter().position(|s| s.id == id) else {
+            self.spawn_item_fetch(id);
+            return;
+        };
+        if self.view == View::Stories {
+            self.selected_index = index;
+        }
+        self.show_comments(self.stories[index].clone());
+    }
+
+    fn handle_item_result(&mut self, r: ItemResult) {
+        // A slow lookup doesn't pull the user away from where they went since
+        let requested_from = self
+            .pending_item
+            .take_if(|lookup| lookup.id == r.id && lookup.task_id == r.task_id)
+            .map(|lookup| lookup.from);
+        if requested_from.as_ref() != Some(&self.view) {
+            self.debug.end_task(r.task_id, "discarded (wrong view)");
+            return;
+        }
+        self.debug.end_task(
+            r.task_id,
+            if r.result.is_ok() {
+                "completed"
+            } else {
+                "failed"
+            },
+        );
+        match r.result {
+            Ok(Some((story, comment))) => self.show_thread(story, comment),
+            Ok(None) => self.flash(&format!("no item with id {}", r.id)),
+            Err(e) => self.flash(&format!("couldn't load {}: {e}", r.id)),
+        }
+    }
+
+    fn edit_command_line(&mut self, msg: &Message) {
+        let Some(line) = &mut self.command_line else {
+            return;
+        };
+        match msg {
+            Message::CommandLineInput(c) => line.insert(*c),
+            #[allow(clippy::collapsible_match)] // keep the side effect out of a match guard
+            Message::CommandLineBackspace => {
+                if !line.backspace() {
+                    self.command_line = None;
+                }
+            }
+            Message::CommandLineComplete => line.complete(&self.feeds),
+            Message::CommandLineHistoryPrev => line.history_prev(&self.command_history),
+            Message::CommandLineHistoryNext => line.history_next(&self.command_history),
+            _ => {}
+        }
+    }
+
+    fn submit_command_line(&mut self) {
+        let Some(line) = self.command_line.take() else {
+            return;
+        };
+        let input = line.input.trim().to_string();
+        if input.is_empty() {
+            return;
+        }
+        if self.command_history.last() != Some(&input) {
+            self.command_history.push(input.clone());
+            let excess = self
+                .command_history
+                .len()
+                .saturating_sub(command::HISTORY_LIMIT);
+            self.command_history.drain(..excess);
+            self.spawn_save_command_history();
+        }
+        match command::parse(&input, &self.feeds) {
+            Ok(msg) => self.update(msg),
+            Err(e) => self.flash(&e),
+        }
+    }
+
+    /// Positions of the items matching the search pattern: story indices, or
+    /// indices into the full comment list, collapsed comments included.
+    /// They're only looked for again once the pattern or the items change.
+    pub fn search_matches(&self) -> Ref<'_, [usize]> {
+        let searched = match self.view {
+            View::Stories => Searched::Stories(self.stories_revision),
+            View::Comments { .. } => Searched::Comments(self.comment_tree.revision()),
+            View::User { .. } => Searched::Nothing,
+        };
+        self.search.matches(searched, |pattern| match searched {
+            Searched::Stories(_) => self
+                .stories
+                .iter()
+                .enumerate()
+                .filter(|(_, story)| search::matches(&story.title, pattern))
+                .map(|(i, _)| i)
+                .collect(),
+            Searched::Comments(_) => self
+                .comment_tree
+                .comments()
+                .iter()
+                .enumerate()
+                .filter(|(_, comment)| {
+                    search::matches(&comment.by, pattern)
+                        || search::matches(&strip_html(&comment.text), pattern)
+                })
+                .map(|(i, _)| i)
+                .collect(),
+            Searched::Nothing => Vec::new(),
+        })
+    }
+
+    /// Match count for the status bar, e.g. "match 3/17".
+    pub fn search_status_text(&self) -> Option<String> {
+        if self.search.pattern().is_empty() {
+            return None;
+        }
+        Some(search::status_text(
+            &self.search_matches(),
+            self.search_position(),
+        ))
+    }
+
+    /// The selected item as a search position.
+    fn search_position(&self) -> Option<usize> {
+        match self.view {
+            View::Stories => {
+                (self.selected_index < self.stories.len()).then_some(self.selected_index)
+            }
+            View::Comments { .. } => self.actual_comment_index(self.selected_index),
+            View::User { .. } => None,
+        }
+    }
+
+    /// Select the item at a search position, expanding collapsed ancestors
+    /// of a comment so it can be shown.
+    fn select_search_position(&mut self, position: usize) {
+        match self.view {
+            View::Stories => self.selected_index = position,
+            View::Comments { .. } => {
+                self.comment_tree.reveal(position);
+                if let Some(index) = self
+                    .visible_comment_indices()
+                    .iter()
+                    .position(|&i| i == position)
+                {
+                    self.selected_index = index;
+                }
+            }
+            View::User { .. } => {}
+        }
+    }
+
+    fn open_search(&mut self, direction: SearchDirection) {
+        self.search.prompt = Some(SearchPrompt {
+            input: String::new(),
+            direction,
+            origin: self.search_position(),
+        });
+    }
+
+    /// Edit the search prompt and jump to the first match as the query is
+    /// typed, going back to where the search started if nothing matches.
+    fn edit_search(&mut self, msg: &Message) {
+        let Some(prompt) = &mut self.search.prompt else {
+            return;
+        };
+        match msg {
+            Message::SearchInput(c) => prompt.input.push(*c),
+            #[allow(clippy::collapsible_match)] // keep the side effect out of a match guard
+            Message::SearchBackspace => {
+                if prompt.input.pop().is_none() {
+                    self.cancel_search();
+                    return;
+                }
+            }
+            _ => {}
+        }
+        let (direction, origin) = (prompt.direction, prompt.origin);
+        let target =
+            search::next_match(&self.search_matches(), origin.unwrap_or(0), direction, true)
+                .map(|(position, _)| position)
+                .or(origin);
+        if let Some(position) = target {
+            self.select_search_position(position);
+        }
+    }
+
+    fn submit_search(&mut self) {
+        let Some(prompt) = self.search.prompt.take() else {
+            return;
+        };
+        self.search.direction = prompt.direction;
+        if prompt.input.is_empty() {
+            // An empty query repeats the last search in the new direction
+            self.search_next(prompt.direction);
+            return;
+        }
+        self.search.query = prompt.input;
+        if self.search_matches().is_empty() {
+            self.flash(&format!("no matches for '{}'", self.search.query));
+        }
+    }
+
+    fn cancel_search(&mut self) {
+        if let Some(prompt) = self.search.prompt.take()
+            && let Some(origin) = prompt.origin
+        {
+            self.select_search_position(origin);
+        }
+    }
+
+    fn search_next(&mut self, direction: SearchDirection) {
+        if self.search.query.is_empty() {
+            self.flash("no previous search");
+            return;
+        }
+        let from = self.search_position().unwrap_or(0);
+        let found = search::next_match(&self.search_matches(), from, direction, false);
+        match found {
+            Some((position, wrapped)) => {
+                self.select_search_position(position);
+                if wrapped {
+                    self.flash("search wrapped");
+                }
+            }
+            None => self.flash(&format!("no matches for '{}'", self.search.query)),
+        }
+    }
+
+    /// Select what was clicked. Double-clicking a story opens its comments,
+    /// and a comment its HN page.
+    fn click(&mut self, target: ClickTarget) {
+        let now = Instant::now();
+        let double = self
+            .last_click
+            .is_some_and(|(last, at)| last == target && now.duration_since(at) < DOUBLE_CLICK);
+        // A third click starts over rather than counting as another double
+        self.last_click = (!double).then_some((target, now));
+
+        // In the split layout, clicking the other pane focuses it first
+        match target {
+            ClickTarget::Story(_) if self.view != View::Stories => self.go_to_stories(),
+            ClickTarget::Comment(_) | ClickTarget::CommentToggle(_)
+                if self.view == View::Stories =>
+            {
+                self.open_comments();
+            }
+            _ => {}
+        }
+
+        match target {
+            ClickTarget::Tab(feed) => self.switch_feed(feed),
+            ClickTarget::Story(index) if index < self.stories.len() => {
+                self.selected_index = index;
+                if double {
+                    self.open_comments();
+                }
+            }
+            ClickTarget::Comment(index) if index < self.visible_comment_indices().len() => {
+                self.selected_index = index;
+                if double {
+                    self.open_url();
+                }
+            }
+            ClickTarget::CommentToggle(index) => {
+                let Some(id) = self
+                    .actual_comment_index(index)
+                    .and_then(|i| self.comment_tree.get(i))
+                    .map(|c| c.id)
+                else {
+                    return;
+                };
+                self.selected_index = index;
+                if !self.comment_tree.collapse(id) {
+                    self.comment_tree.expand(id);
+                }
+            }
+            ClickTarget::Story(_) | ClickTarget::Comment(_) => {}
+        }
+    }
+
+    /// Snapshot of the current view for the navigation history.
+    fn nav_entry(&self) -> NavEntry {
+        let in_comments = matches!(self.view, View::Comments { .. });
+        NavEntry {
+            view: self.view.clone(),
+            selected_index: self.selected_index,
+            scroll_offset: self.scroll_offset,
+            comment_tree: if in_comments {
+                self.comment_tree.clone()
+            } else {
+                CommentTree::new()
+            },
+            comments_fetched_at: self.comments_fetched_at.filter(|_| in_comments),
+            loading: in_comments && self.load.loading,
+            comment_zoom: self.comment_zoom.filter(|_| in_comments),
+        }
+    }
+
+    fn go_back(&mut self) {
+        if self.unzoom() {
+            return;
+        }
+        self.spawn_save_comment_view();
+        self.spawn_save_comment_reads();
+        let current = self.nav_entry();
+        if let Some(entry) = self.nav.back(current) {
+            self.restore(entry);
+        }
+    }
+
+    fn go_forward(&mut self) {
+        self.spawn_save_comment_view();
+        self.spawn_save_comment_reads();
+        let current = self.nav_entry();
+        if let Some(entry) = self.nav.forward(current) {
+            self.restore(entry);
+        }
+    }
+
+    /// Go back as far as the story list.
+    fn go_to_stories(&mut self) {
+        while self.view != View::Stories && self.nav.root().is_some() {
+            self.go_back();
+        }
+    }
+
+    /// Put a view from the history back the way it was left.
+    fn restore(&mut self, entry: NavEntry) {
+        let left = std::mem::replace(&mut self.view, entry.view);
+        self.selected_index = entry.selected_index;
+        self.scroll_offset = entry.scroll_offset;
+        self.search = Search::default();
+        self.hints = None;
+        self.focus_comment = None;
+        self.comment_zoom = entry.comment_zoom;
+        match &self.view {
+            View::Comments { story } => {
+                self.preview = Preview {
+                    story_id: Some(story.id),
+                    ..Preview::default()
+                };
+                self.comment_tree = entry.comment_tree;
+                self.comment_reads.settle(self.comment_tree.comments());
+                self.comments_fetched_at = entry.comments_fetched_at;
+                self.load.set_loading(entry.loading);
+            }
+            View::User { name } => {
+                let name = name.clone();
+                self.load_user(name);
+            }
+            View::Stories if self.is_split() => {
+                // The comments stay on as the preview
+                if let View::Comments { story } = left {
+                    self.preview = Preview {
+                        story_id: Some(story.id),
+                        loading: self.load.loading,
+                        comment_tree: std::mem::take(&mut self.comment_tree),
+                        fetched_at: self.comments_fetched_at,
+                        ..Preview::default()
+                    };
+                }
+                self.load.set_loading(false);
+            }
+            View::Stories => {
+                self.comment_tree.clear();
+                self.preview = Preview::default();
+                self.load.set_loading(false);
+            }
         }
     }
 
@@ -961,12 +2441,16 @@ impl App {
                 self.load.has_more = true;
                 self.spawn_stories_fetch(0, true, false);
             }
-            View::Comments { story_id, .. } => {
-                if let Some(story) = self.stories.iter().find(|s| s.id == *story_id).cloned() {
-                    self.comments_fetched_at = None;
-                    self.load.set_loading(true);
-                    self.spawn_comments_fetch(story, true);
-                }
+            View::Comments { story } => {
+                let story = story.clone();
+                self.comments_fetched_at = None;
+                self.load.set_loading(true);
+                self.spawn_comments_fetch(story, true);
+            }
+            View::User { name } => {
+                let name = name.clone();
+                self.user = None;
+                self.load_user(name);
             }
         }
     }
@@ -975,16 +2459,36 @@ impl App {
         if self.feed != feed {
             self.feed = feed;
             self.view = View::Stories;
+            self.nav.clear();
             self.load_stories();
+            self.spawn_save_last_feed();
+        }
+    }
+
+    /// Applies the tab bar layout and picks the feed to start on: the
+    /// configured startup feed, else `last_feed`, else the first visible tab.
+    pub fn arrange_tabs(&mut self, tabs: &TabSettings, last_feed: Option<&str>) {
+        self.feeds = tabs.arrange(&self.feeds);
+        let startup = tabs.startup.as_deref().or(last_feed);
+        if let Some(feed) =
+            startup.and_then(|name| self.feeds.iter().find(|f| f.matches_name(name)))
+        {
+            self.feed = *feed;
+        } else if !self.feeds.contains(&self.feed)
+            && let Some(&first) = self.feeds.first()
+        {
+            self.feed = first;
         }
     }
 
-    #[allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap)] // feeds.len() is small (7)
+    #[allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap)] // feeds.len() is small
     fn cycle_feed(&mut self, direction: i32) {
-        let feeds = Feed::all();
-        let current_idx = feeds.iter().position(|&f| f == self.feed).unwrap_or(0);
-        let new_idx = (current_idx as i32 + direction).rem_euclid(feeds.len() as i32) as usize;
-        self.switch_feed(feeds[new_idx]);
+        if self.feeds.is_empty() {
+            return;
+        }
+        let current_idx = self.feeds.iter().position(|&f| f == self.feed).unwrap_or(0);
+        let new_idx = (current_idx as i32 + direction).rem_euclid(self.feeds.len() as i32) as usize;
+        self.switch_feed(self.feeds[new_idx]);
     }
 
     pub fn load_stories(&mut self) {
@@ -992,6 +2496,7 @@ impl App {
         self.load.set_loading(true);
         self.load.clear_error();
         self.stories.clear();
+        self.stories_revision += 1;
         self.stories_fetched_at = None;
         self.load.current_page = 0;
         self.load.has_more = self.feed != Feed::Favorites; // Favorites don't paginate
@@ -1152,12 +2657,33 @@ impl App {
                 Ok(fetched) => (Ok(fetched.comments), Some(fetched.fetched_at)),
                 Err(e) => (Err(e), None),
             };
+            let stored_sort = client
+                .storage()
+                .get_comment_sort(story_id)
+                .await
+                .ok()
+                .flatten()
+                .and_then(|label| CommentSort::from_label(&label));
+            let stored_view = client
+                .storage()
+                .get_comment_view_state(story_id)
+                .await
+                .ok()
+                .flatten();
+            let read_comments = client
+                .storage()
+                .get_read_comments(story_id)
+                .await
+                .unwrap_or_default();
             let _ = tx
                 .send(AsyncResult::Comments(CommentsResult {
                     story_id,
                     task_id,
                     result,
                     fetched_at,
+                    stored_sort,
+                    stored_view,
+                    read_comments,
                 }))
                 .await;
         });
@@ -1170,6 +2696,59 @@ impl App {
         });
     }
 
+    fn spawn_item_fetch(&mut self, id: u64) {
+        let client = self.client.clone();
+        let tx = self.result_tx.clone();
+        let task_id = self.debug.start_task(format!("Load item {id}"));
+        self.pending_item = Some(ItemLookup {
+            id,
+            task_id,
+            from: self.view.clone(),
+        });
+        tokio::spawn(async move {
+            let result = client.fetch_thread(id).await;
+            let _ = tx
+                .send(AsyncResult::Item(ItemResult {
+                    id,
+                    task_id,
+                    result,
+                }))
+                .await;
+        });
+    }
+
+    fn spawn_user_fetch(&mut self, name: String) {
+        let client = self.client.clone();
+        let tx = self.result_tx.clone();
+        let task_id = self.debug.start_task(format!("Load user {name}"));
+        tokio::spawn(async move {
+            let result = client.fetch_user(&name).await;
+            let _ = tx
+                .send(AsyncResult::User(UserResult {
+                    name,
+                    task_id,
+                    result,
+                }))
+                .await;
+        });
+    }
+
+    fn spawn_save_command_history(&self) {
+        let storage = self.client.storage().clone();
+        let history = self.command_history.join("\n");
+        tokio::spawn(async move {
+            let _ = storage.set_state(COMMAND_HISTORY_KEY, history).await;
+        });
+    }
+
+    fn spawn_save_last_feed(&self) {
+        let storage = self.client.storage().clone();
+        let key = self.feed.key().into_owned();
+        tokio::spawn(async move {
+            let _ = storage.set_state(LAST_FEED_KEY, key).await;
+        });
+    }
+
     fn spawn_sorted_stories_fetch(&self) {
         let storage = self.client.storage().clone();
         let tx = self.result_tx.clone();
@@ -1205,6 +2784,48 @@ impl App {
         });
     }
 
+    fn spawn_mark_comments_read(&self, story_id: u64, ids: Vec<u64>) {
+        let storage = self.client.storage().clone();
+        tokio::spawn(async move {
+            let _ = storage.mark_comments_read(story_id, ids).await;
+        });
+    }
+
+    /// Save comments read and not saved yet, before the thread is left.
+    fn spawn_save_comment_reads(&mut self) {
+        if let Some((story_id, ids)) = self.comment_reads.take_unsaved() {
+            self.spawn_mark_comments_read(story_id, ids);
+        }
+    }
+
+    /// Remember where the open thread is left, for coming back to it.
+    fn spawn_save_comment_view(&self) {
+        let Some((story_id, state)) = self.comment_view_state() else {
+            return;
+        };
+        let storage = self.client.storage().clone();
+        // A thread left the way it opens has nothing worth keeping
+        let at_top = state.selected == self.comment_tree.comments().first().map(|c| c.id);
+        if state.collapsed.is_empty() && at_top {
+            tokio::spawn(async move {
+                let _ = storage.clear_comment_view_state(story_id).await;
+            });
+            return;
+        }
+        tokio::spawn(async move {
+            let _ = storage.save_comment_view_state(story_id, state).await;
+        });
+    }
+
+    fn spawn_save_comment_sort(&self, story_id: u64, sort: CommentSort) {
+        let storage = self.client.storage().clone();
+        tokio::spawn(async move {
+            let _ = storage
+                .save_comment_sort(story_id, sort.label().to_string())
+                .await;
+        });
+    }
+
     fn spawn_save_feed_sort(&self) {
         let storage = self.client.storage().clone();
         let feed = self.feed;
@@ -1228,12 +2849,13 @@ impl App {
                     self.spawn_toggle_comment_favorite(id);
                 }
             }
+            View::User { .. } => {}
         }
     }
 
     fn toggle_story_favorite(&mut self) {
-        if let View::Comments { story_id, .. } = &self.view {
-            self.spawn_toggle_story_favorite(*story_id);
+        if let View::Comments { story } = &self.view {
+            self.spawn_toggle_story_favorite(story.id);
         }
     }
 
@@ -1255,50 +2877,448 @@ impl App {
         });
     }
 
-    fn spawn_toggle_comment_favorite(&mut self, id: u64) {
-        // Update local state
-        if let Some(comment) = self.comment_tree.get_mut(id) {
-            if comment.favorited_at.is_some() {
-                comment.favorited_at = None;
-                self.flash("unfavorited");
-            } else {
-                comment.favorited_at = Some(now_unix());
-                self.flash("favorited \u{2728}");
-            }
-        }
-        // Persist to DB
-        let storage = self.client.storage().clone();
-        tokio::spawn(async move {
-            let _ = storage.toggle_comment_favorite(id).await;
-        });
-    }
-}
+    fn spawn_toggle_comment_favorite(&mut self, id: u64) {
+        // Update local state
+        if let Some(comment) = self.comment_tree.get_mut(id) {
+            if comment.favorited_at.is_some() {
+                comment.favorited_at = None;
+                self.flash("unfavorited");
+            } else {
+                comment.favorited_at = Some(now_unix());
+                self.flash("favorited \u{2728}");
+            }
+        }
+        // Persist to DB
+        let storage = self.client.storage().clone();
+        tokio::spawn(async move {
+            let _ = storage.toggle_comment_favorite(id).await;
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::{Storage, StorageLocation};
+    use crate::test_utils::{
+        CommentBuilder, StoryBuilder, TestAppBuilder, comments_view, custom_feed, sample_comments,
+        sample_stories,
+    };
+    use crate::theme::{ThemeVariant, default_for_variant};
+    use crate::views::tests::render_to_string;
+
+    fn test_storage() -> Storage {
+        Storage::open(StorageLocation::InMemory).unwrap()
+    }
+
+    fn test_app() -> App {
+        App::new(
+            default_for_variant(ThemeVariant::Dark),
+            None,
+            test_storage(),
+        )
+    }
+
+    #[test]
+    fn test_new_app() {
+        let app = test_app();
+        assert_eq!(app.view, View::Stories);
+        assert_eq!(app.feed, Feed::Top);
+        assert!(!app.should_quit);
+    }
+
+    #[tokio::test]
+    async fn cycle_feed_includes_custom_feeds() {
+        let mut app = test_app();
+        let custom = Feed::custom(vec![custom_feed("Rust", "rust")]);
+        app.feeds.extend(custom.iter().copied());
+        app.feed = Feed::Jobs;
+        app.update(Message::NextFeed);
+        assert_eq!(app.feed, custom[0]);
+        app.update(Message::NextFeed);
+        assert_eq!(app.feed, Feed::Favorites);
+    }
+
+    #[tokio::test]
+    async fn counted_feed_switch_loads_once() {
+        let mut app = test_app();
+        let start = app.feeds.iter().position(|&f| f == app.feed).unwrap();
+        let generation = app.generation;
+        app.update(Message::Repeat(3, Box::new(Message::NextFeed)));
+        assert_eq!(app.feed, app.feeds[(start + 3) % app.feeds.len()]);
+        assert_eq!(app.generation, generation + 1);
+
+        app.update(Message::Repeat(3, Box::new(Message::PrevFeed)));
+        assert_eq!(app.feed, app.feeds[start]);
+        assert_eq!(app.generation, generation + 2);
+    }
+
+    #[tokio::test]
+    async fn arrange_tabs_picks_startup_feed() {
+        let tabs = TabSettings {
+            order: vec!["best".to_string()],
+            hidden: vec!["top".to_string()],
+            startup: None,
+        };
+
+        // Hidden default feed falls back to the first visible tab
+        let mut app = test_app();
+        app.arrange_tabs(&tabs, None);
+        assert_eq!(app.feeds[0], Feed::Best);
+        assert!(!app.feeds.contains(&Feed::Top));
+        assert_eq!(app.feed, Feed::Best);
+
+        // Last active feed is restored
+        let mut app = test_app();
+        app.arrange_tabs(&tabs, Some("ask"));
+        assert_eq!(app.feed, Feed::Ask);
+
+        // Configured startup feed wins over the last one
+        let mut app = test_app();
+        let tabs = TabSettings {
+            startup: Some("Show".to_string()),
+            ..tabs
+        };
+        app.arrange_tabs(&tabs, Some("ask"));
+        assert_eq!(app.feed, Feed::Show);
+    }
+
+    #[tokio::test]
+    async fn switch_feed_remembers_last_feed() {
+        let mut app = test_app();
+        app.update(Message::SwitchFeed(Feed::Ask));
+
+        let storage = app.client.storage().clone();
+        let mut saved = None;
+        for _ in 0..50 {
+            saved = storage.get_state(LAST_FEED_KEY).await.unwrap();
+            if saved.is_some() {
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+        }
+        assert_eq!(saved.as_deref(), Some("ask"));
+    }
+
+    fn run_command(app: &mut App, input: &str) {
+        app.update(Message::OpenCommandLine);
+        for c in input.chars() {
+            app.update(Message::CommandLineInput(c));
+        }
+        app.update(Message::SubmitCommandLine);
+    }
+
+    #[tokio::test]
+    async fn command_line_runs_commands_and_records_history() {
+        let mut app = TestAppBuilder::new().with_stories(sample_stories()).build();
+
+        run_command(&mut app, "sort score");
+        assert_eq!(app.story_sort, StorySort::ScoreDesc);
+        assert!(app.command_line.is_none());
+
+        run_command(&mut app, "bogus");
+        assert_eq!(app.flash_text(), Some("Unknown command 'bogus'"));
+
+        run_command(&mut app, "bogus");
+        assert_eq!(app.command_history, vec!["sort score", "bogus"]);
+
+        app.update(Message::OpenCommandLine);
+        app.update(Message::CommandLineHistoryPrev);
+        app.update(Message::CommandLineHistoryPrev);
+        assert_eq!(
+            app.command_line.as_ref().map(|l| l.input.as_str()),
+            Some("sort score")
+        );
+    }
+
+    #[tokio::test]
+    async fn open_item_selects_story_in_list() {
+        let mut app = TestAppBuilder::new().with_stories(sample_stories()).build();
+        let id = app.stories[2].id;
+
+        run_command(&mut app, &format!("open {id}"));
+
+        assert!(matches!(&app.view, View::Comments { story } if story.id == id));
+        assert_eq!(app.story_index(), 2);
+    }
+
+    #[test]
+    fn backspace_on_empty_command_line_closes_it() {
+        let mut app = test_app();
+        app.update(Message::OpenCommandLine);
+        app.update(Message::CommandLineInput('q'));
+        app.update(Message::CommandLineBackspace);
+        assert!(app.command_line.is_some());
+        app.update(Message::CommandLineBackspace);
+        assert!(app.command_line.is_none());
+    }
+
+    fn type_search(app: &mut App, direction: SearchDirection, query: &str) {
+        app.update(Message::OpenSearch(direction));
+        for c in query.chars() {
+            app.update(Message::SearchInput(c));
+        }
+    }
+
+    #[test]
+    fn search_jumps_through_story_titles() {
+        let mut app = TestAppBuilder::new()
+            .with_stories(sample_stories())
+            .selected(1)
+            .build();
+
+        type_search(&mut app, SearchDirection::Forward, "HN");
+        assert_eq!(app.selected_index, 2);
+        assert_eq!(app.search_status_text().as_deref(), Some("match 2/2"));
+        app.update(Message::SubmitSearch);
+        assert!(app.search.prompt.is_none());
+
+        app.update(Message::SearchNext);
+        assert_eq!(app.selected_index, 0);
+        assert_eq!(app.flash_text(), Some("search wrapped"));
+        app.update(Message::SearchPrev);
+        assert_eq!(app.selected_index, 2);
+    }
+
+    #[test]
+    fn cancelled_search_restores_selection() {
+        let mut app = TestAppBuilder::new()
+            .with_stories(sample_stories())
+            .selected(1)
+            .build();
+
+        type_search(&mut app, SearchDirection::Backward, "ask");
+        assert_eq!(app.selected_index, 2);
+        app.update(Message::CancelSearch);
+        assert_eq!(app.selected_index, 1);
+        assert_eq!(app.search_status_text(), None);
+
+        app.update(Message::SearchNext);
+        assert_eq!(app.flash_text(), Some("no previous search"));
+    }
+
+    #[test]
+    fn search_reveals_collapsed_comments() {
+        let mut app = TestAppBuilder::new()
+            .view(comments_view(1, "Test"))
+            .with_comments(crate::test_utils::sample_comments())
+            .all_collapsed()
+            .build();
+
+        type_search(&mut app, SearchDirection::Forward, "windows");
+        app.update(Message::SubmitSearch);
+        assert_eq!(app.selected_comment().map(|c| c.id), Some(103));
+        assert!(app.comment_tree.is_expanded(100));
+        assert!(!app.comment_tree.is_expanded(101));
+
+        // Authors match too
+        type_search(&mut app, SearchDirection::Forward, "SEARCHER");
+        assert_eq!(app.selected_comment().map(|c| c.id), Some(104));
+        assert_eq!(app.search_status_text().as_deref(), Some("match 1/1"));
+    }
+
+    #[tokio::test]
+    async fn double_click_opens_story() {
+        let mut app = TestAppBuilder::new().with_stories(sample_stories()).build();
+
+        app.update(Message::Click(ClickTarget::Story(2)));
+        assert_eq!(app.selected_index, 2);
+        assert_eq!(app.view, View::Stories);
+        app.update(Message::Click(ClickTarget::Story(2)));
+        assert!(matches!(app.view, View::Comments { .. }));
+        assert_eq!(app.story_index(), 2);
+    }
+
+    #[test]
+    fn clicking_a_marker_toggles_the_comment() {
+        let mut app = TestAppBuilder::new()
+            .view(comments_view(1, "Test"))
+            .with_comments(crate::test_utils::sample_comments())
+            .build();
+
+        app.update(Message::Click(ClickTarget::CommentToggle(1)));
+        assert_eq!(app.selected_index, 1);
+        assert!(!app.comment_tree.is_expanded(101));
+        // Quick second click on the marker toggles again rather than opening
+        app.update(Message::Click(ClickTarget::CommentToggle(1)));
+        assert!(app.comment_tree.is_expanded(101));
+
+        // Clicks past the end are ignored
+        app.update(Message::Click(ClickTarget::Comment(99)));
+        assert_eq!(app.selected_index, 1);
+    }
+
+    #[tokio::test]
+    async fn preview_waits_for_the_selection_to_settle() {
+        let mut app = TestAppBuilder::new()
+            .with_stories(sample_stories())
+            .viewport_width(200)
+            .build();
+        let start = Instant::now();
+
+        app.update_preview(start);
+        app.update_preview(start + Duration::from_millis(100));
+        assert_eq!(app.preview.story_id, None);
+        // Moving on restarts the wait
+        app.update(Message::SelectNext);
+        app.update_preview(start + Duration::from_millis(300));
+        assert_eq!(app.preview.story_id, None);
+        app.update_preview(start + Duration::from_millis(600));
+        assert_eq!(app.preview.story_id, Some(app.stories[1].id));
+        assert!(app.preview.loading);
+    }
+
+    #[tokio::test]
+    async fn preview_timer_wakes_the_loop() {
+        let mut app = TestAppBuilder::new()
+            .with_stories(sample_stories())
+            .viewport_width(200)
+            .build();
+        let start = Instant::now();
+        assert_eq!(app.timer_deadline(), None);
+
+        app.update_preview(start);
+        assert_eq!(app.timer_deadline(), Some(start + PREVIEW_DELAY));
+        app.dirty = false;
+        app.update_preview(start + PREVIEW_DELAY);
+        assert!(app.dirty);
+        assert_eq!(app.timer_deadline(), None);
+    }
+
+    #[tokio::test]
+    async fn redraws_wait_for_changes() {
+        let mut app = TestAppBuilder::new().with_stories(sample_stories()).build();
+        let now = Instant::now();
+        app.dirty = false;
+        assert_eq!(app.redraw_deadline(now), now + IDLE_REDRAW);
+
+        app.update(Message::SelectNext);
+        assert!(app.dirty);
+        assert_eq!(app.redraw_deadline(now), now + IDLE_REDRAW);
+
+        // Running out the flash message needs a frame of its own
+        app.flash("copied");
+        let shown = app.flash_message.as_ref().unwrap().1;
+        assert_eq!(app.redraw_deadline(now), shown + FLASH_DURATION);
+        assert_eq!(
+            app.redraw_deadline(shown + FLASH_DURATION),
+            shown + FLASH_DURATION + IDLE_REDRAW
+        );
+
+        // The spinner turns while loading
+        app.load.set_loading(true);
+        assert_eq!(app.redraw_deadline(now), now + SPINNER_INTERVAL);
+    }
+
+    #[test]
+    fn preview_needs_a_wide_terminal() {
+        let mut app = TestAppBuilder::new()
+            .with_stories(sample_stories())
+            .viewport_width(120)
+            .build();
+        let start = Instant::now();
+
+        app.update_preview(start);
+        app.update_preview(start + Duration::from_secs(1));
+        assert_eq!(app.preview.story_id, None);
+        app.update(Message::SwitchPane);
+        assert_eq!(app.view, View::Stories);
+    }
+
+    #[tokio::test]
+    async fn switching_panes_keeps_the_preview() {
+        let stories = sample_stories();
+        let id = stories[0].id;
+        let mut app = TestAppBuilder::new()
+            .with_stories(stories)
+            .with_comments(crate::test_utils::sample_comments())
+            .preview(id)
+            .viewport_width(200)
+            .build();
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::storage::{Storage, StorageLocation};
-    use crate::test_utils::{StoryBuilder, TestAppBuilder, sample_stories};
-    use crate::theme::{ThemeVariant, default_for_variant};
+        app.update(Message::SwitchPane);
+        assert_eq!(open_story_id(&app), Some(id));
+        assert!(!app.comment_tree.is_empty());
+        assert!(!app.load.loading);
 
-    fn test_storage() -> Storage {
-        Storage::open(StorageLocation::InMemory).unwrap()
+        app.update(Message::SelectNext);
+        app.update(Message::SwitchPane);
+        assert_eq!(app.view, View::Stories);
+        assert_eq!(app.selected_index, 0);
+        assert!(!app.shown_comment_tree().is_empty());
     }
 
-    fn test_app() -> App {
-        App::new(
-            default_for_variant(ThemeVariant::Dark),
-            None,
-            test_storage(),
-        )
+    #[test]
+    fn preview_errors_stay_in_the_pane() {
+        let stories = sample_stories();
+        let id = stories[0].id;
+        let mut app = TestAppBuilder::new()
+            .with_stories(stories)
+            .preview(id)
+            .viewport_width(200)
+            .build();
+
+        app.handle_async_result(AsyncResult::Comments(CommentsResult {
+            story_id: id,
+            task_id: 0,
+            result: Err(ApiError::Network("offline".to_string())),
+            fetched_at: None,
+            stored_sort: None,
+            stored_view: None,
+            read_comments: Vec::new(),
+        }));
+        assert!(app.preview.error.is_some());
+        assert!(app.load.error.is_none());
+
+        app.handle_async_result(AsyncResult::Comments(CommentsResult {
+            story_id: id,
+            task_id: 0,
+            result: Ok(crate::test_utils::sample_comments()),
+            fetched_at: None,
+            stored_sort: None,
+            stored_view: None,
+            read_comments: Vec::new(),
+        }));
+        assert_eq!(app.preview.comment_tree.len(), 5);
+        assert!(app.comment_tree.is_empty());
     }
 
     #[test]
-    fn test_new_app() {
-        let app = test_app();
-        assert_eq!(app.view, View::Stories);
-        assert_eq!(app.feed, Feed::Top);
-        assert!(!app.should_quit);
+    fn a_preview_loading_behind_another_view_keeps_to_itself() {
+        let stories = sample_stories();
+        let id = stories[0].id;
+        let mut app = TestAppBuilder::new()
+            .with_stories(stories)
+            .preview(id)
+            .viewport_width(200)
+            .build();
+        app.view = View::User {
+            name: "pg".to_string(),
+        };
+        app.comment_tree.set(sample_comments()[..2].to_vec());
+
+        app.handle_async_result(AsyncResult::Comments(CommentsResult {
+            story_id: id,
+            task_id: 0,
+            result: Ok(sample_comments()),
+            fetched_at: None,
+            stored_sort: None,
+            stored_view: None,
+            read_comments: Vec::new(),
+        }));
+        assert_eq!(app.comment_tree.len(), 2);
+        assert_eq!(app.preview.comment_tree.len(), 5);
+    }
+
+    #[tokio::test]
+    async fn repeat_applies_message_count_times() {
+        let mut app = TestAppBuilder::new().with_stories(sample_stories()).build();
+        app.pending_keys = Some((PendingKeys::default(), Instant::now()));
+
+        app.update(Message::Repeat(3, Box::new(Message::SelectNext)));
+
+        assert_eq!(app.selected_index, 3);
+        assert!(app.pending_keys.is_none());
     }
 
     #[test]
@@ -1345,12 +3365,10 @@ mod tests {
     #[test]
     fn test_go_back_restores_state() {
         let mut app = test_app();
-        app.view = View::Comments {
-            story_id: 1,
-            story_title: "Test".to_string(),
-            story_index: 5,
-            story_scroll: 10,
-        };
+        app.selected_index = 5;
+        app.scroll_offset = 10;
+        app.nav.push(app.nav_entry());
+        app.view = comments_view(1, "Test");
         app.selected_index = 3; // Comment selection
         app.go_back();
         assert_eq!(app.view, View::Stories);
@@ -1358,6 +3376,628 @@ mod tests {
         assert_eq!(app.scroll_offset, 10);
     }
 
+    fn open_story_id(app: &App) -> Option<u64> {
+        match &app.view {
+            View::Comments { story } => Some(story.id),
+            View::Stories | View::User { .. } => None,
+        }
+    }
+
+    #[tokio::test]
+    async fn back_and_forward_restore_each_view() {
+        let mut app = TestAppBuilder::new()
+            .with_stories(sample_stories())
+            .selected(1)
+            .build();
+        let (first, linked) = (app.stories[1].id, app.stories[3].id);
+        app.update(Message::OpenComments);
+        app.comment_tree.set(sample_comments());
+        app.comment_tree.collapse(101);
+        app.selected_index = 2;
+
+        app.update(Message::OpenItem(linked));
+        assert_eq!(open_story_id(&app), Some(linked));
+        assert_eq!(app.selected_index, 0);
+
+        app.update(Message::Back);
+        assert_eq!(open_story_id(&app), Some(first));
+        assert_eq!(app.selected_index, 2);
+        assert!(!app.comment_tree.is_expanded(101));
+        app.update(Message::Back);
+        assert_eq!(app.view, View::Stories);
+        assert_eq!(app.selected_index, 1);
+        app.update(Message::Back);
+        assert_eq!(app.view, View::Stories);
+
+        app.update(Message::Forward);
+        assert_eq!(open_story_id(&app), Some(first));
+        assert_eq!(app.selected_index, 2);
+        app.update(Message::Forward);
+        assert_eq!(open_story_id(&app), Some(linked));
+        app.update(Message::Forward);
+        assert_eq!(open_story_id(&app), Some(linked));
+    }
+
+    #[tokio::test]
+    async fn comments_for_a_view_left_while_loading_are_kept() {
+        let mut app = TestAppBuilder::new().with_stories(sample_stories()).build();
+        let (first, linked) = (app.stories[0].id, app.stories[3].id);
+        app.update(Message::OpenComments);
+        app.update(Message::OpenItem(linked));
+
+        app.handle_async_result(AsyncResult::Comments(CommentsResult {
+            story_id: first,
+            task_id: 0,
+            result: Ok(sample_comments()),
+            fetched_at: Some(1),
+            stored_sort: None,
+            stored_view: None,
+            read_comments: Vec::new(),
+        }));
+        assert!(app.comment_tree.is_empty());
+
+        app.update(Message::Back);
+        assert_eq!(open_story_id(&app), Some(first));
+        assert_eq!(app.comment_tree.len(), 5);
+        assert!(!app.load.loading);
+    }
+
+    #[tokio::test]
+    async fn link_to_a_comment_in_the_thread_selects_it() {
+        let mut app = TestAppBuilder::new()
+            .with_comments(sample_comments())
+            .view(comments_view(1, "Story"))
+            .preview(1)
+            .build();
+        app.comment_tree.collapse(101);
+
+        app.update(Message::FollowLink(
+            "https://news.ycombinator.com/item?id=102".to_string(),
+        ));
+
+        assert_eq!(app.selected_comment().map(|c| c.id), Some(102));
+        assert!(app.comment_tree.is_expanded(101));
+        app.update(Message::Back);
+        assert_eq!(app.selected_index, 0);
+    }
+
+    #[test]
+    fn jumps_between_top_level_comments() {
+        let mut app = TestAppBuilder::new()
+            .with_comments(sample_comments())
+            .view(comments_view(1, "Story"))
+            .expanded(vec![100, 101])
+            .build();
+        app.selected_index = 2;
+
+        app.update(Message::NextTopLevel);
+        assert_eq!(app.selected_comment().map(|c| c.id), Some(104));
+        app.update(Message::NextTopLevel);
+        assert_eq!(app.selected_comment().map(|c| c.id), Some(104));
+
+        app.selected_index = 3;
+        app.update(Message::PrevTopLevel);
+        assert_eq!(app.selected_comment().map(|c| c.id), Some(100));
+        app.update(Message::NextTopLevel);
+        app.update(Message::PrevTopLevel);
+        assert_eq!(app.selected_comment().map(|c| c.id), Some(100));
+    }
+
+    #[test]
+    fn jumps_to_comments_by_op_and_same_author() {
+        let mut comments = sample_comments();
+        comments[4].by = "commenter1".to_string();
+        let mut app = TestAppBuilder::new()
+            .with_comments(comments)
+            .view(View::Comments {
+                story: StoryBuilder::new().id(1).author("author").build(),
+            })
+            .build();
+
+        // OP's reply is nested in a collapsed thread
+        app.update(Message::NextByOp);
+        assert_eq!(app.selected_comment().map(|c| c.id), Some(102));
+        assert!(app.comment_tree.is_expanded(101));
+        app.update(Message::NextByOp);
+        assert_eq!(app.selected_comment().map(|c| c.id), Some(102));
+        assert_eq!(app.flash_text(), Some("no more comments by author"));
+
+        app.selected_index = 0;
+        app.update(Message::NextBySameAuthor);
+        assert_eq!(app.selected_comment().map(|c| c.id), Some(104));
+    }
+
+    #[test]
+    fn same_author_jumps_follow_the_timeline() {
+        let mut comments = sample_comments();
+        comments[1].time = 1700005000;
+        comments[1].by = "windowsuser".to_string();
+        let mut app = TestAppBuilder::new()
+            .with_comments(comments)
+            .view(comments_view(1, "Story"))
+            .build();
+        app.update(Message::ToggleFlatComments);
+        app.update(Message::SelectNext);
+        app.update(Message::SelectNext);
+        assert_eq!(app.selected_comment().map(|c| c.id), Some(103));
+
+        // The later comment comes first in the thread
+        app.update(Message::NextBySameAuthor);
+        assert_eq!(app.selected_comment().map(|c| c.id), Some(101));
+        app.update(Message::NextBySameAuthor);
+        assert_eq!(app.flash_text(), Some("no more comments by windowsuser"));
+    }
+
+    #[tokio::test]
+    async fn same_author_jumps_stay_in_the_zoomed_replies() {
+        let mut comments = sample_comments();
+        comments[4].by = "commenter2".to_string();
+        let mut app = TestAppBuilder::new()
+            .with_comments(comments)
+            .view(comments_view(1, "Story"))
+            .expanded(vec![100])
+            .selected(1)
+            .build();
+        app.update(Message::ZoomComment);
+        assert_eq!(app.selected_comment().map(|c| c.id), Some(101));
+
+        app.update(Message::NextBySameAuthor);
+        assert_eq!(app.selected_comment().map(|c| c.id), Some(101));
+        assert_eq!(app.flash_text(), Some("no more comments by commenter2"));
+    }
+
+    #[test]
+    fn cycles_through_code_blocks_in_the_selected_comment() {
+        let mut comments = sample_comments();
+        comments[0].text = "<pre><code>a</code></pre><p>and<p><pre><code>b</code></pre>".into();
+        let mut app = TestAppBuilder::new()
+            .with_comments(comments)
+            .view(comments_view(1, "Test"))
+            .build();
+        let id = app.selected_comment().unwrap().id;
+
+        app.update(Message::CycleCodeBlock);
+        assert_eq!(app.selected_code_block(), Some((id, 0)));
+        app.update(Message::CycleCodeBlock);
+        assert_eq!(app.selected_code_block(), Some((id, 1)));
+        app.update(Message::CycleCodeBlock);
+        assert_eq!(app.selected_code_block(), None);
+
+        // Moving on leaves the block behind
+        app.update(Message::CycleCodeBlock);
+        app.update(Message::SelectNext);
+        assert_eq!(app.selected_code_block(), None);
+        app.update(Message::CycleCodeBlock);
+        assert_eq!(app.flash_text(), Some("no code blocks in this comment"));
+    }
+
+    #[tokio::test]
+    async fn sorting_comments_keeps_the_selection() {
+        let mut app = TestAppBuilder::new()
+            .with_comments(sample_comments())
+            .view(comments_view(1, "Test"))
+            .build();
+        while app.selected_comment().is_some_and(|c| c.id != 104) {
+            app.update(Message::SelectNext);
+        }
+
+        app.update(Message::CycleCommentSort);
+        assert_eq!(app.comment_sort, CommentSort::NewestFirst);
+        assert_eq!(app.flash_text(), Some("comments sorted by newest"));
+        assert_eq!(app.selected_comment().map(|c| c.id), Some(104));
+        assert_eq!(app.comment_tree.comments()[0].id, 104);
+
+        // Replies stay under their parent
+        let ids: Vec<u64> = app.comment_tree.comments().iter().map(|c| c.id).collect();
+        assert_eq!(ids, [104, 100, 103, 101, 102]);
+    }
+
+    #[tokio::test]
+    async fn timeline_toggles_back_to_the_same_comment() {
+        let mut comments = sample_comments();
+        comments[3].time = 1; // windowsuser answered first
+        let mut app = TestAppBuilder::new()
+            .with_comments(comments)
+            .view(comments_view(1, "Test"))
+            .expanded(vec![])
+            .build();
+
+        app.update(Message::ToggleFlatComments);
+        assert_eq!(app.flash_text(), Some("comments as a timeline"));
+        let ids: Vec<u64> = app
+            .visible_comment_indices()
+            .iter()
+            .map(|&i| app.comment_tree.comments()[i].id)
+            .collect();
+        assert_eq!(ids, [103, 100, 101, 102, 104]);
+        assert_eq!(app.selected_comment().map(|c| c.id), Some(100));
+
+        // A collapsed reply picked in the timeline is revealed in the tree
+        app.update(Message::SelectNext);
+        app.update(Message::SelectNext);
+        assert_eq!(app.selected_comment().map(|c| c.id), Some(102));
+        app.update(Message::GoToParent);
+        assert_eq!(app.selected_comment().map(|c| c.id), Some(101));
+        app.update(Message::ToggleFlatComments);
+        assert_eq!(app.selected_comment().map(|c| c.id), Some(101));
+        assert_eq!(app.selected_index, 1);
+    }
+
+    #[tokio::test]
+    async fn zooming_shows_replies_and_escape_returns_to_the_thread() {
+        let mut app = TestAppBuilder::new()
+            .with_comments(sample_comments())
+            .view(comments_view(1, "Test"))
+            .expanded(vec![100])
+            .build();
+        app.update(Message::SelectNext);
+        assert_eq!(app.selected_comment().map(|c| c.id), Some(101));
+
+        // Collapsed replies open up when zoomed into
+        app.update(Message::ZoomComment);
+        let ids: Vec<u64> = app
+            .visible_comment_indices()
+            .iter()
+            .map(|&i| app.comment_tree.comments()[i].id)
+            .collect();
+        assert_eq!(ids, [101, 102]);
+        app.update(Message::SelectNext);
+        app.update(Message::SelectNext);
+        assert_eq!(app.selected_comment().map(|c| c.id), Some(102));
+
+        app.update(Message::ZoomComment);
+        assert_eq!(app.flash_text(), Some("no replies to zoom into"));
+
+        app.update(Message::Back);
+        assert!(app.comment_zoom.is_none());
+        assert!(matches!(app.view, View::Comments { .. }));
+        assert_eq!(app.selected_comment().map(|c| c.id), Some(102));
+        assert_eq!(app.selected_index, 2);
+    }
+
+    #[tokio::test]
+    async fn zoom_ends_when_a_refresh_drops_the_comment() {
+        let mut app = TestAppBuilder::new().with_stories(sample_stories()).build();
+        app.update(Message::OpenComments);
+        let story_id = open_story_id(&app).unwrap();
+        let loaded = |comments| {
+            AsyncResult::Comments(CommentsResult {
+                story_id,
+                task_id: 0,
+                result: Ok(comments),
+                fetched_at: None,
+                stored_sort: None,
+                stored_view: None,
+                read_comments: Vec::new(),
+            })
+        };
+        app.handle_async_result(loaded(sample_comments()));
+        app.update(Message::SelectNext);
+        app.update(Message::ZoomComment);
+        assert_eq!(app.comment_zoom, Some(101));
+
+        app.update(Message::Refresh);
+        let mut comments = sample_comments();
+        comments.retain(|c| c.id != 101 && c.id != 102);
+        app.handle_async_result(loaded(comments));
+        assert!(app.comment_zoom.is_none());
+        assert_eq!(app.visible_comment_indices().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn unread_comments_are_jumped_to_and_shown_alone() {
+        let mut app = TestAppBuilder::new().with_stories(sample_stories()).build();
+        app.update(Message::OpenComments);
+        let story_id = open_story_id(&app).unwrap();
+        app.handle_async_result(AsyncResult::Comments(CommentsResult {
+            story_id,
+            task_id: 0,
+            result: Ok(sample_comments()),
+            fetched_at: None,
+            stored_sort: None,
+            stored_view: None,
+            read_comments: vec![100, 101, 104],
+        }));
+        assert!(app.comment_reads.is_new(102));
+        assert!(!app.comment_reads.is_new(101));
+
+        // Read comments stay when unread replies are nested under them
+        app.update(Message::ToggleUnreadOnly);
+        let ids: Vec<u64> = app
+            .visible_comment_indices()
+            .iter()
+            .map(|&i| app.comment_tree.comments()[i].id)
+            .collect();
+        assert_eq!(ids, [100, 101, 102, 103]);
+
+        app.update(Message::NextUnread);
+        assert_eq!(app.selected_comment().map(|c| c.id), Some(102));
+        app.update(Message::NextUnread);
+        assert_eq!(app.selected_comment().map(|c| c.id), Some(103));
+        app.update(Message::NextUnread);
+        assert_eq!(app.flash_text(), Some("no unread comments"));
+        assert_eq!(
+            app.comment_reads.take_unsaved(),
+            Some((story_id, vec![102, 103]))
+        );
+
+        app.update(Message::ToggleUnreadOnly);
+        assert_eq!(app.visible_comment_indices().len(), 5);
+        app.update(Message::ToggleUnreadOnly);
+        assert!(!app.unread_only);
+        assert_eq!(app.flash_text(), Some("no unread comments"));
+    }
+
+    #[tokio::test]
+    async fn unread_only_in_the_timeline_leaves_out_every_read_comment() {
+        let mut app = TestAppBuilder::new()
+            .with_comments(sample_comments())
+            .view(comments_view(1, "Test"))
+            .build();
+        app.comment_reads.load([100, 101, 104]);
+        app.update(Message::ToggleFlatComments);
+
+        // Read parents aren't kept, as the timeline doesn't nest replies
+        app.update(Message::ToggleUnreadOnly);
+        assert!(app.unread_only);
+        let ids: Vec<u64> = app
+            .visible_comment_indices()
+            .iter()
+            .map(|&i| app.comment_tree.comments()[i].id)
+            .collect();
+        assert_eq!(ids, [102, 103]);
+        assert_eq!(app.selected_comment().map(|c| c.id), Some(102));
+
+        app.update(Message::ToggleUnreadOnly);
+        assert_eq!(app.visible_comment_indices().len(), 5);
+    }
+
+    #[tokio::test]
+    async fn comments_drawn_are_read() {
+        let mut app = TestAppBuilder::new()
+            .with_comments(sample_comments())
+            .view(comments_view(1, "Test"))
+            .build();
+        app.comments_on_screen.replace(vec![100, 101, 102]);
+        app.mark_shown_comments_read();
+        assert!(app.comment_reads.is_read(102));
+        assert!(app.comments_on_screen.borrow().is_empty());
+        assert_eq!(
+            app.comment_reads.take_unsaved(),
+            Some((1, vec![100, 101, 102]))
+        );
+    }
+
+    #[tokio::test]
+    async fn reopened_thread_resumes_where_it_was_left() {
+        let mut app = TestAppBuilder::new().with_stories(sample_stories()).build();
+        app.update(Message::OpenComments);
+        let story_id = open_story_id(&app).unwrap();
+        app.handle_async_result(AsyncResult::Comments(CommentsResult {
+            story_id,
+            task_id: 0,
+            result: Ok(sample_comments()),
+            fetched_at: None,
+            stored_sort: None,
+            stored_view: Some(CommentViewState {
+                collapsed: vec![100],
+                selected: Some(104),
+            }),
+            read_comments: Vec::new(),
+        }));
+        assert!(!app.comment_tree.is_expanded(100));
+        assert_eq!(app.selected_comment().map(|c| c.id), Some(104));
+        assert_eq!(app.selected_index, 1);
+
+        // A refresh keeps the thread as it is now
+        app.update(Message::SelectPrev);
+        app.update(Message::ExpandComment);
+        app.update(Message::CollapseComment);
+        app.update(Message::Refresh);
+        app.handle_async_result(AsyncResult::Comments(CommentsResult {
+            story_id,
+            task_id: 0,
+            result: Ok(sample_comments()),
+            fetched_at: None,
+            stored_sort: None,
+            stored_view: None,
+            read_comments: Vec::new(),
+        }));
+        assert_eq!(
+            app.comment_view_state(),
+            Some((
+                story_id,
+                CommentViewState {
+                    collapsed: vec![100],
+                    selected: Some(100),
+                }
+            ))
+        );
+
+        app.update(Message::ResetCommentView);
+        assert!(app.comment_tree.collapsed().is_empty());
+        assert_eq!(app.flash_text(), Some("thread reset"));
+    }
+
+    #[tokio::test]
+    async fn item_lookup_is_dropped_once_the_view_changes() {
+        let mut app = TestAppBuilder::new().with_stories(sample_stories()).build();
+        let story = StoryBuilder::new().id(42).title("Elsewhere").build();
+        app.pending_item = Some(ItemLookup {
+            id: 42,
+            task_id: 7,
+            from: View::Stories,
+        });
+        app.update(Message::OpenComments);
+        let opened = open_story_id(&app);
+
+        app.handle_async_result(AsyncResult::Item(ItemResult {
+            id: 42,
+            task_id: 7,
+            result: Ok(Some((story, None))),
+        }));
+        assert_eq!(open_story_id(&app), opened);
+        assert!(app.pending_item.is_none());
+    }
+
+    #[test]
+    fn sorting_outside_the_list_says_so() {
+        let mut app = TestAppBuilder::new()
+            .with_comments(sample_comments())
+            .view(comments_view(1, "Test"))
+            .build();
+        app.update(Message::SetSortOrder(StorySort::ScoreDesc));
+        assert_eq!(app.story_sort, StorySort::Position);
+        assert_eq!(
+            app.flash_text(),
+            Some("stories can only be sorted from the list")
+        );
+    }
+
+    #[tokio::test]
+    async fn linked_comment_is_selected_once_its_thread_loads() {
+        let mut app = TestAppBuilder::new().with_stories(sample_stories()).build();
+        let story = StoryBuilder::new().id(42).title("Elsewhere").build();
+
+        app.pending_item = Some(ItemLookup {
+            id: 103,
+            task_id: 0,
+            from: View::Stories,
+        });
+        app.handle_async_result(AsyncResult::Item(ItemResult {
+            id: 103,
+            task_id: 0,
+            result: Ok(Some((story, Some(103)))),
+        }));
+        assert_eq!(open_story_id(&app), Some(42));
+        assert!(app.load.loading);
+
+        app.handle_async_result(AsyncResult::Comments(CommentsResult {
+            story_id: 42,
+            task_id: 0,
+            result: Ok(sample_comments()),
+            fetched_at: None,
+            stored_sort: None,
+            stored_view: None,
+            read_comments: Vec::new(),
+        }));
+        assert_eq!(app.selected_comment().map(|c| c.id), Some(103));
+        assert!(app.focus_comment.is_none());
+    }
+
+    #[tokio::test]
+    async fn user_links_open_the_profile_in_app() {
+        let mut app = TestAppBuilder::new()
+            .with_comments(sample_comments())
+            .view(comments_view(1, "Story"))
+            .selected(3)
+            .build();
+
+        app.update(Message::FollowLink(
+            "https://news.ycombinator.com/user?id=pg".to_string(),
+        ));
+        assert_eq!(
+            app.view,
+            View::User {
+                name: "pg".to_string()
+            }
+        );
+        assert!(app.load.loading);
+
+        let user = User {
+            id: "pg".to_string(),
+            created: 1,
+            karma: 10,
+            about: None,
+            submitted: Vec::new(),
+        };
+        app.handle_async_result(AsyncResult::User(UserResult {
+            name: "someone else".to_string(),
+            task_id: 0,
+            result: Ok(Some(user.clone())),
+        }));
+        assert!(app.user.is_none());
+        app.handle_async_result(AsyncResult::User(UserResult {
+            name: "pg".to_string(),
+            task_id: 0,
+            result: Ok(Some(user)),
+        }));
+        assert_eq!(app.user.as_ref().map(|u| u.karma), Some(10));
+        assert!(!app.load.loading);
+
+        app.update(Message::Back);
+        assert_eq!(open_story_id(&app), Some(1));
+        assert_eq!(app.selected_index, 3);
+        app.update(Message::Forward);
+        assert!(matches!(app.view, View::User { .. }));
+        assert!(!app.load.loading, "the profile is kept, not fetched again");
+    }
+
+    /// Comments view with links, rendered once so the app knows what's on
+    /// screen.
+    fn app_with_links() -> App {
+        let app = TestAppBuilder::new()
+            .with_comments(vec![
+                CommentBuilder::new()
+                    .id(1)
+                    .text(r#"<a href="https://a.example">a</a> and <a href="https://b.example">b</a>"#)
+                    .build(),
+                CommentBuilder::new().id(2).text("no links").build(),
+                CommentBuilder::new()
+                    .id(3)
+                    .text(r#"<a href="https://news.ycombinator.com/user?id=pg">pg</a>"#)
+                    .build(),
+            ])
+            .view(comments_view(1, "Story"))
+            .build();
+        render_to_string(80, 24, |frame| {
+            crate::views::comments::render(frame, &app, frame.area());
+        });
+        app
+    }
+
+    #[test]
+    fn hints_label_the_links_on_screen() {
+        let mut app = app_with_links();
+
+        app.update(Message::ShowHints(HintAction::Preview));
+        let hints = app.hints.as_ref().unwrap();
+        assert_eq!(hints.labels_for(1), vec![Some("s"), Some("a")]);
+        assert_eq!(hints.labels_for(3), vec![Some("d")]);
+
+        app.update(Message::HintInput('a'));
+        assert!(app.hints.is_none());
+        assert_eq!(app.flash_text(), Some("https://b.example"));
+    }
+
+    #[tokio::test]
+    async fn hints_open_hn_links_in_app() {
+        let mut app = app_with_links();
+
+        app.update(Message::ShowHints(HintAction::Open));
+        app.update(Message::HintInput('d'));
+
+        assert_eq!(
+            app.view,
+            View::User {
+                name: "pg".to_string()
+            }
+        );
+    }
+
+    #[test]
+    fn unknown_hint_cancels() {
+        let mut app = app_with_links();
+
+        app.update(Message::ShowHints(HintAction::Open));
+        app.update(Message::HintInput('z'));
+        assert!(app.hints.is_none());
+
+        app.update(Message::ShowHints(HintAction::Open));
+        app.update(Message::HintBackspace);
+        assert!(app.hints.is_none());
+    }
+
     #[test]
     fn visible_story_capacity_with_no_viewport() {
         let app = TestAppBuilder::new().build();
@@ -1450,12 +4090,7 @@ mod tests {
             .with_stories(stories)
             .viewport_height(50)
             .has_more(true)
-            .view(View::Comments {
-                story_id: 1,
-                story_title: "Test".to_string(),
-                story_index: 0,
-                story_scroll: 0,
-            })
+            .view(comments_view(1, "Test"))
             .build();
         assert!(!app.should_fill_viewport());
     }
@@ -1505,12 +4140,7 @@ mod tests {
     async fn refresh_clears_comments_fetched_at() {
         let mut app = TestAppBuilder::new()
             .with_stories(sample_stories())
-            .view(View::Comments {
-                story_id: 1,
-                story_title: "Test".to_string(),
-                story_index: 0,
-                story_scroll: 0,
-            })
+            .view(comments_view(1, "Test"))
             .comments_fetched_at(1700000000)
             .build();
 
@@ -1612,12 +4242,7 @@ mod tests {
         let stories = vec![StoryBuilder::new().id(1).score(100).build()];
         let mut app = TestAppBuilder::new()
             .with_stories(stories)
-            .view(View::Comments {
-                story_id: 1,
-                story_title: "Test".to_string(),
-                story_index: 0,
-                story_scroll: 0,
-            })
+            .view(comments_view(1, "Test"))
             .build();
         app.update(Message::CycleSortOrder);
         assert_eq!(app.story_sort, StorySort::Position);
@@ -85,7 +85,7 @@ impl Harness {
 
     fn run(&mut self) -> Result<()> {
         // Give the TUI time to start and load data
-        std::thread::sleep(Duration::from_millis(3000));
+        std::thread::sleep(Duration::from_secs(3));
         self.drain_pty()?;
 
         loop {
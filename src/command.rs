@@ -0,0 +1,426 @@
+//! Ex-style command line.
+//!
+//! `:` opens a prompt that parses commands such as `:feed best`,
+//! `:open 41234567`, `:sort score`, `:theme nord` or `:user pg` into
+//! [`Message`]s. Commands can be shortened to any unique prefix (`:fe best`)
+//! and Tab cycles through fuzzy completions for the command or its argument.
+
+use crate::api::Feed;
+use crate::app::Message;
+use crate::storage::StorySort;
+use crate::theme::all_themes;
+
+/// Maximum number of entries kept in the command history.
+pub const HISTORY_LIMIT: usize = 100;
+
+pub struct CommandSpec {
+    pub name: &'static str,
+    pub usage: &'static str,
+    pub description: &'static str,
+}
+
+pub const COMMANDS: &[CommandSpec] = &[
+    CommandSpec {
+        name: "feed",
+        usage: "feed <name>",
+        description: "switch to a feed tab",
+    },
+    CommandSpec {
+        name: "open",
+        usage: "open <id|url>",
+        description: "open the comments for an item id, or a link",
+    },
+    CommandSpec {
+        name: "sort",
+        usage: "sort <api|score|comments|time>",
+        description: "sort the story list",
+    },
+    CommandSpec {
+        name: "theme",
+        usage: "theme <name>",
+        description: "switch theme",
+    },
+    CommandSpec {
+        name: "user",
+        usage: "user <name>",
+        description: "open a user's profile",
+    },
+    CommandSpec {
+        name: "refresh",
+        usage: "refresh",
+        description: "reload the current view",
+    },
+    CommandSpec {
+        name: "forget",
+        usage: "forget",
+        description: "open the thread from the top, forgetting where it was left",
+    },
+    CommandSpec {
+        name: "help",
+        usage: "help",
+        description: "show keybindings",
+    },
+    CommandSpec {
+        name: "quit",
+        usage: "quit",
+        description: "exit hn",
+    },
+];
+
+const SORTS: &[StorySort] = &[
+    StorySort::Position,
+    StorySort::ScoreDesc,
+    StorySort::CommentsDesc,
+    StorySort::TimeDesc,
+];
+
+/// Parse a command line (without the leading `:`) into a message.
+pub fn parse(input: &str, feeds: &[Feed]) -> Result<Message, String> {
+    let input = input.trim().trim_start_matches(':');
+    let (name, arg) = input
+        .split_once(char::is_whitespace)
+        .map_or((input, ""), |(name, arg)| (name, arg.trim()));
+    if name.is_empty() {
+        return Err("Empty command".to_string());
+    }
+    let command = resolve_command(name)?;
+
+    let require_arg = || {
+        if arg.is_empty() {
+            Err(format!("Usage: :{}", command.usage))
+        } else {
+            Ok(arg)
+        }
+    };
+
+    match command.name {
+        "feed" => {
+            let arg = require_arg()?;
+            feeds
+                .iter()
+                .find(|f| f.matches_name(arg))
+                .map(|&f| Message::SwitchFeed(f))
+                .ok_or_else(|| format!("Unknown feed '{arg}'"))
+        }
+        "open" => {
+            let arg = require_arg()?;
+            if arg.contains("://") {
+                return Ok(Message::FollowLink(arg.to_string()));
+            }
+            arg.trim_start_matches('#')
+                .parse()
+                .map(Message::OpenItem)
+                .map_err(|_| format!("Invalid item id '{arg}'"))
+        }
+        "sort" => {
+            let arg = require_arg()?;
+            SORTS
+                .iter()
+                .find(|s| s.label().eq_ignore_ascii_case(arg))
+                .map(|&s| Message::SetSortOrder(s))
+                .ok_or_else(|| format!("Unknown sort '{arg}'"))
+        }
+        "theme" => {
+            let arg = require_arg()?;
+            all_themes()
+                .into_iter()
+                .find(|t| t.name.eq_ignore_ascii_case(arg))
+                .map(|t| Message::SetTheme(t.name))
+                .ok_or_else(|| format!("Unknown theme '{arg}'"))
+        }
+        "user" => Ok(Message::OpenUserProfile(require_arg()?.to_string())),
+        "refresh" => Ok(Message::Refresh),
+        "forget" => Ok(Message::ResetCommentView),
+        "help" => Ok(Message::ToggleHelp),
+        "quit" => Ok(Message::Quit),
+        _ => unreachable!("every command in COMMANDS is handled"),
+    }
+}
+
+/// Resolve a command by exact name or unique prefix.
+fn resolve_command(name: &str) -> Result<&'static CommandSpec, String> {
+    let name = name.to_ascii_lowercase();
+    if let Some(exact) = COMMANDS.iter().find(|c| c.name == name) {
+        return Ok(exact);
+    }
+    let mut matches = COMMANDS.iter().filter(|c| c.name.starts_with(&name));
+    match (matches.next(), matches.next()) {
+        (Some(command), None) => Ok(command),
+        (Some(_), Some(_)) => Err(format!("Ambiguous command '{name}'")),
+        _ => Err(format!("Unknown command '{name}'")),
+    }
+}
+
+/// Completions for `input`, best match first. Each one is a full
+/// replacement for the input.
+pub fn complete(input: &str, feeds: &[Feed]) -> Vec<String> {
+    let Some((name, arg)) = input.split_once(char::is_whitespace) else {
+        let names = COMMANDS.iter().map(|c| c.name.to_string());
+        return rank(names, input);
+    };
+    let Ok(command) = resolve_command(name) else {
+        return Vec::new();
+    };
+    let candidates: Vec<String> = match command.name {
+        "feed" => feeds.iter().map(|f| f.label().to_lowercase()).collect(),
+        "sort" => SORTS.iter().map(|s| s.label().to_string()).collect(),
+        "theme" => all_themes().into_iter().map(|t| t.name).collect(),
+        _ => Vec::new(),
+    };
+    rank(candidates.into_iter(), arg.trim_start())
+        .into_iter()
+        .map(|arg| format!("{} {arg}", command.name))
+        .collect()
+}
+
+fn rank(candidates: impl Iterator<Item = String>, query: &str) -> Vec<String> {
+    let mut scored: Vec<(usize, String)> = candidates
+        .filter_map(|c| fuzzy_score(&c, query).map(|score| (score, c)))
+        .collect();
+    scored.sort_by_key(|(score, _)| *score);
+    scored.into_iter().map(|(_, c)| c).collect()
+}
+
+/// Score `candidate` against `query` as a case-insensitive subsequence match.
+/// Lower is better: prefix matches score 0, other matches rank by how early
+/// in the candidate the query is used up.
+fn fuzzy_score(candidate: &str, query: &str) -> Option<usize> {
+    let candidate = candidate.to_lowercase();
+    let query = query.to_lowercase();
+    if candidate.starts_with(&query) {
+        return Some(0);
+    }
+    let mut chars = candidate.char_indices();
+    let mut end = 0;
+    for q in query.chars() {
+        (end, _) = chars.find(|&(_, c)| c == q)?;
+    }
+    Some(1 + end)
+}
+
+/// State of the open command line prompt.
+#[derive(Debug, Clone, Default)]
+pub struct CommandLine {
+    pub input: String,
+    /// Completions being cycled with Tab, and which one is shown.
+    completions: Vec<String>,
+    completion_index: Option<usize>,
+    /// Position while browsing history, counted back from the newest entry,
+    /// and the input typed before browsing started.
+    history_index: Option<usize>,
+    draft: String,
+}
+
+impl CommandLine {
+    pub fn insert(&mut self, c: char) {
+        self.input.push(c);
+        self.reset();
+    }
+
+    /// Delete the last character. Returns false if the input was already
+    /// empty, which closes the prompt like in vim.
+    pub fn backspace(&mut self) -> bool {
+        self.reset();
+        self.input.pop().is_some()
+    }
+
+    /// Replace the input with the next completion.
+    pub fn complete(&mut self, feeds: &[Feed]) {
+        if self.completion_index.is_none() {
+            self.completions = complete(&self.input, feeds);
+        }
+        if self.completions.is_empty() {
+            return;
+        }
+        let next = self
+            .completion_index
+            .map_or(0, |i| (i + 1) % self.completions.len());
+        self.completion_index = Some(next);
+        self.input.clone_from(&self.completions[next]);
+    }
+
+    /// The completion list and selected entry, if Tab is being cycled.
+    pub fn completions(&self) -> Option<(&[String], usize)> {
+        self.completion_index
+            .map(|i| (self.completions.as_slice(), i))
+    }
+
+    /// Step back to an older history entry.
+    pub fn history_prev(&mut self, history: &[String]) {
+        let index = self.history_index.map_or(0, |i| i + 1);
+        if index >= history.len() {
+            return;
+        }
+        if self.history_index.is_none() {
+            self.draft.clone_from(&self.input);
+        }
+        self.history_index = Some(index);
+        self.input.clone_from(&history[history.len() - 1 - index]);
+        self.completion_index = None;
+    }
+
+    /// Step forward to a newer history entry, ending at the original input.
+    pub fn history_next(&mut self, history: &[String]) {
+        match self.history_index {
+            Some(0) => {
+                self.history_index = None;
+                self.input = std::mem::take(&mut self.draft);
+            }
+            Some(i) => {
+                self.history_index = Some(i - 1);
+                self.input.clone_from(&history[history.len() - i]);
+            }
+            None => {}
+        }
+        self.completion_index = None;
+    }
+
+    const fn reset(&mut self) {
+        self.completion_index = None;
+        self.history_index = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn feeds() -> Vec<Feed> {
+        Feed::all().to_vec()
+    }
+
+    #[test]
+    fn parses_commands_into_messages() {
+        let feeds = feeds();
+        assert_eq!(
+            parse("feed best", &feeds),
+            Ok(Message::SwitchFeed(Feed::Best))
+        );
+        assert_eq!(
+            parse("feed Favs", &feeds),
+            Ok(Message::SwitchFeed(Feed::Favorites))
+        );
+        assert_eq!(
+            parse("open 41234567", &feeds),
+            Ok(Message::OpenItem(41_234_567))
+        );
+        assert_eq!(
+            parse("open https://news.ycombinator.com/item?id=1", &feeds),
+            Ok(Message::FollowLink(
+                "https://news.ycombinator.com/item?id=1".to_string()
+            ))
+        );
+        assert_eq!(
+            parse("sort score", &feeds),
+            Ok(Message::SetSortOrder(StorySort::ScoreDesc))
+        );
+        assert_eq!(
+            parse("theme Nord", &feeds),
+            Ok(Message::SetTheme("nord".to_string()))
+        );
+        assert_eq!(
+            parse(":user pg", &feeds),
+            Ok(Message::OpenUserProfile("pg".to_string()))
+        );
+        assert_eq!(parse("forget", &feeds), Ok(Message::ResetCommentView));
+        assert_eq!(parse("  quit ", &feeds), Ok(Message::Quit));
+    }
+
+    #[test]
+    fn commands_accept_unique_prefixes() {
+        let feeds = feeds();
+        assert_eq!(parse("fe ask", &feeds), Ok(Message::SwitchFeed(Feed::Ask)));
+        assert_eq!(parse("q", &feeds), Ok(Message::Quit));
+        assert_eq!(parse("t", &feeds), Err("Usage: :theme <name>".to_string()));
+    }
+
+    #[test]
+    fn reports_bad_input() {
+        let feeds = feeds();
+        assert_eq!(parse("", &feeds), Err("Empty command".to_string()));
+        assert_eq!(
+            parse("frobnicate", &feeds),
+            Err("Unknown command 'frobnicate'".to_string())
+        );
+        assert_eq!(
+            parse("feed", &feeds),
+            Err("Usage: :feed <name>".to_string())
+        );
+        assert_eq!(
+            parse("feed nope", &feeds),
+            Err("Unknown feed 'nope'".to_string())
+        );
+        assert_eq!(
+            parse("open abc", &feeds),
+            Err("Invalid item id 'abc'".to_string())
+        );
+        assert_eq!(
+            parse("sort size", &feeds),
+            Err("Unknown sort 'size'".to_string())
+        );
+    }
+
+    #[test]
+    fn completes_commands_and_arguments() {
+        let feeds = feeds();
+        assert_eq!(complete("th", &feeds), vec!["theme"]);
+        // Subsequence matches rank after prefix matches
+        assert_eq!(complete("re", &feeds)[0], "refresh");
+        assert_eq!(complete("fd", &feeds), vec!["feed"]);
+        assert_eq!(
+            complete("sort c", &feeds),
+            vec!["sort comments", "sort score"]
+        );
+        assert_eq!(
+            complete("feed s", &feeds),
+            vec![
+                "feed show",
+                "feed ask",
+                "feed best",
+                "feed favs",
+                "feed jobs"
+            ]
+        );
+        assert_eq!(complete("theme gruvl", &feeds), vec!["theme gruvbox-light"]);
+        assert!(complete("quit x", &feeds).is_empty());
+    }
+
+    #[test]
+    fn tab_cycles_completions() {
+        let feeds = feeds();
+        let mut line = CommandLine::default();
+        line.insert('s');
+        line.complete(&feeds);
+        assert_eq!(line.input, "sort");
+        line.complete(&feeds);
+        assert_eq!(line.input, "user");
+        assert_eq!(line.completions().map(|(c, i)| (c.len(), i)), Some((3, 1)));
+        line.complete(&feeds);
+        line.complete(&feeds);
+        assert_eq!(line.input, "sort");
+
+        // Typing starts a fresh completion
+        line.insert(' ');
+        line.complete(&feeds);
+        assert_eq!(line.input, "sort api");
+    }
+
+    #[test]
+    fn history_navigation_restores_draft() {
+        let history = vec!["feed best".to_string(), "sort time".to_string()];
+        let mut line = CommandLine::default();
+        line.insert('o');
+
+        line.history_prev(&history);
+        assert_eq!(line.input, "sort time");
+        line.history_prev(&history);
+        assert_eq!(line.input, "feed best");
+        line.history_prev(&history);
+        assert_eq!(line.input, "feed best");
+
+        line.history_next(&history);
+        assert_eq!(line.input, "sort time");
+        line.history_next(&history);
+        assert_eq!(line.input, "o");
+    }
+}
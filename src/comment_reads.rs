@@ -0,0 +1,117 @@
+//! Which comments have been read, so a thread can show what's new since it
+//! was last open and be narrowed down to the comments not read yet.
+
+use std::collections::HashSet;
+
+use crate::api::Comment;
+
+/// Reads held back before they're saved together.
+const SAVE_BATCH: usize = 20;
+
+/// Comments read in this session or an earlier one. Comment ids are unique
+/// across threads, so one set covers them all.
+#[derive(Debug, Default)]
+pub struct CommentReads {
+    read: HashSet<u64>,
+    /// Comments of the open thread that were read when it loaded, or when
+    /// "unread only" was last turned on. Reading more doesn't change it, so
+    /// comments don't disappear or lose their mark while they're read.
+    seen: HashSet<u64>,
+    /// Comments read but not saved yet, and the story they belong to.
+    unsaved: Option<(u64, Vec<u64>)>,
+}
+
+impl CommentReads {
+    pub fn is_read(&self, id: u64) -> bool {
+        self.read.contains(&id)
+    }
+
+    /// Read when the thread was opened, so "unread only" leaves it out.
+    pub fn was_seen(&self, id: u64) -> bool {
+        self.seen.contains(&id)
+    }
+
+    /// Posted since the thread was last read. Nothing is new in a thread
+    /// read for the first time, and a comment stays new while it's read.
+    pub fn is_new(&self, id: u64) -> bool {
+        !self.seen.is_empty() && !self.seen.contains(&id)
+    }
+
+    /// Add comments read in earlier sessions.
+    pub fn load(&mut self, ids: impl IntoIterator<Item = u64>) {
+        self.read.extend(ids);
+    }
+
+    /// Take note of which of `thread`'s comments are read by now.
+    pub fn settle(&mut self, thread: &[Comment]) {
+        self.seen = thread
+            .iter()
+            .map(|c| c.id)
+            .filter(|id| self.read.contains(id))
+            .collect();
+    }
+
+    /// Mark a comment of `story_id` read. Returns reads to save once enough
+    /// have been held back, or when they belong to another story.
+    pub fn mark(&mut self, story_id: u64, id: u64) -> Option<(u64, Vec<u64>)> {
+        if !self.read.insert(id) {
+            return None;
+        }
+        let done = self.unsaved.take_if(|(story, _)| *story != story_id);
+        let (_, ids) = self.unsaved.get_or_insert_with(|| (story_id, Vec::new()));
+        ids.push(id);
+        if done.is_some() {
+            return done;
+        }
+        self.unsaved.take_if(|(_, ids)| ids.len() >= SAVE_BATCH)
+    }
+
+    /// Reads not saved yet, for saving before the thread is left.
+    pub const fn take_unsaved(&mut self) -> Option<(u64, Vec<u64>)> {
+        self.unsaved.take()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::sample_comments;
+
+    #[test]
+    fn reads_are_saved_in_batches_per_story() {
+        let mut reads = CommentReads::default();
+        for id in 1..SAVE_BATCH as u64 {
+            assert_eq!(reads.mark(7, id), None);
+        }
+        assert_eq!(reads.mark(7, 1), None);
+        let (story, ids) = reads.mark(7, 100).unwrap();
+        assert_eq!(story, 7);
+        assert_eq!(ids.len(), SAVE_BATCH);
+
+        reads.mark(7, 200);
+        assert_eq!(reads.mark(8, 300), Some((7, vec![200])));
+        assert_eq!(reads.take_unsaved(), Some((8, vec![300])));
+        assert_eq!(reads.take_unsaved(), None);
+    }
+
+    #[test]
+    fn comments_are_new_once_the_thread_was_read_before() {
+        let comments = sample_comments();
+        let mut reads = CommentReads::default();
+        reads.settle(&comments);
+        assert!(!reads.is_new(comments[1].id));
+
+        reads.load([comments[0].id]);
+        reads.settle(&comments);
+        assert!(reads.was_seen(comments[0].id));
+        assert!(!reads.is_new(comments[0].id));
+        assert!(reads.is_new(comments[1].id));
+
+        // Reading doesn't hide it or its mark until the thread settles again
+        reads.mark(1, comments[1].id);
+        assert!(!reads.was_seen(comments[1].id));
+        assert!(reads.is_new(comments[1].id));
+        reads.settle(&comments);
+        assert!(!reads.is_new(comments[1].id));
+    }
+}
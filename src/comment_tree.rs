@@ -3,19 +3,79 @@
 //! Handles expansion state and visibility calculation for a flat list of comments
 //! with depth information.
 
-use std::collections::HashSet;
+use std::cmp::Reverse;
+use std::collections::{HashMap, HashSet};
+use std::ops::Range;
+use std::sync::atomic::{AtomicU64, Ordering};
 
 use crate::api::Comment;
 
+/// Order of replies under each comment, and of the top-level comments.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CommentSort {
+    /// The order HN ranks them in.
+    #[default]
+    Hn,
+    NewestFirst,
+    OldestFirst,
+    /// Comments with the most replies below them first.
+    MostReplies,
+}
+
+impl CommentSort {
+    pub const fn next(self) -> Self {
+        match self {
+            Self::Hn => Self::NewestFirst,
+            Self::NewestFirst => Self::OldestFirst,
+            Self::OldestFirst => Self::MostReplies,
+            Self::MostReplies => Self::Hn,
+        }
+    }
+
+    pub const fn label(self) -> &'static str {
+        match self {
+            Self::Hn => "hn",
+            Self::NewestFirst => "newest",
+            Self::OldestFirst => "oldest",
+            Self::MostReplies => "replies",
+        }
+    }
+
+    pub fn from_label(label: &str) -> Option<Self> {
+        [
+            Self::Hn,
+            Self::NewestFirst,
+            Self::OldestFirst,
+            Self::MostReplies,
+        ]
+        .into_iter()
+        .find(|sort| sort.label() == label)
+    }
+}
+
+/// Source of tree revisions. They're unique across trees, so a tree put back
+/// from history never looks like the one it replaced.
+static REVISIONS: AtomicU64 = AtomicU64::new(1);
+
+fn next_revision() -> u64 {
+    REVISIONS.fetch_add(1, Ordering::Relaxed)
+}
+
 /// Manages a comment tree's expansion state and visibility.
 ///
 /// Comments are stored as a flat list with depth information. The `CommentTree`
 /// tracks which comments are expanded and computes which comments should be visible
 /// based on their ancestors' expansion state.
-#[derive(Debug, Default)]
+#[derive(Debug, Clone, Default)]
 pub struct CommentTree {
     comments: Vec<Comment>,
     expanded: HashSet<u64>,
+    sort: CommentSort,
+    /// Each comment's place in the order HN sent them, to sort back to.
+    hn_order: HashMap<u64, usize>,
+    /// Changes whenever the comments or their order do, for caches keyed
+    /// by comment position.
+    revision: u64,
 }
 
 impl CommentTree {
@@ -23,16 +83,41 @@ impl CommentTree {
         Self::default()
     }
 
-    /// Replace the comment list and expand all comments.
+    /// Replace the comment list, in HN order, and expand all comments. They
+    /// keep the tree's current sort.
     pub fn set(&mut self, comments: Vec<Comment>) {
+        self.hn_order = comments
+            .iter()
+            .enumerate()
+            .map(|(i, c)| (c.id, i))
+            .collect();
         self.comments = comments;
         self.expand_all();
+        self.set_sort(self.sort);
     }
 
     /// Clear all comments and expansion state.
     pub fn clear(&mut self) {
         self.comments.clear();
         self.expanded.clear();
+        self.hn_order.clear();
+        self.revision = next_revision();
+    }
+
+    pub const fn sort(&self) -> CommentSort {
+        self.sort
+    }
+
+    pub const fn revision(&self) -> u64 {
+        self.revision
+    }
+
+    /// Reorder siblings at every level, each keeping its replies below it.
+    pub fn set_sort(&mut self, sort: CommentSort) {
+        self.sort = sort;
+        let comments = std::mem::take(&mut self.comments);
+        self.comments = sort_siblings(comments, sort, &self.hn_order);
+        self.revision = next_revision();
     }
 
     /// Get the underlying comments slice.
@@ -95,6 +180,17 @@ impl CommentTree {
         visible
     }
 
+    /// Indices of all comments in the order they were posted, oldest first,
+    /// for reading the thread as a timeline.
+    pub fn chronological_indices(&self) -> Vec<usize> {
+        let mut indices: Vec<usize> = (0..self.comments.len()).collect();
+        indices.sort_by_key(|&i| {
+            let comment = &self.comments[i];
+            (comment.time, self.hn_order.get(&comment.id).copied())
+        });
+        indices
+    }
+
     /// Number of currently visible comments.
     pub fn visible_count(&self) -> usize {
         self.visible_indices().len()
@@ -149,6 +245,44 @@ impl CommentTree {
         }
     }
 
+    /// Expand every ancestor of a comment so that it becomes visible.
+    ///
+    /// `index` is the actual index in the flat comment list.
+    pub fn reveal(&mut self, index: usize) {
+        let Some(mut depth) = self.comments.get(index).map(|c| c.depth) else {
+            return;
+        };
+        let mut ancestors = Vec::new();
+        for comment in self.comments[..index].iter().rev() {
+            if depth == 0 {
+                break;
+            }
+            if comment.depth < depth {
+                depth = comment.depth;
+                ancestors.push(comment.id);
+            }
+        }
+        for id in ancestors {
+            self.expand(id);
+        }
+    }
+
+    /// Ids of the comments whose replies are hidden, in thread order.
+    pub fn collapsed(&self) -> Vec<u64> {
+        self.comments
+            .iter()
+            .filter(|c| !c.kids.is_empty() && !self.expanded.contains(&c.id))
+            .map(|c| c.id)
+            .collect()
+    }
+
+    /// Collapse the comments in `ids`, such as those from [`Self::collapsed`].
+    pub fn collapse_each(&mut self, ids: &[u64]) {
+        for id in ids {
+            self.expanded.remove(id);
+        }
+    }
+
     /// Expand all comments that have children.
     pub fn expand_all(&mut self) {
         for comment in &self.comments {
@@ -190,31 +324,107 @@ impl CommentTree {
         None
     }
 
+    /// The comment `index` replies to, or `None` for top-level comments.
+    pub fn parent(&self, index: usize) -> Option<&Comment> {
+        self.parent_index(index).map(|i| &self.comments[i])
+    }
+
+    fn parent_index(&self, index: usize) -> Option<usize> {
+        let depth = self.comments.get(index)?.depth;
+        // Top-level comments have no parent to look back for
+        if depth == 0 {
+            return None;
+        }
+        self.comments[..index].iter().rposition(|c| c.depth < depth)
+    }
+
+    /// Actual indices of the comments `index` is nested under, top-level
+    /// first.
+    pub fn ancestors(&self, index: usize) -> Vec<usize> {
+        let mut ancestors: Vec<usize> =
+            std::iter::successors(self.parent_index(index), |&i| self.parent_index(i)).collect();
+        ancestors.reverse();
+        ancestors
+    }
+
+    /// Actual indices of a comment and all its replies.
+    pub fn subtree(&self, index: usize) -> Range<usize> {
+        let Some(depth) = self.comments.get(index).map(|c| c.depth) else {
+            return index..index;
+        };
+        let end = self.comments[index + 1..]
+            .iter()
+            .position(|c| c.depth <= depth)
+            .map_or(self.comments.len(), |n| index + 1 + n);
+        index..end
+    }
+
+    /// For each comment, whether it or one of its replies matches, so the
+    /// matches can be shown with the comments they're nested under.
+    pub fn leading_to(&self, matches: impl Fn(&Comment) -> bool) -> Vec<bool> {
+        let mut keep = vec![false; self.comments.len()];
+        let mut path: Vec<usize> = Vec::new();
+        for (i, comment) in self.comments.iter().enumerate() {
+            path.truncate(comment.depth);
+            if matches(comment) {
+                keep[i] = true;
+                // Ancestors kept already have theirs kept too
+                let unkept = path.iter().rev().take_while(|&&a| !keep[a]).count();
+                for &ancestor in &path[path.len() - unkept..] {
+                    keep[ancestor] = true;
+                }
+            }
+            path.push(i);
+        }
+        keep
+    }
+
     /// Find the visible index of the parent comment.
     ///
-    /// Walks backward through visible comments to find a comment at a lower depth.
+    /// `visible_indices` may be in any order, such as the timeline's.
     pub fn find_parent_visible_index(
         &self,
         visible_indices: &[usize],
         visible_index: usize,
     ) -> Option<usize> {
         let actual_idx = visible_indices.get(visible_index).copied()?;
-        let current_depth = self.comments.get(actual_idx)?.depth;
-
-        if current_depth == 0 {
-            return None;
-        }
+        let parent = self.parent_index(actual_idx)?;
+        visible_indices.iter().position(|&i| i == parent)
+    }
+}
 
-        for i in (0..visible_index).rev() {
-            if let Some(&actual) = visible_indices.get(i)
-                && self.comments[actual].depth < current_depth
-            {
-                return Some(i);
-            }
+/// Sort a run of sibling subtrees, flattened in depth-first order, and the
+/// replies within each.
+fn sort_siblings(
+    comments: Vec<Comment>,
+    sort: CommentSort,
+    hn_order: &HashMap<u64, usize>,
+) -> Vec<Comment> {
+    let Some(depth) = comments.first().map(|c| c.depth) else {
+        return comments;
+    };
+    let mut subtrees: Vec<Vec<Comment>> = Vec::new();
+    for comment in comments {
+        match subtrees.last_mut() {
+            Some(subtree) if comment.depth > depth => subtree.push(comment),
+            _ => subtrees.push(vec![comment]),
         }
-
-        None
     }
+    let position = |subtree: &[Comment]| hn_order.get(&subtree[0].id).copied();
+    match sort {
+        CommentSort::Hn => subtrees.sort_by_key(|t| position(t)),
+        CommentSort::NewestFirst => subtrees.sort_by_key(|t| (Reverse(t[0].time), position(t))),
+        CommentSort::OldestFirst => subtrees.sort_by_key(|t| (t[0].time, position(t))),
+        CommentSort::MostReplies => subtrees.sort_by_key(|t| (Reverse(t.len()), position(t))),
+    }
+    subtrees
+        .into_iter()
+        .flat_map(|mut subtree| {
+            let replies = subtree.split_off(1);
+            subtree.extend(sort_siblings(replies, sort, hn_order));
+            subtree
+        })
+        .collect()
 }
 
 #[cfg(test)]
@@ -342,6 +552,19 @@ mod tests {
         assert!(tree.is_expanded(5)); // Comment 5 not affected
     }
 
+    #[test]
+    fn test_reveal_expands_ancestors_only() {
+        let mut tree = CommentTree::new();
+        tree.set(sample_tree());
+        tree.collapse_all();
+        tree.reveal(2); // Comment 4, under 1 -> 2
+
+        assert!(tree.is_expanded(1));
+        assert!(tree.is_expanded(2));
+        assert!(!tree.is_expanded(5));
+        assert_eq!(tree.visible_indices(), vec![0, 1, 2, 3, 4]);
+    }
+
     #[test]
     fn test_expand_all() {
         let mut tree = CommentTree::new();
@@ -413,6 +636,106 @@ mod tests {
         assert_eq!(result, None);
     }
 
+    #[test]
+    fn test_parent() {
+        let mut tree = CommentTree::new();
+        tree.set(sample_tree());
+
+        assert!(tree.parent(0).is_none());
+        assert_eq!(tree.parent(2).map(|c| c.id), Some(2));
+        assert_eq!(tree.parent(3).map(|c| c.id), Some(1));
+    }
+
+    #[test]
+    fn test_sort_keeps_replies_under_their_parent() {
+        let ids = |tree: &CommentTree| tree.comments().iter().map(|c| c.id).collect::<Vec<_>>();
+        let mut comments = sample_tree();
+        for (comment, time) in comments.iter_mut().zip([10, 30, 40, 20, 50, 60]) {
+            comment.time = time;
+        }
+        let mut tree = CommentTree::new();
+        tree.set(comments.clone());
+
+        tree.set_sort(CommentSort::NewestFirst);
+        assert_eq!(ids(&tree), vec![5, 6, 1, 2, 4, 3]);
+        tree.set_sort(CommentSort::OldestFirst);
+        assert_eq!(ids(&tree), vec![1, 3, 2, 4, 5, 6]);
+        tree.set_sort(CommentSort::MostReplies);
+        assert_eq!(ids(&tree), vec![1, 2, 4, 3, 5, 6]);
+        tree.set_sort(CommentSort::Hn);
+        assert_eq!(ids(&tree), vec![1, 2, 4, 3, 5, 6]);
+
+        // New comments keep the sort
+        tree.set_sort(CommentSort::NewestFirst);
+        tree.set(comments);
+        assert_eq!(ids(&tree), vec![5, 6, 1, 2, 4, 3]);
+    }
+
+    #[test]
+    fn test_sort_labels_round_trip() {
+        let mut sort = CommentSort::Hn;
+        for _ in 0..4 {
+            assert_eq!(CommentSort::from_label(sort.label()), Some(sort));
+            sort = sort.next();
+        }
+        assert_eq!(sort, CommentSort::Hn);
+        assert_eq!(CommentSort::from_label("best"), None);
+    }
+
+    #[test]
+    fn test_chronological_indices_follow_time() {
+        let mut comments = sample_tree();
+        for (comment, time) in comments.iter_mut().zip([10, 30, 40, 20, 50, 30]) {
+            comment.time = time;
+        }
+        let mut tree = CommentTree::new();
+        tree.set(comments);
+        tree.collapse_all();
+
+        // Ties keep HN's order, and collapsed replies are included
+        let timeline = tree.chronological_indices();
+        assert_eq!(timeline, vec![0, 3, 1, 5, 2, 4]);
+        // Comment 4 replies to comment 2, earlier in the timeline
+        assert_eq!(tree.find_parent_visible_index(&timeline, 4), Some(2));
+        assert_eq!(tree.find_parent_visible_index(&timeline, 0), None);
+    }
+
+    #[test]
+    fn test_ancestors_and_subtree() {
+        let mut tree = CommentTree::new();
+        tree.set(sample_tree());
+
+        assert_eq!(tree.ancestors(2), vec![0, 1]);
+        assert!(tree.ancestors(4).is_empty());
+        assert_eq!(tree.subtree(0), 0..4);
+        assert_eq!(tree.subtree(1), 1..3);
+        assert_eq!(tree.subtree(4), 4..6);
+        assert_eq!(tree.subtree(5), 5..6);
+    }
+
+    #[test]
+    fn test_leading_to_keeps_ancestors_of_matches() {
+        let mut tree = CommentTree::new();
+        tree.set(sample_tree());
+
+        let keep = tree.leading_to(|c| c.id == 4 || c.id == 6);
+        assert_eq!(keep, vec![true, true, true, false, true, true]);
+        assert_eq!(tree.leading_to(|_| false), vec![false; 6]);
+    }
+
+    #[test]
+    fn test_collapsed_lists_hidden_replies() {
+        let mut tree = CommentTree::new();
+        tree.set(sample_tree());
+        assert!(tree.collapsed().is_empty());
+
+        tree.collapse(5);
+        tree.collapse(2);
+        // Comment 3 has no replies to hide
+        tree.collapse(3);
+        assert_eq!(tree.collapsed(), vec![2, 5]);
+    }
+
     #[test]
     fn test_visible_count() {
         let mut tree = CommentTree::new();
@@ -1,7 +1,9 @@
 //! Dynamic help text generation from keymaps.
 
 use crate::app::Message;
-use crate::keys::{Keymap, format_key};
+use crate::hints::HintAction;
+use crate::keys::{Keymap, format_keys};
+use crate::search::SearchDirection;
 
 /// A single help item representing one or more related actions.
 pub struct HelpItem {
@@ -34,11 +36,7 @@ impl HelpItem {
         let keys: Vec<String> = self
             .messages
             .iter()
-            .filter_map(|msg| {
-                keymap
-                    .find_key(msg)
-                    .map(|(code, mods)| format_key(code, mods))
-            })
+            .filter_map(|msg| keymap.find_key(msg).map(format_keys))
             .collect();
         if keys.is_empty() {
             return None;
@@ -52,11 +50,7 @@ impl HelpItem {
         let keys: Vec<String> = self
             .messages
             .iter()
-            .filter_map(|msg| {
-                keymap
-                    .find_key(msg)
-                    .map(|(code, mods)| format_key(code, mods))
-            })
+            .filter_map(|msg| keymap.find_key(msg).map(format_keys))
             .collect();
         if keys.is_empty() {
             return None;
@@ -92,9 +86,9 @@ impl HelpConfig {
 /// Help configuration for the stories view.
 pub fn stories_help() -> HelpConfig {
     use Message::{
-        CopyUrl, CycleSortOrder, NextFeed, OpenComments, OpenHnPage, OpenThemePicker, OpenUrl,
-        PrevFeed, Quit, Refresh, SelectFirst, SelectLast, SelectNext, SelectPrev, ToggleDebug,
-        ToggleFavorite, ToggleHelp,
+        Back, CopyUrl, CycleSortOrder, Forward, NextFeed, OpenComments, OpenHnPage, OpenSearch,
+        OpenThemePicker, OpenUrl, PrevFeed, Quit, Refresh, SearchNext, SearchPrev, SelectFirst,
+        SelectLast, SelectNext, SelectPrev, SwitchPane, ToggleDebug, ToggleFavorite, ToggleHelp,
     };
     HelpConfig {
         expanded: vec![
@@ -105,8 +99,12 @@ pub fn stories_help() -> HelpConfig {
             HelpItem::new(OpenHnPage, "open on hn"),
             HelpItem::new(CopyUrl, "copy"),
             HelpItem::new(OpenComments, "comments"),
+            HelpItem::new(SwitchPane, "pane"),
+            HelpItem::pair(Back, Forward, "history"),
             HelpItem::new(ToggleFavorite, "fav"),
             HelpItem::new(CycleSortOrder, "sort"),
+            HelpItem::new(OpenSearch(SearchDirection::Forward), "search"),
+            HelpItem::pair(SearchNext, SearchPrev, "matches"),
             HelpItem::new(Refresh, "refresh"),
             HelpItem::new(OpenThemePicker, "themes"),
             HelpItem::new(ToggleDebug, "debug"),
@@ -127,9 +125,12 @@ pub fn stories_help() -> HelpConfig {
 pub fn comments_help() -> HelpConfig {
     use Message::{
         Back, CollapseComment, CollapseSubtree, CollapseThread, CopyStoryUrl, CopyUrl,
-        ExpandComment, ExpandSubtree, ExpandThread, GoToParent, OpenStoryUrl, OpenThemePicker,
-        OpenUrl, Quit, Refresh, SelectNext, SelectPrev, ToggleDebug, ToggleFavorite, ToggleHelp,
-        ToggleStoryFavorite,
+        CycleCodeBlock, CycleCommentSort, ExpandComment, ExpandSubtree, ExpandThread, Forward,
+        GoToParent, NextByOp, NextBySameAuthor, NextTopLevel, NextUnread, OpenLink, OpenSearch,
+        OpenStoryUrl, OpenThemePicker, OpenUrl, PrevTopLevel, Quit, Refresh, ResetCommentView,
+        SearchNext, SearchPrev, SelectNext, SelectPrev, ShowHints, SwitchPane, ToggleAncestors,
+        ToggleDebug, ToggleFavorite, ToggleFlatComments, ToggleHelp, ToggleStoryFavorite,
+        ToggleUnreadOnly, ZoomComment,
     };
     HelpConfig {
         expanded: vec![
@@ -137,14 +138,30 @@ pub fn comments_help() -> HelpConfig {
             HelpItem::pair(ExpandComment, CollapseComment, "expand"),
             HelpItem::pair(ExpandSubtree, CollapseSubtree, "subtree"),
             HelpItem::pair(ExpandThread, CollapseThread, "thread"),
+            HelpItem::new(ResetCommentView, "reset"),
             HelpItem::new(GoToParent, "parent"),
+            HelpItem::pair(NextTopLevel, PrevTopLevel, "top-level"),
+            HelpItem::new(NextByOp, "op"),
+            HelpItem::new(NextBySameAuthor, "author"),
             HelpItem::new(OpenUrl, "link"),
+            HelpItem::new(OpenLink, "follow"),
+            HelpItem::new(ShowHints(HintAction::Open), "hints"),
+            HelpItem::new(CycleCodeBlock, "code"),
+            HelpItem::new(CycleCommentSort, "sort"),
+            HelpItem::new(ToggleFlatComments, "timeline"),
+            HelpItem::new(ZoomComment, "zoom"),
+            HelpItem::new(ToggleAncestors, "context"),
+            HelpItem::pair(NextUnread, ToggleUnreadOnly, "unread"),
             HelpItem::new(OpenStoryUrl, "story"),
             HelpItem::new(CopyUrl, "copy"),
             HelpItem::new(CopyStoryUrl, "copy story"),
             HelpItem::new(ToggleFavorite, "fav"),
             HelpItem::new(ToggleStoryFavorite, "fav story"),
             HelpItem::new(Back, "back"),
+            HelpItem::new(Forward, "forward"),
+            HelpItem::new(SwitchPane, "pane"),
+            HelpItem::new(OpenSearch(SearchDirection::Forward), "search"),
+            HelpItem::pair(SearchNext, SearchPrev, "matches"),
             HelpItem::new(Refresh, "refresh"),
             HelpItem::new(OpenThemePicker, "themes"),
             HelpItem::new(ToggleDebug, "debug"),
@@ -163,6 +180,41 @@ pub fn comments_help() -> HelpConfig {
     }
 }
 
+/// Help configuration for the user profile view.
+pub fn user_help() -> HelpConfig {
+    use Message::{Back, CopyUrl, Forward, OpenUrl, Quit, Refresh, ToggleHelp};
+    HelpConfig {
+        expanded: vec![
+            HelpItem::new(OpenUrl, "open on hn"),
+            HelpItem::new(CopyUrl, "copy"),
+            HelpItem::pair(Back, Forward, "history"),
+            HelpItem::new(Refresh, "refresh"),
+            HelpItem::new(Quit, "quit"),
+            HelpItem::new(ToggleHelp, "hide"),
+        ],
+        compact: vec![
+            HelpItem::new(OpenUrl, "open on hn"),
+            HelpItem::new(Back, "back"),
+            HelpItem::new(ToggleHelp, "help"),
+        ],
+    }
+}
+
+/// Help configuration while picking a link hint.
+pub fn hints_help() -> HelpConfig {
+    use Message::{CancelHints, HintBackspace};
+    HelpConfig {
+        expanded: vec![
+            HelpItem::new(HintBackspace, "undo"),
+            HelpItem::new(CancelHints, "cancel"),
+        ],
+        compact: vec![
+            HelpItem::new(HintBackspace, "undo"),
+            HelpItem::new(CancelHints, "cancel"),
+        ],
+    }
+}
+
 /// Help configuration for the theme picker.
 pub fn theme_picker_help() -> HelpConfig {
     use Message::{CloseThemePicker, ConfirmThemePicker, ThemePickerDown, ThemePickerUp};
@@ -200,9 +252,9 @@ pub fn context_menu_help() -> HelpConfig {
 /// Help items for the stories view overlay.
 pub fn stories_overlay_items() -> Vec<HelpItem> {
     use Message::{
-        CopyUrl, CycleSortOrder, NextFeed, OpenComments, OpenHnPage, OpenThemePicker, OpenUrl,
-        PrevFeed, Quit, Refresh, SelectFirst, SelectLast, SelectNext, SelectPrev, ToggleDebug,
-        ToggleFavorite, ToggleHelp,
+        CopyUrl, CycleSortOrder, NextFeed, OpenCommandLine, OpenComments, OpenHnPage, OpenSearch,
+        OpenThemePicker, OpenUrl, PrevFeed, Quit, Refresh, SearchNext, SearchPrev, SelectFirst,
+        SelectLast, SelectNext, SelectPrev, ToggleDebug, ToggleFavorite, ToggleHelp,
     };
     vec![
         HelpItem::pair(SelectNext, SelectPrev, "navigate"),
@@ -214,7 +266,10 @@ pub fn stories_overlay_items() -> Vec<HelpItem> {
         HelpItem::new(CopyUrl, "copy url"),
         HelpItem::new(ToggleFavorite, "favorite"),
         HelpItem::new(CycleSortOrder, "sort"),
+        HelpItem::new(OpenSearch(SearchDirection::Forward), "search"),
+        HelpItem::pair(SearchNext, SearchPrev, "next/prev match"),
         HelpItem::new(Refresh, "refresh"),
+        HelpItem::new(OpenCommandLine, "command line"),
         HelpItem::new(OpenThemePicker, "themes"),
         HelpItem::new(ToggleDebug, "debug"),
         HelpItem::new(Quit, "quit"),
@@ -226,24 +281,66 @@ pub fn stories_overlay_items() -> Vec<HelpItem> {
 pub fn comments_overlay_items() -> Vec<HelpItem> {
     use Message::{
         Back, CollapseComment, CollapseSubtree, CollapseThread, CopyStoryUrl, CopyUrl,
-        ExpandComment, ExpandSubtree, ExpandThread, GoToParent, OpenStoryUrl, OpenThemePicker,
-        OpenUrl, Quit, Refresh, SelectNext, SelectPrev, ToggleDebug, ToggleFavorite, ToggleHelp,
-        ToggleStoryFavorite,
+        CycleCodeBlock, CycleCommentSort, ExpandComment, ExpandSubtree, ExpandThread, GoToParent,
+        NextByOp, NextBySameAuthor, NextTopLevel, NextUnread, OpenCommandLine, OpenLink,
+        OpenSearch, OpenStoryUrl, OpenThemePicker, OpenUrl, PrevTopLevel, Quit, Refresh,
+        ResetCommentView, SearchNext, SearchPrev, SelectNext, SelectPrev, ShowHints,
+        ToggleAncestors, ToggleDebug, ToggleFavorite, ToggleFlatComments, ToggleHelp,
+        ToggleStoryFavorite, ToggleUnreadOnly, ZoomComment,
     };
     vec![
         HelpItem::pair(SelectNext, SelectPrev, "navigate"),
         HelpItem::pair(ExpandComment, CollapseComment, "expand/collapse"),
         HelpItem::pair(ExpandSubtree, CollapseSubtree, "subtree"),
         HelpItem::pair(ExpandThread, CollapseThread, "all comments"),
+        HelpItem::new(ResetCommentView, "forget where it was left"),
         HelpItem::new(GoToParent, "go to parent"),
+        HelpItem::pair(NextTopLevel, PrevTopLevel, "next/prev top-level"),
+        HelpItem::pair(NextByOp, NextBySameAuthor, "next by op/same author"),
         HelpItem::new(OpenUrl, "open comment link"),
+        HelpItem::new(OpenLink, "follow link in comment"),
+        HelpItem::pair(
+            ShowHints(HintAction::Open),
+            ShowHints(HintAction::Copy),
+            "pick a link to open/copy",
+        ),
+        HelpItem::new(ShowHints(HintAction::Preview), "pick a link to preview"),
+        HelpItem::new(CycleCodeBlock, "pick a code block"),
+        HelpItem::new(CycleCommentSort, "sort comments"),
+        HelpItem::new(ToggleFlatComments, "timeline/tree"),
+        HelpItem::new(ZoomComment, "zoom into replies"),
+        HelpItem::new(ToggleAncestors, "show what it replies to"),
+        HelpItem::pair(NextUnread, ToggleUnreadOnly, "next unread/unread only"),
         HelpItem::new(OpenStoryUrl, "open story link"),
-        HelpItem::new(CopyUrl, "copy url"),
+        HelpItem::new(CopyUrl, "copy url or code block"),
         HelpItem::new(CopyStoryUrl, "copy story url"),
         HelpItem::new(ToggleFavorite, "favorite comment"),
         HelpItem::new(ToggleStoryFavorite, "favorite story"),
         HelpItem::new(Back, "back to stories"),
+        HelpItem::new(OpenSearch(SearchDirection::Forward), "search"),
+        HelpItem::pair(SearchNext, SearchPrev, "next/prev match"),
+        HelpItem::new(Refresh, "refresh"),
+        HelpItem::new(OpenCommandLine, "command line"),
+        HelpItem::new(OpenThemePicker, "themes"),
+        HelpItem::new(ToggleDebug, "debug"),
+        HelpItem::new(Quit, "quit"),
+        HelpItem::new(ToggleHelp, "close"),
+    ]
+}
+
+/// Help items for the user profile overlay.
+pub fn user_overlay_items() -> Vec<HelpItem> {
+    use Message::{
+        Back, CopyUrl, Forward, OpenCommandLine, OpenThemePicker, OpenUrl, Quit, Refresh,
+        ToggleDebug, ToggleHelp,
+    };
+    vec![
+        HelpItem::new(OpenUrl, "open on hn"),
+        HelpItem::new(CopyUrl, "copy url"),
+        HelpItem::new(Back, "back"),
+        HelpItem::new(Forward, "forward"),
         HelpItem::new(Refresh, "refresh"),
+        HelpItem::new(OpenCommandLine, "command line"),
         HelpItem::new(OpenThemePicker, "themes"),
         HelpItem::new(ToggleDebug, "debug"),
         HelpItem::new(Quit, "quit"),
@@ -261,7 +358,7 @@ mod tests {
         let keymap = global_keymap().extend(stories_keymap());
         let help = stories_help().format(&keymap, true);
         assert!(help.contains("j/k:nav"));
-        assert!(help.contains("g/G:top/bottom"));
+        assert!(help.contains("gg/G:top/bottom"));
         assert!(help.contains("H/L:feeds"));
         assert!(help.contains("l:comments"));
         assert!(help.contains("q:quit"));
@@ -274,7 +371,7 @@ mod tests {
         let compact = stories_help().format(&keymap, false);
         assert!(compact.len() < expanded.len());
         assert!(compact.contains("H/L:feeds"));
-        assert!(compact.contains("?:help"));
+        assert!(compact.contains("F1:help"));
     }
 
     #[test]
@@ -0,0 +1,168 @@
+//! User-defined highlight rules for stories and comments.
+//!
+//! Rules are declared in `settings.toml` and make matching story titles,
+//! domains, or authors stand out using the theme's highlight style:
+//!
+//! ```toml
+//! [[highlights]]
+//! title = "rust"
+//!
+//! [[highlights]]
+//! domain = "lwn.net"
+//!
+//! [[highlights]]
+//! author = "dang"
+//! ```
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HighlightRule {
+    /// Case-insensitive whole-word match against the story title.
+    Title(String),
+    /// Matches the story domain or any of its subdomains.
+    Domain(String),
+    /// Case-insensitive match against the story or comment author.
+    Author(String),
+}
+
+impl HighlightRule {
+    fn matches_title(&self, title: &str) -> bool {
+        match self {
+            Self::Title(keyword) => contains_word(title, keyword),
+            _ => false,
+        }
+    }
+
+    fn matches_domain(&self, domain: &str) -> bool {
+        match self {
+            Self::Domain(pattern) => {
+                let pattern = pattern.trim_start_matches("www.");
+                domain.eq_ignore_ascii_case(pattern)
+                    || domain
+                        .to_ascii_lowercase()
+                        .ends_with(&format!(".{}", pattern.to_ascii_lowercase()))
+            }
+            _ => false,
+        }
+    }
+
+    fn matches_author(&self, author: &str) -> bool {
+        match self {
+            Self::Author(name) => author.eq_ignore_ascii_case(name),
+            _ => false,
+        }
+    }
+}
+
+/// The active set of highlight rules.
+#[derive(Debug, Clone, Default)]
+pub struct Highlights {
+    rules: Vec<HighlightRule>,
+}
+
+impl Highlights {
+    pub const fn new(rules: Vec<HighlightRule>) -> Self {
+        Self { rules }
+    }
+
+    pub fn title(&self, title: &str) -> bool {
+        self.rules.iter().any(|r| r.matches_title(title))
+    }
+
+    pub fn domain(&self, domain: &str) -> bool {
+        self.rules.iter().any(|r| r.matches_domain(domain))
+    }
+
+    pub fn author(&self, author: &str) -> bool {
+        self.rules.iter().any(|r| r.matches_author(author))
+    }
+}
+
+/// Returns true if `keyword` appears in `text` on word boundaries, ignoring case.
+/// "rust" matches "Rust 1.80 released" but not "Trust issues".
+fn contains_word(text: &str, keyword: &str) -> bool {
+    let keyword = keyword.trim().to_lowercase();
+    if keyword.is_empty() {
+        return false;
+    }
+    let text = text.to_lowercase();
+    let is_word_char = |c: char| c.is_alphanumeric() || c == '_';
+    text.match_indices(&keyword).any(|(start, matched)| {
+        let end = start + matched.len();
+        let before_ok = text[..start]
+            .chars()
+            .next_back()
+            .is_none_or(|c| !is_word_char(c));
+        let after_ok = text[end..].chars().next().is_none_or(|c| !is_word_char(c));
+        before_ok && after_ok
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn highlights() -> Highlights {
+        Highlights::new(vec![
+            HighlightRule::Title("rust".to_string()),
+            HighlightRule::Title("large language model".to_string()),
+            HighlightRule::Domain("github.com".to_string()),
+            HighlightRule::Author("dang".to_string()),
+        ])
+    }
+
+    #[test]
+    fn title_matches_whole_words_ignoring_case() {
+        let h = highlights();
+        assert!(h.title("Rust 1.80 released"));
+        assert!(h.title("Why I moved to rust."));
+        assert!(h.title("Running a Large Language Model at home"));
+        assert!(!h.title("Trust issues"));
+        assert!(!h.title("Rusty old cars"));
+    }
+
+    #[test]
+    fn domain_matches_subdomains() {
+        let h = highlights();
+        assert!(h.domain("github.com"));
+        assert!(h.domain("gist.github.com"));
+        assert!(!h.domain("notgithub.com"));
+        assert!(!h.domain("github.com.evil.net"));
+    }
+
+    #[test]
+    fn author_matches_ignoring_case() {
+        let h = highlights();
+        assert!(h.author("dang"));
+        assert!(h.author("Dang"));
+        assert!(!h.author("dangus"));
+    }
+
+    #[test]
+    fn empty_rules_match_nothing() {
+        let h = Highlights::default();
+        assert!(!h.title("Rust"));
+        assert!(!h.domain("github.com"));
+        assert!(!h.author("dang"));
+    }
+
+    #[test]
+    fn rules_deserialize_from_toml() {
+        #[derive(Deserialize)]
+        struct Wrapper {
+            highlights: Vec<HighlightRule>,
+        }
+        let parsed: Wrapper =
+            toml::from_str("[[highlights]]\ntitle = \"rust\"\n\n[[highlights]]\nauthor = \"pg\"\n")
+                .unwrap();
+        assert_eq!(
+            parsed.highlights,
+            vec![
+                HighlightRule::Title("rust".to_string()),
+                HighlightRule::Author("pg".to_string()),
+            ]
+        );
+    }
+}
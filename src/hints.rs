@@ -0,0 +1,163 @@
+//! Link hints: the links on screen get short labels, vimium-style, so any of
+//! them can be opened, copied or previewed by typing its label.
+
+/// Keys labels are made from, easiest to reach first.
+const ALPHABET: &[char] = &[
+    's', 'a', 'd', 'f', 'j', 'k', 'l', 'e', 'w', 'c', 'm', 'p', 'g', 'h',
+];
+
+/// What happens to the link whose label is typed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HintAction {
+    /// Follow it, in the app for HN links.
+    Open,
+    Copy,
+    /// Show the full URL without opening it.
+    Preview,
+}
+
+impl HintAction {
+    pub const fn label(self) -> &'static str {
+        match self {
+            Self::Open => "Open link",
+            Self::Copy => "Copy link",
+            Self::Preview => "Preview link",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Hint {
+    pub label: String,
+    pub comment_id: u64,
+    pub url: String,
+}
+
+/// Result of typing a key in hint mode.
+#[derive(Debug, PartialEq, Eq)]
+pub enum HintMatch {
+    /// A label was completed.
+    Chosen(String),
+    /// Several labels still start with what's been typed.
+    Pending,
+    /// No label starts with what's been typed.
+    None,
+}
+
+/// Links labelled for picking, and the part of a label typed so far.
+#[derive(Debug)]
+pub struct Hints {
+    pub action: HintAction,
+    pub links: Vec<Hint>,
+    pub typed: String,
+}
+
+impl Hints {
+    /// Label `urls`, given with their comment's id in screen order.
+    pub fn new(action: HintAction, urls: Vec<(u64, String)>) -> Self {
+        let links = labels(urls.len())
+            .into_iter()
+            .zip(urls)
+            .map(|(label, (comment_id, url))| Hint {
+                label,
+                comment_id,
+                url,
+            })
+            .collect();
+        Self {
+            action,
+            links,
+            typed: String::new(),
+        }
+    }
+
+    /// Labels for the links in a comment, in order. Links whose label no
+    /// longer matches what's been typed get `None`.
+    pub fn labels_for(&self, comment_id: u64) -> Vec<Option<&str>> {
+        self.links
+            .iter()
+            .filter(|hint| hint.comment_id == comment_id)
+            .map(|hint| {
+                hint.label
+                    .starts_with(&self.typed)
+                    .then_some(hint.label.as_str())
+            })
+            .collect()
+    }
+
+    /// Narrow the hints down by a typed key.
+    pub fn narrow(&mut self, c: char) -> HintMatch {
+        self.typed.push(c);
+        let mut matching = self
+            .links
+            .iter()
+            .filter(|hint| hint.label.starts_with(&self.typed));
+        match (matching.next(), matching.next()) {
+            (None, _) => HintMatch::None,
+            (Some(hint), None) if hint.label == self.typed => HintMatch::Chosen(hint.url.clone()),
+            _ => HintMatch::Pending,
+        }
+    }
+}
+
+/// `count` labels of equal length, so none is a prefix of another. Only as
+/// many links as there are two-key labels can be picked.
+fn labels(count: usize) -> Vec<String> {
+    if count <= ALPHABET.len() {
+        return ALPHABET.iter().take(count).map(char::to_string).collect();
+    }
+    ALPHABET
+        .iter()
+        .flat_map(|a| ALPHABET.iter().map(move |b| format!("{a}{b}")))
+        .take(count)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn links(count: u64) -> Vec<(u64, String)> {
+        (0..count)
+            .map(|i| (i / 2, format!("https://example.com/{i}")))
+            .collect()
+    }
+
+    #[test]
+    fn few_links_get_single_keys() {
+        assert_eq!(labels(3), vec!["s", "a", "d"]);
+    }
+
+    #[test]
+    fn many_links_get_two_keys_each() {
+        let labels = labels(20);
+        assert_eq!(labels.len(), 20);
+        assert!(labels.iter().all(|label| label.len() == 2));
+        assert_eq!(labels[0], "ss");
+        assert_eq!(labels[14], "as");
+    }
+
+    #[test]
+    fn typing_a_label_chooses_its_link() {
+        let mut hints = Hints::new(HintAction::Open, links(3));
+        assert_eq!(
+            hints.narrow('a'),
+            HintMatch::Chosen("https://example.com/1".to_string())
+        );
+
+        let mut hints = Hints::new(HintAction::Open, links(3));
+        assert_eq!(hints.narrow('x'), HintMatch::None);
+    }
+
+    #[test]
+    fn two_key_labels_narrow_after_the_first_key() {
+        let mut hints = Hints::new(HintAction::Copy, links(20));
+        assert_eq!(hints.narrow('a'), HintMatch::Pending);
+        assert_eq!(hints.labels_for(0), vec![None, None]);
+        assert_eq!(hints.labels_for(7), vec![Some("as"), Some("aa")]);
+        assert_eq!(
+            hints.narrow('a'),
+            HintMatch::Chosen("https://example.com/15".to_string())
+        );
+    }
+}
@@ -0,0 +1,251 @@
+//! OSC 8 hyperlinks: views record where links are drawn, and after each frame
+//! those cells are written again wrapped in the escape that makes the
+//! terminal treat them as a link to the full URL.
+//!
+//! Ratatui measures a cell's symbol to diff frames, so the escapes can't live
+//! in the buffer itself; they go straight to the terminal after the frame.
+
+use std::env;
+use std::io::{self, Write};
+
+use crossterm::{
+    cursor::{RestorePosition, SavePosition},
+    queue,
+};
+use ratatui::{
+    backend::{Backend, CrosstermBackend},
+    buffer::{Buffer, Cell},
+    layout::Rect,
+};
+use unicode_width::UnicodeWidthStr;
+
+/// A link's place in a block of text lines, before it is put on screen.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TextLink {
+    pub line: usize,
+    pub column: usize,
+    pub width: usize,
+    pub url: String,
+}
+
+/// Links recorded while rendering the current frame.
+#[derive(Debug, Default)]
+pub struct LinkAreas {
+    links: Vec<(Rect, String)>,
+}
+
+impl LinkAreas {
+    pub fn clear(&mut self) {
+        self.links.clear();
+    }
+
+    pub fn add(&mut self, area: Rect, url: impl Into<String>) {
+        if !area.is_empty() {
+            self.links.push((area, url.into()));
+        }
+    }
+
+    /// Drop the links under a popup drawn over them.
+    pub fn cover(&mut self, area: Rect) {
+        self.links.retain(|(link, _)| !link.intersects(area));
+    }
+
+    pub fn links(&self) -> &[(Rect, String)] {
+        &self.links
+    }
+}
+
+/// Whether the terminal is likely to understand OSC 8. Most terminals that
+/// don't simply ignore it, but a few print it as text.
+pub fn detected() -> bool {
+    supported_by(
+        env::var("TERM").ok().as_deref(),
+        env::var("TERM_PROGRAM").ok().as_deref(),
+    )
+}
+
+fn supported_by(term: Option<&str>, term_program: Option<&str>) -> bool {
+    let Some(term) = term else {
+        return false;
+    };
+    // GNU screen shares tmux's TERM values but doesn't pass links through
+    let screen = term.starts_with("screen") && term_program != Some("tmux");
+    !matches!(term, "" | "dumb" | "linux") && !screen
+}
+
+/// A link as it was last written to the terminal.
+#[derive(Debug)]
+struct DrawnLink {
+    area: Rect,
+    url: String,
+    cells: Vec<Cell>,
+}
+
+/// Links written by the previous frame, to tell which need writing again.
+#[derive(Debug, Default)]
+pub struct Hyperlinks {
+    screen: Rect,
+    drawn: Vec<DrawnLink>,
+}
+
+impl Hyperlinks {
+    /// Escapes that turn `links` in the frame just drawn into hyperlinks.
+    /// Links already on screen are skipped, and cells of links that are gone
+    /// but whose text stayed put are drawn again without one.
+    pub fn escapes(&mut self, buffer: &Buffer, links: &[(Rect, String)]) -> io::Result<Vec<u8>> {
+        // A resize redraws the whole screen
+        if buffer.area != self.screen {
+            self.screen = buffer.area;
+            self.drawn.clear();
+        }
+        let current: Vec<DrawnLink> = links
+            .iter()
+            .map(|(area, url)| {
+                let area = area.intersection(buffer.area);
+                DrawnLink {
+                    area,
+                    url: url.clone(),
+                    cells: cells(buffer, area)
+                        .map(|(_, _, cell)| cell.clone())
+                        .collect(),
+                }
+            })
+            .collect();
+
+        let mut escapes = Vec::new();
+        let mut out = CrosstermBackend::new(&mut escapes);
+        for old in &self.drawn {
+            let replaced = current.iter().any(|link| link.area == old.area);
+            let unchanged = cells(buffer, old.area)
+                .map(|(_, _, cell)| cell)
+                .eq(&old.cells);
+            if !replaced && unchanged {
+                out.draw(cells(buffer, old.area))?;
+            }
+        }
+        for link in &current {
+            let on_screen = self
+                .drawn
+                .iter()
+                .any(|old| old.area == link.area && old.url == link.url && old.cells == link.cells);
+            if !on_screen {
+                write!(out, "\x1b]8;;{}\x1b\\", sanitize(&link.url))?;
+                out.draw(cells(buffer, link.area))?;
+                write!(out, "\x1b]8;;\x1b\\")?;
+            }
+        }
+        self.drawn = current;
+
+        if !escapes.is_empty() {
+            // Leave the cursor where the frame put it, for the prompts
+            let mut wrapped = Vec::with_capacity(escapes.len() + 4);
+            queue!(wrapped, SavePosition)?;
+            wrapped.append(&mut escapes);
+            queue!(wrapped, RestorePosition)?;
+            escapes = wrapped;
+        }
+        Ok(escapes)
+    }
+}
+
+/// The cells in `area` that start a symbol, skipping those covered by wide
+/// characters, the way the frame diff does.
+fn cells(buffer: &Buffer, area: Rect) -> impl Iterator<Item = (u16, u16, &Cell)> {
+    area.rows().flat_map(move |row| {
+        let mut covered = 0;
+        row.positions().filter_map(move |pos| {
+            let cell = &buffer[pos];
+            if covered > 0 {
+                covered -= 1;
+                return None;
+            }
+            covered = cell.symbol().width().saturating_sub(1);
+            Some((pos.x, pos.y, cell))
+        })
+    })
+}
+
+/// Control characters would end the escape early.
+fn sanitize(url: &str) -> String {
+    url.chars().filter(|c| !c.is_control()).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::text::Line;
+
+    const URL: &str = "https://example.com/article";
+
+    fn frame(text: &str) -> Buffer {
+        let mut buffer = Buffer::empty(Rect::new(0, 0, 20, 2));
+        buffer.set_line(0, 1, &Line::from(text), 20);
+        buffer
+    }
+
+    fn link(width: u16) -> Vec<(Rect, String)> {
+        vec![(Rect::new(4, 1, width, 1), URL.to_string())]
+    }
+
+    fn text(escapes: &[u8]) -> String {
+        String::from_utf8(escapes.to_vec()).unwrap()
+    }
+
+    #[test]
+    fn terminals_that_print_the_escape_are_left_out() {
+        assert!(supported_by(Some("xterm-256color"), None));
+        assert!(supported_by(Some("tmux-256color"), Some("tmux")));
+        assert!(supported_by(Some("screen-256color"), Some("tmux")));
+        assert!(!supported_by(Some("screen-256color"), None));
+        assert!(!supported_by(Some("linux"), None));
+        assert!(!supported_by(Some("dumb"), None));
+        assert!(!supported_by(None, None));
+    }
+
+    #[test]
+    fn links_are_written_once() {
+        let mut hyperlinks = Hyperlinks::default();
+        let buffer = frame("see example.com");
+
+        let escapes = text(&hyperlinks.escapes(&buffer, &link(11)).unwrap());
+        let open = escapes.find(&format!("\x1b]8;;{URL}\x1b\\")).unwrap();
+        let shown = escapes.find("example.com").unwrap();
+        let close = escapes.rfind("\x1b]8;;\x1b\\").unwrap();
+        assert!(open < shown && shown < close);
+        assert!(escapes.starts_with("\x1b7") && escapes.ends_with("\x1b8"));
+
+        assert!(hyperlinks.escapes(&buffer, &link(11)).unwrap().is_empty());
+    }
+
+    #[test]
+    fn changed_text_is_linked_again() {
+        let mut hyperlinks = Hyperlinks::default();
+        hyperlinks
+            .escapes(&frame("see example.com"), &link(11))
+            .unwrap();
+
+        let escapes = hyperlinks
+            .escapes(&frame("see example.org"), &link(11))
+            .unwrap();
+        assert!(text(&escapes).contains("example.org"));
+    }
+
+    #[test]
+    fn links_that_are_gone_are_drawn_plain() {
+        let mut hyperlinks = Hyperlinks::default();
+        let buffer = frame("see example.com");
+        hyperlinks.escapes(&buffer, &link(11)).unwrap();
+
+        let escapes = text(&hyperlinks.escapes(&buffer, &[]).unwrap());
+        assert!(escapes.contains("example.com"));
+        assert!(!escapes.contains("\x1b]8;;"));
+    }
+
+    #[test]
+    fn control_characters_are_dropped_from_urls() {
+        let mut hyperlinks = Hyperlinks::default();
+        let links = vec![(Rect::new(4, 1, 3, 1), "https://a.b/\x1b]x\x07".to_string())];
+        let escapes = text(&hyperlinks.escapes(&frame("see abc"), &links).unwrap());
+        assert!(escapes.contains("\x1b]8;;https://a.b/]x\x1b\\"));
+    }
+}
@@ -0,0 +1,475 @@
+//! User keybinding overrides loaded from `keys.toml` in the config directory.
+//!
+//! Each table is an input context mapping action names to key lists. Listing
+//! an action replaces all of its default keys in that context; an empty list
+//! unbinds it:
+//!
+//! ```toml
+//! [stories]
+//! select_next = ["j", "Down", "C-n"]
+//! toggle_favorite = []
+//!
+//! [global]
+//! quit = ["C-q"]
+//! ```
+//!
+//! Keys use the same notation as the help text: `x`, `C-x`, `M-x`, `Enter`,
+//! `Esc`, `Tab`, `Space`, `Up`, `F1`, ... Sequences are space-separated, e.g.
+//! `"g g"` or `"z c"`.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+use anyhow::{Context, Result, bail};
+use crossterm::event::{KeyCode, KeyModifiers};
+use serde::Deserialize;
+
+use crate::app::Message;
+use crate::hints::HintAction;
+use crate::keys::{Key, Keymap, Keymaps, format_keys};
+use crate::search::SearchDirection;
+
+/// Action names usable in `keys.toml`.
+const ACTIONS: &[(&str, Message)] = &[
+    ("select_next", Message::SelectNext),
+    ("select_prev", Message::SelectPrev),
+    ("select_first", Message::SelectFirst),
+    ("select_last", Message::SelectLast),
+    ("page_down", Message::PageDown),
+    ("page_up", Message::PageUp),
+    ("open_url", Message::OpenUrl),
+    ("open_story_url", Message::OpenStoryUrl),
+    ("open_hn_page", Message::OpenHnPage),
+    ("open_comments", Message::OpenComments),
+    ("expand_comment", Message::ExpandComment),
+    ("collapse_comment", Message::CollapseComment),
+    ("expand_subtree", Message::ExpandSubtree),
+    ("collapse_subtree", Message::CollapseSubtree),
+    ("expand_thread", Message::ExpandThread),
+    ("collapse_thread", Message::CollapseThread),
+    ("reset_comment_view", Message::ResetCommentView),
+    ("go_to_parent", Message::GoToParent),
+    ("next_toplevel", Message::NextTopLevel),
+    ("prev_toplevel", Message::PrevTopLevel),
+    ("next_by_op", Message::NextByOp),
+    ("next_by_same_author", Message::NextBySameAuthor),
+    ("open_link", Message::OpenLink),
+    ("cycle_code_block", Message::CycleCodeBlock),
+    ("cycle_comment_sort", Message::CycleCommentSort),
+    ("toggle_flat_comments", Message::ToggleFlatComments),
+    ("zoom_comment", Message::ZoomComment),
+    ("toggle_ancestors", Message::ToggleAncestors),
+    ("next_unread", Message::NextUnread),
+    ("toggle_unread_only", Message::ToggleUnreadOnly),
+    ("back", Message::Back),
+    ("forward", Message::Forward),
+    ("quit", Message::Quit),
+    ("refresh", Message::Refresh),
+    ("toggle_help", Message::ToggleHelp),
+    ("toggle_debug", Message::ToggleDebug),
+    ("next_feed", Message::NextFeed),
+    ("prev_feed", Message::PrevFeed),
+    ("feed_1", Message::SwitchFeedTab(0)),
+    ("feed_2", Message::SwitchFeedTab(1)),
+    ("feed_3", Message::SwitchFeedTab(2)),
+    ("feed_4", Message::SwitchFeedTab(3)),
+    ("feed_5", Message::SwitchFeedTab(4)),
+    ("feed_6", Message::SwitchFeedTab(5)),
+    ("feed_7", Message::SwitchFeedTab(6)),
+    ("feed_8", Message::SwitchFeedTab(7)),
+    ("feed_9", Message::SwitchFeedTab(8)),
+    ("switch_pane", Message::SwitchPane),
+    ("open_theme_picker", Message::OpenThemePicker),
+    ("close_theme_picker", Message::CloseThemePicker),
+    ("confirm_theme_picker", Message::ConfirmThemePicker),
+    ("theme_picker_up", Message::ThemePickerUp),
+    ("theme_picker_down", Message::ThemePickerDown),
+    ("copy_url", Message::CopyUrl),
+    ("copy_story_url", Message::CopyStoryUrl),
+    ("toggle_favorite", Message::ToggleFavorite),
+    ("toggle_story_favorite", Message::ToggleStoryFavorite),
+    ("cycle_sort_order", Message::CycleSortOrder),
+    ("open_context_menu", Message::OpenContextMenu),
+    ("close_context_menu", Message::CloseContextMenu),
+    ("context_menu_up", Message::ContextMenuUp),
+    ("context_menu_down", Message::ContextMenuDown),
+    ("confirm_context_menu", Message::ConfirmContextMenu),
+    ("open_command_line", Message::OpenCommandLine),
+    ("close_command_line", Message::CloseCommandLine),
+    ("submit_command_line", Message::SubmitCommandLine),
+    ("command_line_backspace", Message::CommandLineBackspace),
+    ("command_line_complete", Message::CommandLineComplete),
+    ("command_line_history_prev", Message::CommandLineHistoryPrev),
+    ("command_line_history_next", Message::CommandLineHistoryNext),
+    (
+        "search_forward",
+        Message::OpenSearch(SearchDirection::Forward),
+    ),
+    (
+        "search_backward",
+        Message::OpenSearch(SearchDirection::Backward),
+    ),
+    ("search_next", Message::SearchNext),
+    ("search_prev", Message::SearchPrev),
+    ("submit_search", Message::SubmitSearch),
+    ("cancel_search", Message::CancelSearch),
+    ("search_backspace", Message::SearchBackspace),
+    ("hint_open", Message::ShowHints(HintAction::Open)),
+    ("hint_copy", Message::ShowHints(HintAction::Copy)),
+    ("hint_preview", Message::ShowHints(HintAction::Preview)),
+    ("hint_backspace", Message::HintBackspace),
+    ("cancel_hints", Message::CancelHints),
+];
+
+type Overrides = BTreeMap<String, Vec<String>>;
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct KeysFile {
+    global: Overrides,
+    stories: Overrides,
+    comments: Overrides,
+    user: Overrides,
+    split: Overrides,
+    theme_picker: Overrides,
+    context_menu: Overrides,
+    help_overlay: Overrides,
+    command_line: Overrides,
+    search: Overrides,
+    hints: Overrides,
+}
+
+/// Load keymaps from `path`, falling back to the defaults if it doesn't exist.
+pub fn load(path: &Path) -> Result<Keymaps> {
+    if !path.exists() {
+        return Ok(Keymaps::default());
+    }
+    let contents = fs::read_to_string(path).context("Failed to read keys file")?;
+    parse(&contents)
+}
+
+/// Apply the overrides in `contents` to the default keymaps and check the
+/// result for conflicting bindings.
+pub fn parse(contents: &str) -> Result<Keymaps> {
+    let file: KeysFile = toml::from_str(contents).context("Failed to parse keys file")?;
+    let mut keymaps = Keymaps::default();
+    apply(&mut keymaps.global, &file.global, "global")?;
+    apply(&mut keymaps.stories, &file.stories, "stories")?;
+    apply(&mut keymaps.comments, &file.comments, "comments")?;
+    apply(&mut keymaps.user, &file.user, "user")?;
+    apply(&mut keymaps.split, &file.split, "split")?;
+    apply(
+        &mut keymaps.theme_picker,
+        &file.theme_picker,
+        "theme_picker",
+    )?;
+    apply(
+        &mut keymaps.context_menu,
+        &file.context_menu,
+        "context_menu",
+    )?;
+    apply(
+        &mut keymaps.help_overlay,
+        &file.help_overlay,
+        "help_overlay",
+    )?;
+    apply(
+        &mut keymaps.command_line,
+        &file.command_line,
+        "command_line",
+    )?;
+    apply(&mut keymaps.search, &file.search, "search")?;
+    apply(&mut keymaps.hints, &file.hints, "hints")?;
+    validate(&keymaps)?;
+    Ok(keymaps)
+}
+
+fn apply(keymap: &mut Keymap, overrides: &Overrides, context: &str) -> Result<()> {
+    for (name, keys) in overrides {
+        let Some(message) = action(name) else {
+            bail!("Unknown action '{name}' in [{context}]");
+        };
+        let keys = keys
+            .iter()
+            .map(|key| {
+                parse_sequence(key)
+                    .with_context(|| format!("Invalid key for '{name}' in [{context}]"))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        keymap.rebind(&message, &keys);
+    }
+    Ok(())
+}
+
+/// Reject keys bound to two different actions in the same context. Global
+/// bindings shadow the view bindings, so views are checked together with them.
+fn validate(keymaps: &Keymaps) -> Result<()> {
+    let contexts = [
+        ("stories", keymaps.stories_view()),
+        ("comments", keymaps.comments_view()),
+        ("user", keymaps.user_view()),
+        ("theme_picker", keymaps.theme_picker.clone()),
+        ("context_menu", keymaps.context_menu.clone()),
+        ("help_overlay", keymaps.help_overlay.clone()),
+        ("command_line", keymaps.command_line.clone()),
+        ("search", keymaps.search.clone()),
+        ("hints", keymaps.hints.clone()),
+    ];
+    for (context, keymap) in &contexts {
+        let mut seen: Vec<(&[Key], &Message)> = Vec::new();
+        for (keys, message) in keymap.bindings() {
+            if let Some((_, other)) = seen
+                .iter()
+                .find(|(k, msg)| *k == keys.as_slice() && *msg != message)
+            {
+                bail!(
+                    "Key '{}' is bound to both {} and {} in [{context}]",
+                    format_keys(keys),
+                    action_name(other),
+                    action_name(message),
+                );
+            }
+            seen.push((keys, message));
+        }
+    }
+    Ok(())
+}
+
+fn action(name: &str) -> Option<Message> {
+    ACTIONS
+        .iter()
+        .find(|(n, _)| *n == name)
+        .map(|(_, msg)| msg.clone())
+}
+
+fn action_name(message: &Message) -> String {
+    ACTIONS
+        .iter()
+        .find(|(_, msg)| msg == message)
+        .map_or_else(|| format!("{message:?}"), |(name, _)| (*name).to_string())
+}
+
+/// Parse a space-separated key sequence such as `g g` or `z c`.
+pub fn parse_sequence(sequence: &str) -> Result<Vec<Key>> {
+    let keys = sequence
+        .split_whitespace()
+        .map(parse_key)
+        .collect::<Result<Vec<_>>>()?;
+    if keys.is_empty() {
+        bail!("Empty key sequence");
+    }
+    Ok(keys)
+}
+
+/// Parse a key in help-text notation: an optional `C-` or `M-` prefix
+/// followed by a single character or a named key. The arrows help shows for
+/// the arrow keys read back as those keys.
+pub fn parse_key(key: &str) -> Result<Key> {
+    let (mods, name) = match key.split_at_checked(2) {
+        Some(("C-", rest)) => (KeyModifiers::CONTROL, rest),
+        Some(("M-", rest)) => (KeyModifiers::ALT, rest),
+        _ => (KeyModifiers::NONE, key),
+    };
+
+    let mut chars = name.chars();
+    let code = match (chars.next(), chars.next()) {
+        (Some('↑'), None) => KeyCode::Up,
+        (Some('↓'), None) => KeyCode::Down,
+        (Some('←'), None) => KeyCode::Left,
+        (Some('→'), None) => KeyCode::Right,
+        (Some(c), None) => KeyCode::Char(c),
+        _ => match name.to_ascii_lowercase().as_str() {
+            "space" => KeyCode::Char(' '),
+            "enter" | "return" => KeyCode::Enter,
+            "esc" | "escape" => KeyCode::Esc,
+            "tab" => KeyCode::Tab,
+            "bksp" | "backspace" => KeyCode::Backspace,
+            "up" => KeyCode::Up,
+            "down" => KeyCode::Down,
+            "left" => KeyCode::Left,
+            "right" => KeyCode::Right,
+            "home" => KeyCode::Home,
+            "end" => KeyCode::End,
+            "pgup" | "pageup" => KeyCode::PageUp,
+            "pgdn" | "pagedown" => KeyCode::PageDown,
+            "del" | "delete" => KeyCode::Delete,
+            other => match other.strip_prefix('f').map(str::parse::<u8>) {
+                Some(Ok(n)) if (1..=12).contains(&n) => KeyCode::F(n),
+                _ => bail!("Unknown key '{key}'"),
+            },
+        },
+    };
+    Ok((code, mods))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::help::stories_help;
+    use crate::keys::format_key;
+
+    #[test]
+    fn defaults_have_no_conflicts() {
+        assert!(validate(&Keymaps::default()).is_ok());
+    }
+
+    #[test]
+    fn parse_key_notation() {
+        assert_eq!(
+            parse_key("j").unwrap(),
+            (KeyCode::Char('j'), KeyModifiers::NONE)
+        );
+        assert_eq!(
+            parse_key("G").unwrap(),
+            (KeyCode::Char('G'), KeyModifiers::NONE)
+        );
+        assert_eq!(
+            parse_key("C-n").unwrap(),
+            (KeyCode::Char('n'), KeyModifiers::CONTROL)
+        );
+        assert_eq!(
+            parse_key("M-Left").unwrap(),
+            (KeyCode::Left, KeyModifiers::ALT)
+        );
+        assert_eq!(
+            parse_key("Down").unwrap(),
+            (KeyCode::Down, KeyModifiers::NONE)
+        );
+        assert_eq!(
+            parse_key("Space").unwrap(),
+            (KeyCode::Char(' '), KeyModifiers::NONE)
+        );
+        assert_eq!(
+            parse_key("F5").unwrap(),
+            (KeyCode::F(5), KeyModifiers::NONE)
+        );
+        assert_eq!(
+            parse_key("-").unwrap(),
+            (KeyCode::Char('-'), KeyModifiers::NONE)
+        );
+        assert!(parse_key("Hyper").is_err());
+        assert!(parse_key("F13").is_err());
+    }
+
+    #[test]
+    fn format_key_round_trips() {
+        for key in [
+            "j", "C-d", "M-x", "Enter", "Esc", "Tab", "Space", "Bksp", "F1",
+        ] {
+            let (code, mods) = parse_key(key).unwrap();
+            assert_eq!(format_keys(&[(code, mods)]), key);
+        }
+
+        // Every default key reads back from the way help shows it
+        let keymaps = Keymaps::default();
+        let all = [
+            &keymaps.global,
+            &keymaps.stories,
+            &keymaps.comments,
+            &keymaps.user,
+            &keymaps.theme_picker,
+            &keymaps.context_menu,
+            &keymaps.help_overlay,
+            &keymaps.command_line,
+            &keymaps.search,
+            &keymaps.hints,
+        ];
+        for &(code, mods) in all.iter().flat_map(|k| k.bindings()).flat_map(|(k, _)| k) {
+            assert_eq!(
+                parse_key(&format_key(code, mods)).unwrap(),
+                (code, mods),
+                "{code:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn feed_number_keys_can_be_remapped() {
+        let keymaps = parse("[stories]\nfeed_2 = [\"M-2\"]\n").unwrap();
+        assert_eq!(
+            keymaps.stories.find_key(&Message::SwitchFeedTab(1)),
+            Some(&[(KeyCode::Char('2'), KeyModifiers::ALT)][..])
+        );
+        // Number keys are checked for conflicts like any other
+        let err = parse("[stories]\nrefresh = [\"g 3\"]\n").unwrap_err();
+        assert!(err.to_string().contains("feed_3 and refresh"));
+    }
+
+    #[test]
+    fn overrides_replace_and_unbind() {
+        let keymaps = parse(
+            "[stories]\nselect_next = [\"i\", \"Down\"]\ntoggle_favorite = []\n\n[global]\nquit = [\"C-q\"]\n",
+        )
+        .unwrap();
+
+        let stories = keymaps.stories_view();
+        assert_eq!(
+            stories.find_key(&Message::SelectNext),
+            Some(&[(KeyCode::Char('i'), KeyModifiers::NONE)][..])
+        );
+        assert_eq!(stories.find_key(&Message::ToggleFavorite), None);
+        assert_eq!(
+            stories.find_key(&Message::Quit),
+            Some(&[(KeyCode::Char('q'), KeyModifiers::CONTROL)][..])
+        );
+        // Comments keep the default navigation keys
+        assert_eq!(
+            keymaps.comments.find_key(&Message::SelectNext),
+            Some(&[(KeyCode::Char('j'), KeyModifiers::NONE)][..])
+        );
+    }
+
+    #[test]
+    fn sequences_can_be_bound() {
+        let keymaps = parse("[comments]\ngo_to_parent = [\"g p\", \"C-w k\"]\n").unwrap();
+        assert_eq!(
+            keymaps.comments.find_key(&Message::GoToParent),
+            Some(
+                &[
+                    (KeyCode::Char('g'), KeyModifiers::NONE),
+                    (KeyCode::Char('p'), KeyModifiers::NONE)
+                ][..]
+            )
+        );
+        assert!(parse_sequence("  ").is_err());
+        assert_eq!(format_keys(&parse_sequence("C-w k").unwrap()), "C-w k");
+    }
+
+    #[test]
+    fn help_reflects_remapped_keys() {
+        let keymaps = parse("[stories]\nselect_next = [\"i\"]\nselect_prev = [\"e\"]\n").unwrap();
+        let help = stories_help().format(&keymaps.stories_view(), true);
+        assert!(help.contains("i/e:nav"));
+    }
+
+    #[test]
+    fn conflicting_bindings_are_rejected() {
+        let err = parse("[stories]\nrefresh = [\"j\"]\n").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Key 'j' is bound to both select_next and refresh in [stories]"
+        );
+
+        // Global bindings shadow view bindings
+        let err = parse("[global]\nquit = [\"o\"]\n").unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("Key 'o' is bound to both quit and open_url")
+        );
+
+        // Moving the conflicting action away resolves it
+        assert!(parse("[stories]\nrefresh = [\"j\"]\nselect_next = [\"i\"]\n").is_ok());
+    }
+
+    #[test]
+    fn unknown_names_are_rejected() {
+        let err = parse("[stories]\nlaunch_rocket = [\"x\"]\n").unwrap_err();
+        assert!(err.to_string().contains("Unknown action 'launch_rocket'"));
+
+        assert!(parse("[sidebar]\nquit = [\"q\"]\n").is_err());
+
+        let err = parse("[stories]\nquit = [\"Hyper\"]\n").unwrap_err();
+        assert!(format!("{err:#}").contains("Unknown key 'Hyper'"));
+    }
+}
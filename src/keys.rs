@@ -1,12 +1,39 @@
+use std::time::{Duration, Instant};
+
 use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
 
-use crate::api::Feed;
 use crate::app::{App, Message, View};
+use crate::hints::HintAction;
+use crate::search::SearchDirection;
+
+/// A single key press: a key code plus the modifiers it must be held with.
+pub type Key = (KeyCode, KeyModifiers);
+
+/// How long an incomplete key sequence or count waits for the next key.
+pub const PENDING_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// Counts are capped so `99999j` can't stall the UI.
+const MAX_COUNT: u16 = 999;
 
 /// A declarative keybinding map that can be composed and extended.
-#[derive(Clone)]
+///
+/// Each binding is a sequence of one or more keys, so `gg` and `zc` can be
+/// expressed alongside plain single-key bindings.
+#[derive(Debug, Clone)]
 pub struct Keymap {
-    bindings: Vec<(KeyCode, KeyModifiers, Message)>,
+    bindings: Vec<(Vec<Key>, Message)>,
+}
+
+/// Result of looking up a partially typed key sequence.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Lookup {
+    /// The keys match a binding and nothing longer.
+    Match(Message),
+    /// The keys are the start of a longer binding. Carries the message bound
+    /// to exactly these keys, if any, to fire when the sequence times out.
+    Pending(Option<Message>),
+    /// Nothing is bound to these keys.
+    None,
 }
 
 impl Keymap {
@@ -18,45 +45,107 @@ impl Keymap {
 
     /// Add a key binding with no modifiers.
     pub fn bind(mut self, code: KeyCode, message: Message) -> Self {
-        self.bindings.push((code, KeyModifiers::NONE, message));
+        self.bindings
+            .push((vec![(code, KeyModifiers::NONE)], message));
         self
     }
 
     /// Add a key binding with Ctrl modifier.
     pub fn bind_ctrl(mut self, code: KeyCode, message: Message) -> Self {
-        self.bindings.push((code, KeyModifiers::CONTROL, message));
+        self.bindings
+            .push((vec![(code, KeyModifiers::CONTROL)], message));
+        self
+    }
+
+    /// Add a key binding with Alt modifier.
+    pub fn bind_alt(mut self, code: KeyCode, message: Message) -> Self {
+        self.bindings
+            .push((vec![(code, KeyModifiers::ALT)], message));
         self
     }
 
-    /// Look up a message for a key event.
+    /// Add a binding for a sequence of unmodified keys, e.g. `gg`.
+    pub fn bind_seq(mut self, codes: &[KeyCode], message: Message) -> Self {
+        let keys = codes
+            .iter()
+            .map(|&code| (code, KeyModifiers::NONE))
+            .collect();
+        self.bindings.push((keys, message));
+        self
+    }
+
+    /// Replace every sequence bound to `message` with `sequences`. An empty
+    /// slice unbinds it.
+    pub fn rebind(&mut self, message: &Message, sequences: &[Vec<Key>]) {
+        self.bindings.retain(|(_, msg)| msg != message);
+        self.bindings
+            .extend(sequences.iter().map(|keys| (keys.clone(), message.clone())));
+    }
+
+    /// All bindings in declaration order.
+    pub fn bindings(&self) -> impl Iterator<Item = &(Vec<Key>, Message)> {
+        self.bindings.iter()
+    }
+
+    /// Look up a message for a single key event, ignoring sequences.
     /// Later bindings take precedence over earlier ones.
     pub fn get(&self, event: &KeyEvent) -> Option<Message> {
-        self.bindings
+        match self.lookup(&[(event.code, event.modifiers)]) {
+            Lookup::Match(msg) | Lookup::Pending(Some(msg)) => Some(msg),
+            Lookup::Pending(None) | Lookup::None => None,
+        }
+    }
+
+    /// Look up the keys typed so far. Later bindings take precedence over
+    /// earlier ones.
+    pub fn lookup(&self, keys: &[Key]) -> Lookup {
+        let exact = self
+            .bindings
             .iter()
             .rev()
-            .find(|(code, mods, _)| *code == event.code && event.modifiers.contains(*mods))
-            .map(|(_, _, msg)| msg.clone())
+            .find(|(seq, _)| seq.len() == keys.len() && starts_with(keys, seq))
+            .map(|(_, msg)| msg.clone());
+        let longer = self
+            .bindings
+            .iter()
+            .any(|(seq, _)| seq.len() > keys.len() && starts_with(keys, seq));
+        match (exact, longer) {
+            (exact, true) => Lookup::Pending(exact),
+            (Some(msg), false) => Lookup::Match(msg),
+            (None, false) => Lookup::None,
+        }
     }
 
     /// Extend this keymap with another. The other keymap's bindings take precedence.
-    #[cfg_attr(not(test), allow(dead_code))]
     pub fn extend(mut self, other: Self) -> Self {
         self.bindings.extend(other.bindings);
         self
     }
 
-    /// Find the first key bound to a specific message.
-    pub fn find_key(&self, message: &Message) -> Option<(KeyCode, KeyModifiers)> {
+    /// Find the first key sequence bound to a specific message.
+    pub fn find_key(&self, message: &Message) -> Option<&[Key]> {
         self.bindings
             .iter()
-            .find(|(_, _, msg)| msg == message)
-            .map(|(code, mods, _)| (*code, *mods))
+            .find(|(_, msg)| msg == message)
+            .map(|(keys, _)| keys.as_slice())
     }
 }
 
+/// Returns true if the typed `keys` match the start of the bound `seq`.
+fn starts_with(keys: &[Key], seq: &[Key]) -> bool {
+    keys.len() <= seq.len()
+        && keys
+            .iter()
+            .zip(seq)
+            .all(|((code, mods), (bound_code, bound_mods))| {
+                code == bound_code && mods.contains(*bound_mods)
+            })
+}
+
 /// Format a key binding for display in help text.
 pub fn format_key(code: KeyCode, mods: KeyModifiers) -> String {
     let key_str = match code {
+        KeyCode::Char(' ') => "Space".to_string(),
         KeyCode::Char(c) => c.to_string(),
         KeyCode::Enter => "Enter".to_string(),
         KeyCode::Esc => "Esc".to_string(),
@@ -66,6 +155,11 @@ pub fn format_key(code: KeyCode, mods: KeyModifiers) -> String {
         KeyCode::Right => "→".to_string(),
         KeyCode::Backspace => "Bksp".to_string(),
         KeyCode::Tab => "Tab".to_string(),
+        KeyCode::Home => "Home".to_string(),
+        KeyCode::End => "End".to_string(),
+        KeyCode::PageUp => "PgUp".to_string(),
+        KeyCode::PageDown => "PgDn".to_string(),
+        KeyCode::Delete => "Del".to_string(),
         KeyCode::F(n) => format!("F{n}"),
         _ => "?".to_string(),
     };
@@ -78,12 +172,114 @@ pub fn format_key(code: KeyCode, mods: KeyModifiers) -> String {
     }
 }
 
+/// Format a key sequence for display. Plain characters run together (`gg`),
+/// anything longer is space-separated (`C-w j`).
+pub fn format_keys(keys: &[Key]) -> String {
+    let parts: Vec<String> = keys
+        .iter()
+        .map(|&(code, mods)| format_key(code, mods))
+        .collect();
+    let separator = if parts.iter().all(|p| p.chars().count() == 1) {
+        ""
+    } else {
+        " "
+    };
+    parts.join(separator)
+}
+
+/// An incomplete key sequence and/or count prefix, e.g. `5` or `3g`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PendingKeys {
+    pub count: Option<u16>,
+    pub keys: Vec<Key>,
+}
+
+impl PendingKeys {
+    /// Text for the status bar indicator.
+    pub fn display(&self) -> String {
+        let count = self.count.map(|n| n.to_string()).unwrap_or_default();
+        format!("{count}{}", format_keys(&self.keys))
+    }
+
+    /// Apply the count prefix to a resolved message.
+    fn finish(&self, message: Message) -> Message {
+        match self.count {
+            Some(count) if count > 1 && message.is_repeatable() => {
+                Message::Repeat(count, Box::new(message))
+            }
+            _ => message,
+        }
+    }
+}
+
 impl Default for Keymap {
     fn default() -> Self {
         Self::new()
     }
 }
 
+/// The keymap for every input context, with any user overrides from
+/// `keys.toml` applied (see [`crate::keyconfig`]).
+#[derive(Debug, Clone)]
+pub struct Keymaps {
+    pub global: Keymap,
+    pub stories: Keymap,
+    pub comments: Keymap,
+    pub user: Keymap,
+    /// Added to the view's bindings while the split layout is shown.
+    pub split: Keymap,
+    pub theme_picker: Keymap,
+    pub context_menu: Keymap,
+    pub help_overlay: Keymap,
+    pub command_line: Keymap,
+    pub search: Keymap,
+    pub hints: Keymap,
+}
+
+impl Keymaps {
+    /// Bindings that can be active in the stories view.
+    pub fn stories_view(&self) -> Keymap {
+        self.global
+            .clone()
+            .extend(self.stories.clone())
+            .extend(self.split.clone())
+    }
+
+    /// Bindings that can be active in the comments view.
+    pub fn comments_view(&self) -> Keymap {
+        self.global
+            .clone()
+            .extend(self.comments.clone())
+            .extend(self.split.clone())
+    }
+
+    /// Bindings that can be active in the user profile view.
+    pub fn user_view(&self) -> Keymap {
+        self.global
+            .clone()
+            .extend(self.user.clone())
+            .extend(self.split.clone())
+    }
+}
+
+impl Default for Keymaps {
+    fn default() -> Self {
+        Self {
+            global: global_keymap(),
+            stories: stories_keymap(),
+            comments: comments_keymap(),
+            user: user_keymap(),
+            split: split_keymap(),
+            theme_picker: theme_picker_keymap(),
+            context_menu: context_menu_keymap(),
+            help_overlay: help_overlay_keymap(),
+            command_line: command_line_keymap(),
+            search: search_keymap(),
+            hints: hints_keymap(),
+        }
+    }
+}
+
 /// Global keybindings that work in all views.
 pub fn global_keymap() -> Keymap {
     Keymap::new()
@@ -91,6 +287,41 @@ pub fn global_keymap() -> Keymap {
         .bind_ctrl(KeyCode::Char('c'), Message::Quit)
         .bind(KeyCode::Char('`'), Message::ToggleDebug)
         .bind(KeyCode::Char('t'), Message::OpenThemePicker)
+        .bind(KeyCode::Char(':'), Message::OpenCommandLine)
+}
+
+/// Keybindings for the `:` command line. Other printable keys are typed
+/// into the prompt.
+pub fn command_line_keymap() -> Keymap {
+    Keymap::new()
+        .bind(KeyCode::Enter, Message::SubmitCommandLine)
+        .bind(KeyCode::Esc, Message::CloseCommandLine)
+        .bind_ctrl(KeyCode::Char('c'), Message::CloseCommandLine)
+        .bind(KeyCode::Backspace, Message::CommandLineBackspace)
+        .bind(KeyCode::Tab, Message::CommandLineComplete)
+        .bind(KeyCode::Up, Message::CommandLineHistoryPrev)
+        .bind_ctrl(KeyCode::Char('p'), Message::CommandLineHistoryPrev)
+        .bind(KeyCode::Down, Message::CommandLineHistoryNext)
+        .bind_ctrl(KeyCode::Char('n'), Message::CommandLineHistoryNext)
+}
+
+/// Keybindings for the `/` and `?` search prompt. Other printable keys are
+/// typed into the query.
+pub fn search_keymap() -> Keymap {
+    Keymap::new()
+        .bind(KeyCode::Enter, Message::SubmitSearch)
+        .bind(KeyCode::Esc, Message::CancelSearch)
+        .bind_ctrl(KeyCode::Char('c'), Message::CancelSearch)
+        .bind(KeyCode::Backspace, Message::SearchBackspace)
+}
+
+/// Keybindings while picking a link hint. Other printable keys are typed as
+/// part of a label.
+pub fn hints_keymap() -> Keymap {
+    Keymap::new()
+        .bind(KeyCode::Esc, Message::CancelHints)
+        .bind_ctrl(KeyCode::Char('c'), Message::CancelHints)
+        .bind(KeyCode::Backspace, Message::HintBackspace)
 }
 
 /// Keybindings for the theme picker popup.
@@ -111,7 +342,7 @@ pub fn theme_picker_keymap() -> Keymap {
 /// Keybindings for the help overlay popup.
 fn help_overlay_keymap() -> Keymap {
     Keymap::new()
-        .bind(KeyCode::Char('?'), Message::ToggleHelp)
+        .bind(KeyCode::F(1), Message::ToggleHelp)
         .bind(KeyCode::Esc, Message::ToggleHelp)
         .bind(KeyCode::Char('q'), Message::ToggleHelp)
         .bind_ctrl(KeyCode::Char('c'), Message::ToggleHelp)
@@ -141,7 +372,10 @@ fn navigation_keymap() -> Keymap {
         .bind(KeyCode::Down, Message::SelectNext)
         .bind(KeyCode::Char('k'), Message::SelectPrev)
         .bind(KeyCode::Up, Message::SelectPrev)
-        .bind(KeyCode::Char('g'), Message::SelectFirst)
+        .bind_seq(
+            &[KeyCode::Char('g'), KeyCode::Char('g')],
+            Message::SelectFirst,
+        )
         .bind(KeyCode::Char('G'), Message::SelectLast)
         .bind_ctrl(KeyCode::Char('d'), Message::PageDown)
         .bind_ctrl(KeyCode::Char('u'), Message::PageUp)
@@ -149,7 +383,17 @@ fn navigation_keymap() -> Keymap {
         .bind(KeyCode::Char('y'), Message::CopyUrl)
         .bind(KeyCode::Char('r'), Message::Refresh)
         .bind(KeyCode::Char('R'), Message::Refresh)
-        .bind(KeyCode::Char('?'), Message::ToggleHelp)
+        .bind(
+            KeyCode::Char('/'),
+            Message::OpenSearch(SearchDirection::Forward),
+        )
+        .bind(
+            KeyCode::Char('?'),
+            Message::OpenSearch(SearchDirection::Backward),
+        )
+        .bind(KeyCode::Char('n'), Message::SearchNext)
+        .bind(KeyCode::Char('N'), Message::SearchPrev)
+        .bind(KeyCode::F(1), Message::ToggleHelp)
 }
 
 /// Stories view keybindings.
@@ -163,13 +407,21 @@ pub fn stories_keymap() -> Keymap {
         .bind(KeyCode::Char(','), Message::OpenContextMenu)
         .bind(KeyCode::Char('H'), Message::PrevFeed)
         .bind(KeyCode::Char('L'), Message::NextFeed)
-        .bind(KeyCode::Char('1'), Message::SwitchFeed(Feed::Favorites))
-        .bind(KeyCode::Char('2'), Message::SwitchFeed(Feed::Top))
-        .bind(KeyCode::Char('3'), Message::SwitchFeed(Feed::New))
-        .bind(KeyCode::Char('4'), Message::SwitchFeed(Feed::Best))
-        .bind(KeyCode::Char('5'), Message::SwitchFeed(Feed::Ask))
-        .bind(KeyCode::Char('6'), Message::SwitchFeed(Feed::Show))
-        .bind(KeyCode::Char('7'), Message::SwitchFeed(Feed::Jobs))
+        .extend(feed_tab_keymap())
+        .extend(history_keymap())
+}
+
+/// `g1` to `g9` jump straight to the first nine feed tabs. Bare digits are
+/// left for counts.
+fn feed_tab_keymap() -> Keymap {
+    ('1'..='9')
+        .enumerate()
+        .fold(Keymap::new(), |keymap, (tab, c)| {
+            keymap.bind_seq(
+                &[KeyCode::Char('g'), KeyCode::Char(c)],
+                Message::SwitchFeedTab(tab),
+            )
+        })
 }
 
 /// Comments view keybindings.
@@ -183,46 +435,212 @@ pub fn comments_keymap() -> Keymap {
         .bind(KeyCode::Char('='), Message::ExpandThread)
         .bind(KeyCode::Char('-'), Message::CollapseThread)
         .bind(KeyCode::Char('_'), Message::CollapseThread)
+        .bind_seq(
+            &[KeyCode::Char('z'), KeyCode::Char('o')],
+            Message::ExpandComment,
+        )
+        .bind_seq(
+            &[KeyCode::Char('z'), KeyCode::Char('c')],
+            Message::CollapseComment,
+        )
+        .bind_seq(
+            &[KeyCode::Char('z'), KeyCode::Char('R')],
+            Message::ExpandThread,
+        )
+        .bind_seq(
+            &[KeyCode::Char('z'), KeyCode::Char('M')],
+            Message::CollapseThread,
+        )
+        .bind_seq(
+            &[KeyCode::Char('z'), KeyCode::Char('X')],
+            Message::ResetCommentView,
+        )
         .bind(KeyCode::Char('p'), Message::GoToParent)
+        .bind_seq(
+            &[KeyCode::Char(']'), KeyCode::Char(']')],
+            Message::NextTopLevel,
+        )
+        .bind_seq(
+            &[KeyCode::Char('['), KeyCode::Char('[')],
+            Message::PrevTopLevel,
+        )
+        .bind_seq(&[KeyCode::Char(']'), KeyCode::Char('o')], Message::NextByOp)
+        .bind_seq(
+            &[KeyCode::Char(']'), KeyCode::Char('a')],
+            Message::NextBySameAuthor,
+        )
+        .bind_seq(&[KeyCode::Char('g'), KeyCode::Char('f')], Message::OpenLink)
+        .bind(KeyCode::Char('c'), Message::CycleCodeBlock)
+        .bind(KeyCode::Char('s'), Message::CycleCommentSort)
+        .bind(KeyCode::Char('T'), Message::ToggleFlatComments)
+        .bind(KeyCode::Char('Z'), Message::ZoomComment)
+        .bind(KeyCode::Char('a'), Message::ToggleAncestors)
+        .bind(KeyCode::Char('u'), Message::NextUnread)
+        .bind(KeyCode::Char('U'), Message::ToggleUnreadOnly)
+        .bind(KeyCode::Char('x'), Message::ShowHints(HintAction::Open))
+        .bind(KeyCode::Char('X'), Message::ShowHints(HintAction::Copy))
+        .bind_seq(
+            &[KeyCode::Char('g'), KeyCode::Char('x')],
+            Message::ShowHints(HintAction::Preview),
+        )
         .bind(KeyCode::Char('f'), Message::ToggleFavorite)
         .bind(KeyCode::Char('F'), Message::ToggleStoryFavorite)
         .bind(KeyCode::Char('O'), Message::OpenStoryUrl)
         .bind(KeyCode::Char('Y'), Message::CopyStoryUrl)
         .bind(KeyCode::Esc, Message::Back)
+        .extend(history_keymap())
+}
+
+/// User profile keybindings.
+pub fn user_keymap() -> Keymap {
+    Keymap::new()
+        .bind(KeyCode::Char('o'), Message::OpenUrl)
+        .bind(KeyCode::Char('y'), Message::CopyUrl)
+        .bind(KeyCode::Char('r'), Message::Refresh)
+        .bind(KeyCode::Char('R'), Message::Refresh)
+        .bind(KeyCode::Char('h'), Message::Back)
+        .bind(KeyCode::Esc, Message::Back)
+        .bind(KeyCode::F(1), Message::ToggleHelp)
+        .extend(history_keymap())
+}
+
+/// Split layout keybindings, for moving between the story list and the
+/// comments.
+pub fn split_keymap() -> Keymap {
+    Keymap::new().bind(KeyCode::Tab, Message::SwitchPane)
+}
+
+/// Back and forward through the views visited, like a browser.
+fn history_keymap() -> Keymap {
+    Keymap::new()
+        .bind(KeyCode::Backspace, Message::Back)
+        .bind_alt(KeyCode::Left, Message::Back)
+        .bind_alt(KeyCode::Right, Message::Forward)
 }
 
 pub fn handle_key(key: KeyEvent, app: &App) -> Option<Message> {
+    let keymaps = &app.keymaps;
+
+    // The command line captures all input while open
+    if app.command_line.is_some() {
+        return keymaps.command_line.get(&key).or(match key.code {
+            KeyCode::Char(c) if (key.modifiers - KeyModifiers::SHIFT).is_empty() => {
+                Some(Message::CommandLineInput(c))
+            }
+            _ => None,
+        });
+    }
+
+    // So does the search prompt
+    if app.search.prompt.is_some() {
+        return keymaps.search.get(&key).or(match key.code {
+            KeyCode::Char(c) if (key.modifiers - KeyModifiers::SHIFT).is_empty() => {
+                Some(Message::SearchInput(c))
+            }
+            _ => None,
+        });
+    }
+
+    // And picking a link hint
+    if app.hints.is_some() {
+        return keymaps.hints.get(&key).or(match key.code {
+            KeyCode::Char(c) if (key.modifiers - KeyModifiers::SHIFT).is_empty() => {
+                Some(Message::HintInput(c))
+            }
+            _ => None,
+        });
+    }
+
     // Context menu takes highest priority when open
     if app.context_menu.is_some() {
-        return context_menu_keymap().get(&key);
+        return keymaps.context_menu.get(&key);
     }
 
     // Theme picker takes priority when open
     if app.theme_picker.is_some() {
-        return theme_picker_keymap().get(&key);
+        return keymaps.theme_picker.get(&key);
     }
 
     // Help overlay takes priority when open
     if app.help_overlay {
-        return help_overlay_keymap().get(&key);
+        return keymaps.help_overlay.get(&key);
+    }
+
+    let keymap = view_keymap(app);
+    let pending = app
+        .pending_keys
+        .as_ref()
+        .map(|(pending, _)| pending.clone())
+        .unwrap_or_default();
+    let key = (key.code, key.modifiers);
+
+    // Digits build a count prefix unless they are bound on their own in
+    // keys.toml. Once a count has started every digit extends it.
+    if pending.keys.is_empty()
+        && let KeyCode::Char(c) = key.0
+        && let Some(digit) = c.to_digit(10)
+        && (key.1 - KeyModifiers::SHIFT).is_empty()
+        && (pending.count.is_some() || (digit != 0 && keymap.lookup(&[key]) == Lookup::None))
+    {
+        #[allow(clippy::cast_possible_truncation)] // digit is 0..=9
+        let count = pending.count.unwrap_or(0).saturating_mul(10) + digit as u16;
+        return Some(Message::PendingKeys(PendingKeys {
+            count: Some(count.min(MAX_COUNT)),
+            keys: Vec::new(),
+        }));
+    }
+
+    let mut keys = pending.keys.clone();
+    keys.push(key);
+    match keymap.lookup(&keys) {
+        Lookup::Match(msg) => Some(pending.finish(msg)),
+        Lookup::Pending(_) => Some(Message::PendingKeys(PendingKeys {
+            count: pending.count,
+            keys,
+        })),
+        // An unknown key abandons whatever was pending
+        Lookup::None if app.pending_keys.is_some() => Some(Message::ClearPendingKeys),
+        Lookup::None => None,
     }
+}
 
-    // Global keys first
-    if let Some(msg) = global_keymap().get(&key) {
-        return Some(msg);
+/// Resolve a pending sequence that has waited longer than [`PENDING_TIMEOUT`].
+/// Fires the binding for the keys typed so far if there is one, otherwise
+/// drops them.
+pub fn expire_pending(app: &App, now: Instant) -> Option<Message> {
+    let (pending, since) = app.pending_keys.as_ref()?;
+    if now.duration_since(*since) < PENDING_TIMEOUT {
+        return None;
+    }
+    match view_keymap(app).lookup(&pending.keys) {
+        Lookup::Match(msg) | Lookup::Pending(Some(msg)) => Some(pending.finish(msg)),
+        Lookup::Pending(None) | Lookup::None => Some(Message::ClearPendingKeys),
     }
+}
 
-    // View-specific keys
-    match app.view {
-        View::Stories => stories_keymap().get(&key),
-        View::Comments { .. } => comments_keymap().get(&key),
+/// Bindings for the current view. Global keys win over view keys.
+/// Bindings active in the current view, for handling keys and help text.
+pub fn view_keymap(app: &App) -> Keymap {
+    let keymaps = &app.keymaps;
+    let view = match app.view {
+        View::Stories => keymaps.stories.clone(),
+        View::Comments { .. } => keymaps.comments.clone(),
+        View::User { .. } => keymaps.user.clone(),
+    };
+    let view = view.extend(keymaps.global.clone());
+    if app.is_split() {
+        view.extend(keymaps.split.clone())
+    } else {
+        view
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::api::Feed;
     use crate::storage::{Storage, StorageLocation};
+    use crate::test_utils::{comments_view, custom_feed};
     use crate::theme::{ThemeVariant, default_for_variant};
     use crossterm::event::{KeyEventKind, KeyEventState};
 
@@ -258,12 +676,7 @@ mod tests {
 
     fn comments_app() -> App {
         let mut app = test_app();
-        app.view = View::Comments {
-            story_id: 1,
-            story_title: "Test".to_string(),
-            story_index: 0,
-            story_scroll: 0,
-        };
+        app.view = comments_view(1, "Test");
         app
     }
 
@@ -288,9 +701,26 @@ mod tests {
         ));
     }
 
+    /// Feed `keys` through `handle_key`, applying pending state between
+    /// presses, and return the final message.
+    fn press(app: &mut App, keys: &str) -> Option<Message> {
+        let mut last = None;
+        for c in keys.chars() {
+            last = handle_key(make_key(KeyCode::Char(c)), app);
+            match &last {
+                Some(msg @ (Message::PendingKeys(_) | Message::ClearPendingKeys)) => {
+                    app.update(msg.clone());
+                }
+                // Other messages would clear the pending state in update
+                _ => app.pending_keys = None,
+            }
+        }
+        last
+    }
+
     #[test]
     fn test_navigation_keys() {
-        let app = test_app();
+        let mut app = test_app();
         assert!(matches!(
             handle_key(make_key(KeyCode::Char('j')), &app),
             Some(Message::SelectNext)
@@ -299,10 +729,7 @@ mod tests {
             handle_key(make_key(KeyCode::Char('k')), &app),
             Some(Message::SelectPrev)
         ));
-        assert!(matches!(
-            handle_key(make_key(KeyCode::Char('g')), &app),
-            Some(Message::SelectFirst)
-        ));
+        assert!(matches!(press(&mut app, "gg"), Some(Message::SelectFirst)));
         assert!(matches!(
             handle_key(make_key(KeyCode::Char('G')), &app),
             Some(Message::SelectLast)
@@ -330,19 +757,23 @@ mod tests {
 
     #[test]
     fn test_feed_switch_keys() {
-        let app = test_app();
-        assert!(matches!(
-            handle_key(make_key(KeyCode::Char('1')), &app),
-            Some(Message::SwitchFeed(Feed::Favorites))
-        ));
-        assert!(matches!(
-            handle_key(make_key(KeyCode::Char('2')), &app),
-            Some(Message::SwitchFeed(Feed::Top))
-        ));
-        assert!(matches!(
-            handle_key(make_key(KeyCode::Char('5')), &app),
-            Some(Message::SwitchFeed(Feed::Ask))
-        ));
+        let mut app = test_app();
+        assert_eq!(press(&mut app, "g1"), Some(Message::SwitchFeedTab(0)));
+        assert_eq!(press(&mut app, "g5"), Some(Message::SwitchFeedTab(4)));
+    }
+
+    #[tokio::test]
+    async fn test_feed_switch_keys_include_custom_feeds() {
+        let mut app = test_app();
+        let custom = Feed::custom(vec![custom_feed("Rust", "rust")]);
+        app.feeds.extend(custom.iter().copied());
+        let msg = press(&mut app, "g8").unwrap();
+        app.update(msg);
+        assert_eq!(app.feed, custom[0]);
+        // Without a ninth feed, g9 does nothing
+        let msg = press(&mut app, "g9").unwrap();
+        app.update(msg);
+        assert_eq!(app.feed, custom[0]);
     }
 
     #[test]
@@ -401,6 +832,24 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn test_history_keys_work_in_both_views() {
+        for app in [test_app(), comments_app()] {
+            assert_eq!(
+                handle_key(make_key(KeyCode::Backspace), &app),
+                Some(Message::Back)
+            );
+            assert_eq!(
+                handle_key(make_key_with_mods(KeyCode::Left, KeyModifiers::ALT), &app),
+                Some(Message::Back)
+            );
+            assert_eq!(
+                handle_key(make_key_with_mods(KeyCode::Right, KeyModifiers::ALT), &app),
+                Some(Message::Forward)
+            );
+        }
+    }
+
     #[test]
     fn test_shared_keys_work_in_both_views() {
         let stories_app = test_app();
@@ -427,6 +876,18 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn test_tab_switches_panes_only_side_by_side() {
+        let mut app = comments_app();
+        assert_eq!(handle_key(make_key(KeyCode::Tab), &app), None);
+
+        app.viewport_width = Some(200);
+        assert_eq!(
+            handle_key(make_key(KeyCode::Tab), &app),
+            Some(Message::SwitchPane)
+        );
+    }
+
     #[test]
     fn test_keymap_extend_precedence() {
         // Later bindings take precedence
@@ -437,6 +898,205 @@ mod tests {
         assert!(matches!(extended.get(&event), Some(Message::Refresh)));
     }
 
+    #[test]
+    fn test_remapped_keys() {
+        let mut app = test_app();
+        app.keymaps.stories.rebind(
+            &Message::SelectNext,
+            &[vec![(KeyCode::Char('n'), KeyModifiers::NONE)]],
+        );
+        app.keymaps.global.rebind(&Message::ToggleDebug, &[]);
+
+        assert_eq!(
+            handle_key(make_key(KeyCode::Char('n')), &app),
+            Some(Message::SelectNext)
+        );
+        assert_eq!(handle_key(make_key(KeyCode::Char('j')), &app), None);
+        assert_eq!(handle_key(make_key(KeyCode::Char('`')), &app), None);
+        // Other contexts keep their defaults
+        assert_eq!(
+            handle_key(make_key(KeyCode::Char('j')), &comments_app()),
+            Some(Message::SelectNext)
+        );
+    }
+
+    #[test]
+    fn test_sequence_waits_for_next_key() {
+        let mut app = comments_app();
+        assert_eq!(
+            press(&mut app, "z"),
+            Some(Message::PendingKeys(PendingKeys {
+                count: None,
+                keys: vec![(KeyCode::Char('z'), KeyModifiers::NONE)],
+            }))
+        );
+        assert_eq!(app.pending_keys_text().as_deref(), Some("z"));
+        assert_eq!(press(&mut app, "c"), Some(Message::CollapseComment));
+
+        let mut app = comments_app();
+        assert_eq!(press(&mut app, "zo"), Some(Message::ExpandComment));
+        assert_eq!(press(&mut app, "zM"), Some(Message::CollapseThread));
+    }
+
+    #[test]
+    fn test_unbound_continuation_clears_pending() {
+        let mut app = test_app();
+        assert_eq!(press(&mut app, "gx"), Some(Message::ClearPendingKeys));
+        assert!(app.pending_keys.is_none());
+    }
+
+    #[test]
+    fn test_count_prefix_repeats_motion() {
+        let mut app = comments_app();
+        press(&mut app, "12");
+        assert_eq!(app.pending_keys_text().as_deref(), Some("12"));
+        assert_eq!(
+            press(&mut app, "j"),
+            Some(Message::Repeat(12, Box::new(Message::SelectNext)))
+        );
+
+        let mut app = comments_app();
+        let msg = {
+            press(&mut app, "3");
+            handle_key(
+                make_key_with_mods(KeyCode::Char('d'), KeyModifiers::CONTROL),
+                &app,
+            )
+        };
+        assert_eq!(msg, Some(Message::Repeat(3, Box::new(Message::PageDown))));
+
+        // Counts carry through sequences and are ignored by one-shot actions
+        let mut app = comments_app();
+        assert_eq!(press(&mut app, "4gg"), Some(Message::SelectFirst));
+        let mut app = comments_app();
+        assert_eq!(press(&mut app, "2o"), Some(Message::OpenUrl));
+    }
+
+    #[test]
+    fn test_bound_digits_are_not_counts() {
+        // Counts work in the stories view too
+        let mut app = test_app();
+        assert_eq!(
+            press(&mut app, "5j"),
+            Some(Message::Repeat(5, Box::new(Message::SelectNext)))
+        );
+        // A digit bound in keys.toml runs its action instead
+        app.keymaps = crate::keyconfig::parse("[stories]\nfeed_2 = [\"2\"]\n").unwrap();
+        assert_eq!(press(&mut app, "2"), Some(Message::SwitchFeedTab(1)));
+        // Once a count has started, any digit extends it
+        assert_eq!(
+            press(&mut app, "82j"),
+            Some(Message::Repeat(82, Box::new(Message::SelectNext)))
+        );
+        // 0 never starts a count
+        let mut app = comments_app();
+        assert_eq!(press(&mut app, "0"), None);
+    }
+
+    #[test]
+    fn test_pending_sequence_times_out() {
+        let mut app = comments_app();
+        app.keymaps.comments = app
+            .keymaps
+            .comments
+            .clone()
+            .bind(KeyCode::Char('z'), Message::ToggleFavorite);
+
+        // 'z' alone is bound, but waits because 'zc' etc. are longer bindings
+        press(&mut app, "z");
+        let (_, since) = app.pending_keys.clone().unwrap();
+        assert_eq!(expire_pending(&app, since), None);
+        assert_eq!(
+            expire_pending(&app, since + PENDING_TIMEOUT),
+            Some(Message::ToggleFavorite)
+        );
+
+        // Without an exact binding the keys are dropped
+        let mut app = test_app();
+        press(&mut app, "5g");
+        let (_, since) = app.pending_keys.clone().unwrap();
+        assert_eq!(
+            expire_pending(&app, since + PENDING_TIMEOUT),
+            Some(Message::ClearPendingKeys)
+        );
+    }
+
+    #[test]
+    fn test_command_line_captures_typing() {
+        let mut app = test_app();
+        assert_eq!(
+            handle_key(make_key(KeyCode::Char(':')), &app),
+            Some(Message::OpenCommandLine)
+        );
+        app.update(Message::OpenCommandLine);
+
+        assert_eq!(
+            handle_key(make_key(KeyCode::Char('q')), &app),
+            Some(Message::CommandLineInput('q'))
+        );
+        assert_eq!(
+            handle_key(
+                make_key_with_mods(KeyCode::Char('F'), KeyModifiers::SHIFT),
+                &app
+            ),
+            Some(Message::CommandLineInput('F'))
+        );
+        assert_eq!(
+            handle_key(make_key(KeyCode::Tab), &app),
+            Some(Message::CommandLineComplete)
+        );
+        assert_eq!(
+            handle_key(make_key(KeyCode::Esc), &app),
+            Some(Message::CloseCommandLine)
+        );
+    }
+
+    #[test]
+    fn test_search_prompt_captures_typing() {
+        let mut app = test_app();
+        assert_eq!(
+            handle_key(make_key(KeyCode::Char('?')), &app),
+            Some(Message::OpenSearch(SearchDirection::Backward))
+        );
+        assert_eq!(
+            handle_key(make_key(KeyCode::F(1)), &app),
+            Some(Message::ToggleHelp)
+        );
+        app.update(Message::OpenSearch(SearchDirection::Forward));
+
+        assert_eq!(
+            handle_key(make_key(KeyCode::Char('n')), &app),
+            Some(Message::SearchInput('n'))
+        );
+        assert_eq!(
+            handle_key(make_key(KeyCode::Enter), &app),
+            Some(Message::SubmitSearch)
+        );
+        assert_eq!(
+            handle_key(make_key(KeyCode::Esc), &app),
+            Some(Message::CancelSearch)
+        );
+    }
+
+    #[test]
+    fn test_hint_mode_captures_typing() {
+        let mut app = comments_app();
+        assert_eq!(
+            handle_key(make_key(KeyCode::Char('x')), &app),
+            Some(Message::ShowHints(HintAction::Open))
+        );
+        app.hints = Some(crate::hints::Hints::new(HintAction::Open, Vec::new()));
+
+        assert_eq!(
+            handle_key(make_key(KeyCode::Char('j')), &app),
+            Some(Message::HintInput('j'))
+        );
+        assert_eq!(
+            handle_key(make_key(KeyCode::Esc), &app),
+            Some(Message::CancelHints)
+        );
+    }
+
     #[test]
     fn test_unknown_key_returns_none() {
         let app = test_app();
@@ -1,11 +1,20 @@
 mod api;
 mod app;
 mod cli;
+mod command;
+mod comment_reads;
 mod comment_tree;
 mod event;
 mod help;
+mod highlight;
+mod hints;
+mod hyperlink;
+mod keyconfig;
 mod keys;
 mod logging;
+mod mouse;
+mod nav;
+mod search;
 mod settings;
 mod storage;
 mod theme;
@@ -18,20 +27,25 @@ mod widgets;
 mod test_utils;
 
 use anyhow::{Context, Result, bail};
-use app::{App, Message, View};
+use api::Feed;
+use app::{App, COMMAND_HISTORY_KEY, LAST_FEED_KEY, Message, View};
 use clap::Parser;
 use cli::{Cli, Commands, OutputFormat, ThemeArgs, ThemeCommands};
+use comment_tree::CommentSort;
 use event::Event;
+use highlight::Highlights;
+use hyperlink::Hyperlinks;
 use ratatui::Frame;
-use settings::Settings;
+use settings::{Settings, TabSettings};
+use std::io::Write;
 use std::path::{Path, PathBuf};
-use std::time::Duration;
-use storage::{Storage, StorageLocation};
+use std::time::Instant;
+use storage::{COMMENT_SORT_KEY, Storage, StorageLocation};
 use theme::{
     ResolvedTheme, ThemeVariant, all_themes, by_name, default_for_variant, detect_terminal_theme,
     load_theme_file,
 };
-use tokio::time::interval;
+use tokio::time::sleep_until;
 use tui::CrosstermEvents;
 
 #[tokio::main]
@@ -147,6 +161,20 @@ fn resolve_theme(
     Ok(default_for_variant(variant))
 }
 
+/// Bring back what the last session left behind: the feed, command history
+/// and comment sort.
+async fn restore_state(app: &mut App, tabs: &TabSettings) {
+    let storage = app.client.storage().clone();
+    let last_feed = storage.get_state(LAST_FEED_KEY).await.ok().flatten();
+    app.arrange_tabs(tabs, last_feed.as_deref());
+    if let Ok(Some(history)) = storage.get_state(COMMAND_HISTORY_KEY).await {
+        app.command_history = history.lines().map(str::to_string).collect();
+    }
+    if let Ok(Some(sort)) = storage.get_state(COMMENT_SORT_KEY).await {
+        app.comment_sort = CommentSort::from_label(&sort).unwrap_or_default();
+    }
+}
+
 async fn run_tui(cli: Cli, mut terminal: tui::Tui) -> Result<()> {
     let config_dir = settings::config_dir(cli.config_dir.as_ref())
         .context("Could not determine config directory. Set XDG_CONFIG_HOME or use --config-dir")?;
@@ -156,27 +184,64 @@ async fn run_tui(cli: Cli, mut terminal: tui::Tui) -> Result<()> {
     let storage = Storage::open(StorageLocation::Path(settings::db_path(&config_dir)))
         .context("Failed to open storage database")?;
     let resolved_theme = resolve_theme(&cli, &settings, Some(&config_dir))?;
+    let keys_path = settings::keys_path(&config_dir);
+    let keymaps = keyconfig::load(&keys_path)
+        .with_context(|| format!("Failed to load keybindings from {}", keys_path.display()))?;
     let mut app = App::new(resolved_theme, Some(config_dir), storage);
+    app.keymaps = keymaps;
+    app.highlights = Highlights::new(settings.highlights);
+    app.feeds.extend(Feed::custom(settings.feeds));
+    restore_state(&mut app, &settings.tabs).await;
+    if let Some(width) = settings.split_min_width {
+        app.split_min_width = width;
+    }
+    if settings.mouse == Some(false) {
+        tui::release_mouse()?;
+    }
     let mut events = CrosstermEvents::new();
-    let mut tick = interval(Duration::from_millis(16));
+    let mut redraw_at = Instant::now();
     let mut last_height: Option<u16> = None;
+    let mut last_width: Option<u16> = None;
+    let mut hyperlinks = settings
+        .hyperlinks
+        .unwrap_or_else(hyperlink::detected)
+        .then(Hyperlinks::default);
 
     app.load_stories();
 
     loop {
-        terminal.draw(|frame| render(&app, frame))?;
+        // Draw only when something changed or is due to, so an idle session
+        // sleeps until the next input
+        let now = Instant::now();
+        if app.dirty || now >= redraw_at {
+            draw(&mut terminal, &app, hyperlinks.as_mut())?;
+            app.mark_shown_comments_read();
+            app.dirty = false;
+            redraw_at = app.redraw_deadline(now);
+        }
 
-        // Track viewport height changes for dynamic story loading
-        let current_height = terminal.size()?.height;
-        if last_height != Some(current_height) {
-            last_height = Some(current_height);
-            app.update(Message::UpdateViewportHeight(current_height));
+        // Track viewport height changes for dynamic story loading, and the
+        // width for the split layout
+        let size = terminal.size()?;
+        if last_height != Some(size.height) {
+            last_height = Some(size.height);
+            app.update(Message::UpdateViewportHeight(size.height));
+        }
+        if last_width != Some(size.width) {
+            last_width = Some(size.width);
+            app.update(Message::UpdateViewportWidth(size.width));
         }
 
         if app.should_quit {
             break;
         }
+        if app.dirty {
+            continue;
+        }
 
+        let wake_at = app
+            .timer_deadline()
+            .map_or(redraw_at, |at| at.min(redraw_at));
         tokio::select! {
             event = events.next() => {
                 match event? {
@@ -185,7 +250,12 @@ async fn run_tui(cli: Cli, mut terminal: tui::Tui) -> Result<()> {
                             app.update(msg);
                         }
                     }
-                    Event::Resize => {}
+                    Event::Mouse(mouse) => {
+                        if let Some(msg) = mouse::handle_mouse(mouse, &app) {
+                            app.update(msg);
+                        }
+                    }
+                    Event::Resize => app.dirty = true,
                 }
             }
             result = app.result_rx.recv() => {
@@ -193,20 +263,50 @@ async fn run_tui(cli: Cli, mut terminal: tui::Tui) -> Result<()> {
                     app.handle_async_result(result);
                 }
             }
-            _ = tick.tick() => {}
+            () = sleep_until(wake_at.into()) => {}
+        }
+        let now = Instant::now();
+        if let Some(msg) = keys::expire_pending(&app, now) {
+            app.update(msg);
         }
+        app.update_preview(now);
     }
 
+    // The thread open at exit is picked up where it was left next time, with
+    // the comments read in it
+    if let Some((story_id, state)) = app.comment_view_state() {
+        let storage = app.client.storage();
+        let _ = storage.save_comment_view_state(story_id, state).await;
+    }
+    if let Some((story_id, ids)) = app.comment_reads.take_unsaved() {
+        let _ = app.client.storage().mark_comments_read(story_id, ids).await;
+    }
     if let Some(err) = app.load.error.as_ref() {
         bail!("{err}")
     }
     Ok(())
 }
 
+/// Draw a frame, then turn the links in it into hyperlinks.
+fn draw(terminal: &mut tui::Tui, app: &App, hyperlinks: Option<&mut Hyperlinks>) -> Result<()> {
+    let frame = terminal.draw(|frame| render(app, frame))?;
+    if let Some(hyperlinks) = hyperlinks {
+        let escapes = hyperlinks.escapes(frame.buffer, app.link_areas.borrow().links())?;
+        if !escapes.is_empty() {
+            let backend = terminal.backend_mut();
+            backend.write_all(&escapes)?;
+            backend.flush()?;
+        }
+    }
+    Ok(())
+}
+
 fn render(app: &App, frame: &mut Frame) {
     use ratatui::layout::{Constraint, Layout};
 
     let area = frame.area();
+    app.hit_areas.borrow_mut().clear();
+    app.link_areas.borrow_mut().clear();
 
     // Split area for debug pane if visible
     let (main_area, debug_area) = if app.debug.visible {
@@ -220,15 +320,33 @@ fn render(app: &App, frame: &mut Frame) {
         (area, None)
     };
 
-    match &app.view {
-        View::Stories => views::stories::render(frame, app, main_area),
-        View::Comments { .. } => views::comments::render(frame, app, main_area),
+    if app.is_split() {
+        let [stories_area, comments_area] =
+            Layout::horizontal([Constraint::Percentage(45), Constraint::Percentage(55)])
+                .areas(main_area);
+        views::stories::render(frame, app, stories_area);
+        match &app.view {
+            View::User { .. } => views::user::render(frame, app, comments_area),
+            View::Stories | View::Comments { .. } => {
+                views::comments::render(frame, app, comments_area);
+            }
+        }
+    } else {
+        match &app.view {
+            View::Stories => views::stories::render(frame, app, main_area),
+            View::Comments { .. } => views::comments::render(frame, app, main_area),
+            View::User { .. } => views::user::render(frame, app, main_area),
+        }
     }
 
     if let Some(debug_area) = debug_area {
         views::debug::render(frame, app, debug_area);
     }
 
+    // The command line and search prompt replace the view's status bar
+    views::command_line::render(frame, app, main_area);
+    views::search::render(frame, app, main_area);
+
     // Render context menu overlay if open (highest priority)
     if app.context_menu.is_some() {
         views::context_menu::render(frame, app, area);
@@ -0,0 +1,144 @@
+//! Mouse support: views record what is drawn where, and clicks and wheel
+//! events are mapped to messages against the last rendered frame.
+
+use std::time::Duration;
+
+use crossterm::event::{MouseButton, MouseEvent, MouseEventKind};
+use ratatui::layout::{Position, Rect};
+
+use crate::api::Feed;
+use crate::app::{App, Message};
+
+/// Two clicks on the same target within this window count as a double-click.
+pub const DOUBLE_CLICK: Duration = Duration::from_millis(400);
+
+/// Items moved per wheel notch.
+const SCROLL_ITEMS: u16 = 3;
+
+/// Something on screen that reacts to a click.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ClickTarget {
+    Tab(Feed),
+    /// A story by its index in the list.
+    Story(usize),
+    /// A comment by its visible index.
+    Comment(usize),
+    /// The `[+]`/`[-]` marker of a comment, by visible index.
+    CommentToggle(usize),
+}
+
+/// Click targets recorded while rendering the current frame.
+#[derive(Debug, Default)]
+pub struct HitAreas {
+    targets: Vec<(Rect, ClickTarget)>,
+}
+
+impl HitAreas {
+    pub fn clear(&mut self) {
+        self.targets.clear();
+    }
+
+    pub fn add(&mut self, area: Rect, target: ClickTarget) {
+        self.targets.push((area, target));
+    }
+
+    /// Everything clickable in the last frame, in the order it was drawn.
+    pub fn targets(&self) -> impl Iterator<Item = ClickTarget> + '_ {
+        self.targets.iter().map(|&(_, target)| target)
+    }
+
+    /// The target under a cell. Areas added later sit on top of earlier ones.
+    pub fn target_at(&self, column: u16, row: u16) -> Option<ClickTarget> {
+        self.targets
+            .iter()
+            .rev()
+            .find(|(area, _)| area.contains(Position::new(column, row)))
+            .map(|&(_, target)| target)
+    }
+}
+
+pub fn handle_mouse(event: MouseEvent, app: &App) -> Option<Message> {
+    // Popups and prompts are keyboard-only
+    if app.command_line.is_some()
+        || app.search.prompt.is_some()
+        || app.hints.is_some()
+        || app.context_menu.is_some()
+        || app.theme_picker.is_some()
+        || app.help_overlay
+    {
+        return None;
+    }
+
+    match event.kind {
+        MouseEventKind::Down(MouseButton::Left) => app
+            .hit_areas
+            .borrow()
+            .target_at(event.column, event.row)
+            .map(Message::Click),
+        MouseEventKind::ScrollDown => {
+            Some(Message::Repeat(SCROLL_ITEMS, Box::new(Message::SelectNext)))
+        }
+        MouseEventKind::ScrollUp => {
+            Some(Message::Repeat(SCROLL_ITEMS, Box::new(Message::SelectPrev)))
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::TestAppBuilder;
+    use crossterm::event::KeyModifiers;
+
+    fn mouse(kind: MouseEventKind, column: u16, row: u16) -> MouseEvent {
+        MouseEvent {
+            kind,
+            column,
+            row,
+            modifiers: KeyModifiers::NONE,
+        }
+    }
+
+    #[test]
+    fn later_areas_win() {
+        let mut hits = HitAreas::default();
+        hits.add(Rect::new(0, 0, 10, 3), ClickTarget::Comment(0));
+        hits.add(Rect::new(4, 0, 3, 1), ClickTarget::CommentToggle(0));
+
+        assert_eq!(hits.target_at(5, 0), Some(ClickTarget::CommentToggle(0)));
+        assert_eq!(hits.target_at(5, 1), Some(ClickTarget::Comment(0)));
+        assert_eq!(hits.target_at(10, 0), None);
+    }
+
+    #[test]
+    fn clicks_and_wheel_map_to_messages() {
+        let app = TestAppBuilder::new().build();
+        app.hit_areas
+            .borrow_mut()
+            .add(Rect::new(0, 2, 80, 2), ClickTarget::Story(0));
+
+        let click = MouseEventKind::Down(MouseButton::Left);
+        assert_eq!(
+            handle_mouse(mouse(click, 3, 3), &app),
+            Some(Message::Click(ClickTarget::Story(0)))
+        );
+        assert_eq!(handle_mouse(mouse(click, 3, 9), &app), None);
+        assert_eq!(
+            handle_mouse(mouse(MouseEventKind::ScrollDown, 0, 0), &app),
+            Some(Message::Repeat(3, Box::new(Message::SelectNext)))
+        );
+        assert_eq!(handle_mouse(mouse(MouseEventKind::Moved, 3, 3), &app), None);
+    }
+
+    #[test]
+    fn popups_ignore_the_mouse() {
+        let app = TestAppBuilder::new().help_overlay().build();
+        app.hit_areas
+            .borrow_mut()
+            .add(Rect::new(0, 0, 80, 24), ClickTarget::Story(0));
+
+        let click = MouseEventKind::Down(MouseButton::Left);
+        assert_eq!(handle_mouse(mouse(click, 1, 1), &app), None);
+    }
+}
@@ -0,0 +1,138 @@
+//! Back/forward history between the story list and comment threads.
+
+use crate::app::View;
+use crate::comment_tree::CommentTree;
+
+/// Most views kept to go back to. The story list at the bottom is never dropped.
+const MAX_DEPTH: usize = 50;
+
+/// A view that was navigated away from, with what's needed to put it back
+/// the way it was.
+#[derive(Debug)]
+pub struct NavEntry {
+    pub view: View,
+    pub selected_index: usize,
+    pub scroll_offset: usize,
+    /// Comments with their expansion state. Empty for the story list.
+    pub comment_tree: CommentTree,
+    pub comments_fetched_at: Option<u64>,
+    /// The comments were still loading when the view was left.
+    pub loading: bool,
+    /// The comment the thread was zoomed into.
+    pub comment_zoom: Option<u64>,
+}
+
+/// Browser-style history: views to go back to, most recent last, and views
+/// gone back from, to go forward to again.
+#[derive(Debug, Default)]
+pub struct NavStack {
+    back: Vec<NavEntry>,
+    forward: Vec<NavEntry>,
+}
+
+impl NavStack {
+    /// Record `current` before going somewhere new, which drops the forward
+    /// history.
+    pub fn push(&mut self, current: NavEntry) {
+        self.forward.clear();
+        self.push_back(current);
+    }
+
+    /// Step back, keeping `current` to go forward to.
+    pub fn back(&mut self, current: NavEntry) -> Option<NavEntry> {
+        let entry = self.back.pop()?;
+        self.forward.push(current);
+        Some(entry)
+    }
+
+    /// Step forward again, keeping `current` to go back to.
+    pub fn forward(&mut self, current: NavEntry) -> Option<NavEntry> {
+        let entry = self.forward.pop()?;
+        self.push_back(current);
+        Some(entry)
+    }
+
+    pub fn clear(&mut self) {
+        self.back.clear();
+        self.forward.clear();
+    }
+
+    /// The view the history started from, i.e. the story list.
+    pub fn root(&self) -> Option<&NavEntry> {
+        self.back.first()
+    }
+
+    /// The comment view for `story_id` waiting on its comments, if any.
+    pub fn loading_entry_mut(&mut self, story_id: u64) -> Option<&mut NavEntry> {
+        self.back.iter_mut().chain(&mut self.forward).find(|entry| {
+            entry.loading && matches!(&entry.view, View::Comments { story } if story.id == story_id)
+        })
+    }
+
+    fn push_back(&mut self, entry: NavEntry) {
+        self.back.push(entry);
+        if self.back.len() > MAX_DEPTH {
+            self.back.remove(1);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::comments_view;
+
+    fn entry(view: View, selected_index: usize) -> NavEntry {
+        NavEntry {
+            view,
+            selected_index,
+            scroll_offset: 0,
+            comment_tree: CommentTree::new(),
+            comments_fetched_at: None,
+            loading: false,
+            comment_zoom: None,
+        }
+    }
+
+    #[test]
+    fn back_and_forward_retrace_the_path() {
+        let mut nav = NavStack::default();
+        nav.push(entry(View::Stories, 4));
+        nav.push(entry(comments_view(1, "One"), 2));
+
+        let back = nav.back(entry(comments_view(2, "Two"), 0)).unwrap();
+        assert_eq!(back.view, comments_view(1, "One"));
+        assert_eq!(back.selected_index, 2);
+        let back = nav.back(back).unwrap();
+        assert_eq!(back.selected_index, 4);
+        assert!(nav.back(entry(View::Stories, 0)).is_none());
+
+        let forward = nav.forward(back).unwrap();
+        assert_eq!(forward.view, comments_view(1, "One"));
+        let forward = nav.forward(forward).unwrap();
+        assert_eq!(forward.view, comments_view(2, "Two"));
+        assert!(nav.forward(entry(View::Stories, 0)).is_none());
+    }
+
+    #[test]
+    fn going_somewhere_new_drops_forward_history() {
+        let mut nav = NavStack::default();
+        nav.push(entry(View::Stories, 0));
+        let stories = nav.back(entry(comments_view(1, "One"), 0)).unwrap();
+
+        nav.push(stories);
+        assert!(nav.forward(entry(comments_view(2, "Two"), 0)).is_none());
+    }
+
+    #[test]
+    fn deep_history_keeps_the_story_list() {
+        let mut nav = NavStack::default();
+        nav.push(entry(View::Stories, 7));
+        for id in 0..100 {
+            nav.push(entry(comments_view(id, "Thread"), 0));
+        }
+
+        assert_eq!(nav.back.len(), MAX_DEPTH);
+        assert_eq!(nav.root().unwrap().selected_index, 7);
+    }
+}
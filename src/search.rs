@@ -0,0 +1,227 @@
+//! `/` and `?` search within the current view.
+//!
+//! Matching is case-insensitive. Stories match on their title, comments on
+//! their text or author. Matches are identified by position: the story index,
+//! or the comment's index in the full (not just visible) comment list.
+
+use std::cell::{Ref, RefCell};
+use std::ops::Range;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SearchDirection {
+    #[default]
+    Forward,
+    Backward,
+}
+
+impl SearchDirection {
+    pub const fn reversed(self) -> Self {
+        match self {
+            Self::Forward => Self::Backward,
+            Self::Backward => Self::Forward,
+        }
+    }
+
+    pub const fn prompt(self) -> char {
+        match self {
+            Self::Forward => '/',
+            Self::Backward => '?',
+        }
+    }
+}
+
+/// The last submitted search, plus the prompt while a new one is typed.
+#[derive(Debug, Clone, Default)]
+pub struct Search {
+    /// Query used by `n`/`N`.
+    pub query: String,
+    pub direction: SearchDirection,
+    pub prompt: Option<SearchPrompt>,
+    /// Matches found for the pattern, kept for redraws and `n`/`N`.
+    matches: RefCell<Option<Matches>>,
+}
+
+/// What a search looked through: the story list or the comments, at a
+/// revision that changes whenever their contents or order do.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Searched {
+    Stories(u64),
+    Comments(u64),
+    Nothing,
+}
+
+#[derive(Debug, Clone)]
+struct Matches {
+    pattern: String,
+    searched: Searched,
+    positions: Vec<usize>,
+}
+
+#[derive(Debug, Clone)]
+pub struct SearchPrompt {
+    pub input: String,
+    pub direction: SearchDirection,
+    /// Position selected when the prompt opened, restored on cancel.
+    pub origin: Option<usize>,
+}
+
+impl Search {
+    /// The text to highlight: the prompt input while typing, else the last
+    /// submitted query.
+    pub fn pattern(&self) -> &str {
+        self.prompt
+            .as_ref()
+            .map_or(self.query.as_str(), |prompt| prompt.input.as_str())
+    }
+
+    /// Positions matching the pattern in `searched`. `find` only runs when
+    /// the pattern or what's searched has changed since the last call.
+    pub fn matches(
+        &self,
+        searched: Searched,
+        find: impl FnOnce(&str) -> Vec<usize>,
+    ) -> Ref<'_, [usize]> {
+        let pattern = self.pattern();
+        let fresh = self
+            .matches
+            .borrow()
+            .as_ref()
+            .is_some_and(|m| m.pattern == pattern && m.searched == searched);
+        if !fresh {
+            let positions = if pattern.is_empty() {
+                Vec::new()
+            } else {
+                find(pattern)
+            };
+            *self.matches.borrow_mut() = Some(Matches {
+                pattern: pattern.to_string(),
+                searched,
+                positions,
+            });
+        }
+        Ref::map(self.matches.borrow(), |m| {
+            m.as_ref().map_or(&[][..], |m| m.positions.as_slice())
+        })
+    }
+}
+
+/// Byte ranges of the non-overlapping matches of `query` in `text`.
+pub fn find(text: &str, query: &str) -> Vec<Range<usize>> {
+    if query.is_empty() {
+        return Vec::new();
+    }
+    // ASCII lowercasing keeps byte offsets valid for the original text
+    let text = text.to_ascii_lowercase();
+    let query = query.to_ascii_lowercase();
+    text.match_indices(&query)
+        .map(|(start, m)| start..start + m.len())
+        .collect()
+}
+
+pub fn matches(text: &str, query: &str) -> bool {
+    !query.is_empty()
+        && text
+            .to_ascii_lowercase()
+            .contains(&query.to_ascii_lowercase())
+}
+
+/// The match to move to from position `from`, wrapping around the ends.
+/// `from` itself counts when `inclusive` is set, so typing a query doesn't
+/// move off an item that already matches. Returns the match and whether the
+/// search wrapped.
+pub fn next_match(
+    matches: &[usize],
+    from: usize,
+    direction: SearchDirection,
+    inclusive: bool,
+) -> Option<(usize, bool)> {
+    let found = match direction {
+        SearchDirection::Forward => matches
+            .iter()
+            .find(|&&m| m > from || (inclusive && m == from)),
+        SearchDirection::Backward => matches
+            .iter()
+            .rev()
+            .find(|&&m| m < from || (inclusive && m == from)),
+    };
+    if let Some(&m) = found {
+        return Some((m, false));
+    }
+    let wrapped = match direction {
+        SearchDirection::Forward => matches.first(),
+        SearchDirection::Backward => matches.last(),
+    };
+    wrapped.map(|&m| (m, true))
+}
+
+/// Status bar text such as "match 3/17" when `position` is one of the
+/// matches, or the match count otherwise.
+pub fn status_text(matches: &[usize], position: Option<usize>) -> String {
+    let current = position.and_then(|p| matches.iter().position(|&m| m == p));
+    match (current, matches.len()) {
+        (_, 0) => "no matches".to_string(),
+        (Some(i), total) => format!("match {}/{total}", i + 1),
+        (None, 1) => "1 match".to_string(),
+        (None, total) => format!("{total} matches"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn find_is_case_insensitive() {
+        assert_eq!(find("Rust and rust", "RUST"), vec![0..4, 9..13]);
+        assert_eq!(find("Ünïcode rust", "rust"), vec![10..14]);
+        assert!(find("anything", "").is_empty());
+        assert!(matches("Show HN: Rust", "show hn"));
+        assert!(!matches("Show HN", ""));
+    }
+
+    #[test]
+    fn matches_are_found_again_only_when_stale() {
+        let search = Search {
+            query: "rust".to_string(),
+            ..Search::default()
+        };
+        let calls = std::cell::Cell::new(0);
+        let find = |_: &str| {
+            calls.set(calls.get() + 1);
+            vec![3]
+        };
+        assert_eq!(&*search.matches(Searched::Stories(1), find), [3]);
+        assert_eq!(&*search.matches(Searched::Stories(1), find), [3]);
+        assert_eq!(calls.get(), 1);
+
+        search.matches(Searched::Stories(2), find);
+        search.matches(Searched::Comments(2), find);
+        assert_eq!(calls.get(), 3);
+
+        // An empty pattern matches nothing without searching
+        let search = Search::default();
+        assert!(search.matches(Searched::Stories(1), find).is_empty());
+        assert_eq!(calls.get(), 3);
+    }
+
+    #[test]
+    fn next_match_wraps_in_both_directions() {
+        let matches = [2, 5, 9];
+        let forward = SearchDirection::Forward;
+        let backward = SearchDirection::Backward;
+        assert_eq!(next_match(&matches, 2, forward, false), Some((5, false)));
+        assert_eq!(next_match(&matches, 2, forward, true), Some((2, false)));
+        assert_eq!(next_match(&matches, 9, forward, false), Some((2, true)));
+        assert_eq!(next_match(&matches, 5, backward, false), Some((2, false)));
+        assert_eq!(next_match(&matches, 1, backward, false), Some((9, true)));
+        assert_eq!(next_match(&[], 0, forward, true), None);
+    }
+
+    #[test]
+    fn status_text_counts_matches() {
+        assert_eq!(status_text(&[1, 4, 7], Some(4)), "match 2/3");
+        assert_eq!(status_text(&[1, 4, 7], Some(3)), "3 matches");
+        assert_eq!(status_text(&[1], None), "1 match");
+        assert_eq!(status_text(&[], Some(0)), "no matches");
+    }
+}
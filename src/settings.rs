@@ -4,6 +4,9 @@ use std::path::{Path, PathBuf};
 use anyhow::{Context, Result, bail};
 use serde::{Deserialize, Serialize};
 
+use crate::api::{CustomFeed, Feed};
+use crate::highlight::HighlightRule;
+
 const APP_SENTINEL: &str = "5xx.engineer-hn";
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -13,6 +16,103 @@ pub struct Settings {
 
     #[serde(skip_serializing_if = "Option::is_none")]
     pub theme: Option<String>,
+
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub highlights: Vec<HighlightRule>,
+
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub feeds: Vec<CustomFeed>,
+
+    #[serde(default, skip_serializing_if = "TabSettings::is_default")]
+    pub tabs: TabSettings,
+
+    /// Terminal width from which stories and comments are shown side by
+    /// side. 0 turns the split layout off.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub split_min_width: Option<u16>,
+
+    /// Make links clickable with OSC 8 escapes. Detected from `TERM` when
+    /// unset.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hyperlinks: Option<bool>,
+
+    /// Capture the mouse for clicking and scrolling. Off leaves it to the
+    /// terminal, for selecting text.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mouse: Option<bool>,
+}
+
+/// Feed tab bar layout. Feeds are referenced by label ("Favs") or key
+/// ("favorites"); custom feeds by their name.
+///
+/// ```toml
+/// [tabs]
+/// order = ["best", "rust", "top"]
+/// hidden = ["jobs"]
+/// startup = "best"
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TabSettings {
+    /// Tabs to show first, in this order. Unlisted feeds follow in their default order.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub order: Vec<String>,
+
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub hidden: Vec<String>,
+
+    /// Feed to open on startup. When unset, the last active feed is restored.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub startup: Option<String>,
+}
+
+impl TabSettings {
+    fn is_default(&self) -> bool {
+        self == &Self::default()
+    }
+
+    /// Arranges `feeds` into tab order, dropping hidden ones.
+    pub fn arrange(&self, feeds: &[Feed]) -> Vec<Feed> {
+        let listed = self
+            .order
+            .iter()
+            .filter_map(|name| feeds.iter().find(|f| f.matches_name(name)));
+        let mut tabs: Vec<Feed> = Vec::with_capacity(feeds.len());
+        for &feed in listed.chain(feeds) {
+            let hidden = self.hidden.iter().any(|name| feed.matches_name(name));
+            if !hidden && !tabs.contains(&feed) {
+                tabs.push(feed);
+            }
+        }
+        tabs
+    }
+
+    fn validate(&self, custom_feeds: &[CustomFeed]) -> Result<()> {
+        let known = |name: &str| {
+            Feed::all().iter().any(|f| f.matches_name(name))
+                || custom_feeds
+                    .iter()
+                    .any(|f| f.name.trim().eq_ignore_ascii_case(name.trim()))
+        };
+        let names = self.order.iter().chain(&self.hidden).chain(&self.startup);
+        for name in names {
+            if !known(name) {
+                bail!("Unknown feed '{name}' in [tabs]");
+            }
+        }
+        let is_hidden = |name: &str| {
+            self.hidden
+                .iter()
+                .any(|h| h.trim().eq_ignore_ascii_case(name))
+        };
+        let all_hidden = Feed::all()
+            .iter()
+            .all(|f| self.hidden.iter().any(|h| f.matches_name(h)))
+            && custom_feeds.iter().all(|f| is_hidden(f.name.trim()));
+        if all_hidden {
+            bail!("[tabs] hides every feed");
+        }
+        Ok(())
+    }
 }
 
 impl Default for Settings {
@@ -20,6 +120,12 @@ impl Default for Settings {
         Self {
             app: APP_SENTINEL.to_string(),
             theme: None,
+            highlights: Vec::new(),
+            feeds: Vec::new(),
+            tabs: TabSettings::default(),
+            split_min_width: None,
+            hyperlinks: None,
+            mouse: None,
         }
     }
 }
@@ -63,7 +169,27 @@ impl Settings {
                 self.app
             );
         }
-        Ok(())
+        let mut names = std::collections::HashSet::new();
+        for feed in &self.feeds {
+            let name = feed.name.trim();
+            if name.is_empty() {
+                bail!("Custom feeds must have a name");
+            }
+            let is_builtin = Feed::all()
+                .iter()
+                .any(|f| f.label().eq_ignore_ascii_case(name));
+            if is_builtin || !names.insert(name.to_lowercase()) {
+                bail!("Duplicate feed name '{name}'");
+            }
+            if feed.query.trim().is_empty() && feed.domains.is_empty() {
+                bail!("Custom feed '{name}' needs a query or a list of domains");
+            }
+            // Domain feeds search URLs, so there's nowhere for a query to go
+            if !feed.query.trim().is_empty() && !feed.domains.is_empty() {
+                bail!("Custom feed '{name}' takes a query or a list of domains, not both");
+            }
+        }
+        self.tabs.validate(&self.feeds)
     }
 }
 
@@ -77,6 +203,10 @@ pub fn settings_path(config_dir: &Path) -> PathBuf {
     config_dir.join("settings.toml")
 }
 
+pub fn keys_path(config_dir: &Path) -> PathBuf {
+    config_dir.join("keys.toml")
+}
+
 pub fn themes_dir(config_dir: &Path) -> PathBuf {
     config_dir.join("themes")
 }
@@ -167,6 +297,131 @@ mod tests {
         assert_eq!(loaded.theme, original.theme);
     }
 
+    #[test]
+    fn load_highlight_rules() {
+        let temp = TempDir::new().unwrap();
+        let path = temp.path().join("settings.toml");
+
+        fs::write(
+            &path,
+            "_app = \"5xx.engineer-hn\"\n\n[[highlights]]\ntitle = \"rust\"\n\n[[highlights]]\ndomain = \"lwn.net\"\n",
+        )
+        .unwrap();
+
+        let settings = Settings::load(&path).unwrap();
+
+        assert_eq!(
+            settings.highlights,
+            vec![
+                HighlightRule::Title("rust".to_string()),
+                HighlightRule::Domain("lwn.net".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn load_custom_feeds() {
+        let temp = TempDir::new().unwrap();
+        let path = temp.path().join("settings.toml");
+
+        fs::write(
+            &path,
+            r#"_app = "5xx.engineer-hn"
+
+[[feeds]]
+name = "Rust"
+query = "rust"
+min_points = 50
+
+[[feeds]]
+name = "Blogs"
+domains = ["lwn.net"]
+"#,
+        )
+        .unwrap();
+
+        let settings = Settings::load(&path).unwrap();
+
+        assert_eq!(settings.feeds.len(), 2);
+        assert_eq!(settings.feeds[0].name, "Rust");
+        assert_eq!(settings.feeds[0].min_points, Some(50));
+        assert_eq!(settings.feeds[0].tags(), "story");
+        assert_eq!(settings.feeds[1].domains, vec!["lwn.net".to_string()]);
+    }
+
+    #[test]
+    fn custom_feed_validation() {
+        let temp = TempDir::new().unwrap();
+        let path = temp.path().join("settings.toml");
+
+        fs::write(
+            &path,
+            "_app = \"5xx.engineer-hn\"\n\n[[feeds]]\nname = \"Top\"\nquery = \"x\"\n",
+        )
+        .unwrap();
+        let err = Settings::load(&path).unwrap_err().to_string();
+        assert!(err.contains("Duplicate feed name"));
+
+        fs::write(
+            &path,
+            "_app = \"5xx.engineer-hn\"\n\n[[feeds]]\nname = \"Empty\"\n",
+        )
+        .unwrap();
+        let err = Settings::load(&path).unwrap_err().to_string();
+        assert!(err.contains("needs a query"));
+
+        fs::write(
+            &path,
+            "_app = \"5xx.engineer-hn\"\n\n[[feeds]]\nname = \"Both\"\nquery = \"rust\"\ndomains = [\"lwn.net\"]\n",
+        )
+        .unwrap();
+        let err = Settings::load(&path).unwrap_err().to_string();
+        assert!(err.contains("not both"));
+    }
+
+    #[test]
+    fn tabs_arrange_order_and_hidden() {
+        let tabs = TabSettings {
+            order: vec!["best".to_string(), "Favs".to_string()],
+            hidden: vec!["jobs".to_string(), "new".to_string()],
+            startup: None,
+        };
+
+        let arranged = tabs.arrange(Feed::all());
+
+        assert_eq!(
+            arranged,
+            vec![
+                Feed::Best,
+                Feed::Favorites,
+                Feed::Top,
+                Feed::Ask,
+                Feed::Show
+            ]
+        );
+    }
+
+    #[test]
+    fn tabs_validation_rejects_unknown_feeds() {
+        let temp = TempDir::new().unwrap();
+        let path = temp.path().join("settings.toml");
+
+        fs::write(
+            &path,
+            "_app = \"5xx.engineer-hn\"\n\n[tabs]\norder = [\"top\", \"nope\"]\n",
+        )
+        .unwrap();
+        let err = Settings::load(&path).unwrap_err().to_string();
+        assert!(err.contains("Unknown feed 'nope'"));
+
+        fs::write(
+            &path,
+            "_app = \"5xx.engineer-hn\"\n\n[[feeds]]\nname = \"Rust\"\nquery = \"rust\"\n\n[tabs]\nstartup = \"rust\"\n",
+        )
+        .unwrap();
+        assert!(Settings::load(&path).is_ok());
+    }
+
     #[test]
     fn config_dir_uses_custom_when_provided() {
         let custom = PathBuf::from("/custom/path");
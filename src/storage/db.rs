@@ -1,9 +1,9 @@
 use rusqlite::Connection;
 use tokio::sync::mpsc;
 
-use super::StorageCommand;
 pub use super::migrations::run_migrations;
 use super::queries;
+use super::{StorageCommand, ThreadCommand};
 
 #[allow(clippy::needless_pass_by_value)] // Worker takes ownership of connection
 pub fn run_worker(conn: Connection, mut cmd_rx: mpsc::Receiver<StorageCommand>) {
@@ -70,6 +70,60 @@ pub fn run_worker(conn: Connection, mut cmd_rx: mpsc::Receiver<StorageCommand>)
                 let result = queries::get_feed_sort(&conn, feed);
                 let _ = reply.send(result);
             }
+            StorageCommand::Thread(cmd) => run_thread_command(&conn, cmd),
+            StorageCommand::GetState { key, reply } => {
+                let result = queries::get_state(&conn, &key);
+                let _ = reply.send(result);
+            }
+            StorageCommand::SetState { key, value, reply } => {
+                let result = queries::set_state(&conn, &key, &value);
+                let _ = reply.send(result);
+            }
+        }
+    }
+}
+
+fn run_thread_command(conn: &Connection, cmd: ThreadCommand) {
+    match cmd {
+        ThreadCommand::MarkCommentsRead {
+            story_id,
+            ids,
+            reply,
+        } => {
+            let result = queries::mark_comments_read(conn, story_id, &ids);
+            let _ = reply.send(result);
+        }
+        ThreadCommand::GetReadComments { story_id, reply } => {
+            let result = queries::get_read_comments(conn, story_id);
+            let _ = reply.send(result);
+        }
+        ThreadCommand::SaveCommentViewState {
+            story_id,
+            state,
+            reply,
+        } => {
+            let result = queries::save_comment_view_state(conn, story_id, &state);
+            let _ = reply.send(result);
+        }
+        ThreadCommand::GetCommentViewState { story_id, reply } => {
+            let result = queries::get_comment_view_state(conn, story_id);
+            let _ = reply.send(result);
+        }
+        ThreadCommand::ClearCommentViewState { story_id, reply } => {
+            let result = queries::clear_comment_view_state(conn, story_id);
+            let _ = reply.send(result);
+        }
+        ThreadCommand::SaveCommentSort {
+            story_id,
+            sort,
+            reply,
+        } => {
+            let result = queries::save_comment_sort(conn, story_id, &sort);
+            let _ = reply.send(result);
+        }
+        ThreadCommand::GetCommentSort { story_id, reply } => {
+            let result = queries::get_comment_sort(conn, story_id);
+            let _ = reply.send(result);
         }
     }
 }
@@ -36,6 +36,22 @@ const MIGRATIONS: &[Migration] = &[
         version: 6,
         sql: include_str!("sql/006_feed_sort.sql"),
     },
+    Migration {
+        version: 7,
+        sql: include_str!("sql/007_app_state.sql"),
+    },
+    Migration {
+        version: 8,
+        sql: include_str!("sql/008_comment_view_state.sql"),
+    },
+    Migration {
+        version: 9,
+        sql: include_str!("sql/009_comment_reads.sql"),
+    },
+    Migration {
+        version: 10,
+        sql: include_str!("sql/010_comment_sort.sql"),
+    },
 ];
 
 pub fn run_migrations(conn: &Connection) -> Result<(), StorageError> {
@@ -10,11 +10,14 @@ use std::time::Duration;
 use rusqlite::Connection;
 use tokio::sync::{mpsc, oneshot};
 
-pub use types::{CachedFeed, StorableComment, StorableStory, StorySort};
+pub use types::{CachedFeed, CommentViewState, StorableComment, StorableStory, StorySort};
 
 use crate::api::Feed;
 
-const CACHE_TTL: Duration = Duration::from_secs(86400); // 24 hours
+const CACHE_TTL: Duration = Duration::from_hours(24);
+
+/// `app_state` key holding the comment sort threads open with.
+pub const COMMENT_SORT_KEY: &str = "comment_sort";
 
 pub enum StorageLocation {
     Path(PathBuf),
@@ -133,6 +136,51 @@ pub enum StorageCommand {
         feed: Feed,
         reply: oneshot::Sender<Option<StorySort>>,
     },
+    /// Reads, view state and comment sort of one thread.
+    Thread(ThreadCommand),
+    GetState {
+        key: String,
+        reply: oneshot::Sender<Result<Option<String>, StorageError>>,
+    },
+    SetState {
+        key: String,
+        value: String,
+        reply: oneshot::Sender<Result<(), StorageError>>,
+    },
+}
+
+pub enum ThreadCommand {
+    MarkCommentsRead {
+        story_id: u64,
+        ids: Vec<u64>,
+        reply: oneshot::Sender<Result<(), StorageError>>,
+    },
+    GetReadComments {
+        story_id: u64,
+        reply: oneshot::Sender<Result<Vec<u64>, StorageError>>,
+    },
+    SaveCommentViewState {
+        story_id: u64,
+        state: CommentViewState,
+        reply: oneshot::Sender<Result<(), StorageError>>,
+    },
+    GetCommentViewState {
+        story_id: u64,
+        reply: oneshot::Sender<Result<Option<CommentViewState>, StorageError>>,
+    },
+    ClearCommentViewState {
+        story_id: u64,
+        reply: oneshot::Sender<Result<(), StorageError>>,
+    },
+    SaveCommentSort {
+        story_id: u64,
+        sort: String,
+        reply: oneshot::Sender<Result<(), StorageError>>,
+    },
+    GetCommentSort {
+        story_id: u64,
+        reply: oneshot::Sender<Result<Option<String>, StorageError>>,
+    },
 }
 
 #[derive(Clone)]
@@ -267,6 +315,35 @@ impl Storage {
         rx.await?
     }
 
+    /// Mark comments of a story read, all in one go.
+    pub async fn mark_comments_read(
+        &self,
+        story_id: u64,
+        ids: Vec<u64>,
+    ) -> Result<(), StorageError> {
+        let (tx, rx) = oneshot::channel();
+        self.cmd_tx
+            .send(StorageCommand::Thread(ThreadCommand::MarkCommentsRead {
+                story_id,
+                ids,
+                reply: tx,
+            }))
+            .await?;
+        rx.await?
+    }
+
+    /// Ids of a story's comments that have been read.
+    pub async fn get_read_comments(&self, story_id: u64) -> Result<Vec<u64>, StorageError> {
+        let (tx, rx) = oneshot::channel();
+        self.cmd_tx
+            .send(StorageCommand::Thread(ThreadCommand::GetReadComments {
+                story_id,
+                reply: tx,
+            }))
+            .await?;
+        rx.await?
+    }
+
     pub async fn toggle_story_favorite(&self, id: u64) -> Result<Option<u64>, StorageError> {
         let (tx, rx) = oneshot::channel();
         self.cmd_tx
@@ -343,11 +420,114 @@ impl Storage {
             .ok()?;
         rx.await.ok()?
     }
+
+    /// Remember the collapsed comments and selection of a thread.
+    pub async fn save_comment_view_state(
+        &self,
+        story_id: u64,
+        state: CommentViewState,
+    ) -> Result<(), StorageError> {
+        let (tx, rx) = oneshot::channel();
+        self.cmd_tx
+            .send(StorageCommand::Thread(
+                ThreadCommand::SaveCommentViewState {
+                    story_id,
+                    state,
+                    reply: tx,
+                },
+            ))
+            .await?;
+        rx.await?
+    }
+
+    /// Where a thread was left, if it was saved.
+    pub async fn get_comment_view_state(
+        &self,
+        story_id: u64,
+    ) -> Result<Option<CommentViewState>, StorageError> {
+        let (tx, rx) = oneshot::channel();
+        self.cmd_tx
+            .send(StorageCommand::Thread(ThreadCommand::GetCommentViewState {
+                story_id,
+                reply: tx,
+            }))
+            .await?;
+        rx.await?
+    }
+
+    /// Forget where a thread was left.
+    pub async fn clear_comment_view_state(&self, story_id: u64) -> Result<(), StorageError> {
+        let (tx, rx) = oneshot::channel();
+        self.cmd_tx
+            .send(StorageCommand::Thread(
+                ThreadCommand::ClearCommentViewState {
+                    story_id,
+                    reply: tx,
+                },
+            ))
+            .await?;
+        rx.await?
+    }
+
+    /// Make `sort` the default comment sort, and the one `story_id` opens with.
+    pub async fn save_comment_sort(&self, story_id: u64, sort: String) -> Result<(), StorageError> {
+        let (tx, rx) = oneshot::channel();
+        self.cmd_tx
+            .send(StorageCommand::Thread(ThreadCommand::SaveCommentSort {
+                story_id,
+                sort,
+                reply: tx,
+            }))
+            .await?;
+        rx.await?
+    }
+
+    /// The comment sort picked for a thread, if it differs from the default.
+    pub async fn get_comment_sort(&self, story_id: u64) -> Result<Option<String>, StorageError> {
+        let (tx, rx) = oneshot::channel();
+        self.cmd_tx
+            .send(StorageCommand::Thread(ThreadCommand::GetCommentSort {
+                story_id,
+                reply: tx,
+            }))
+            .await?;
+        rx.await?
+    }
+
+    /// Get a persisted piece of app state.
+    pub async fn get_state(&self, key: impl Into<String>) -> Result<Option<String>, StorageError> {
+        let (tx, rx) = oneshot::channel();
+        self.cmd_tx
+            .send(StorageCommand::GetState {
+                key: key.into(),
+                reply: tx,
+            })
+            .await?;
+        rx.await?
+    }
+
+    /// Persist a piece of app state, replacing any previous value.
+    pub async fn set_state(
+        &self,
+        key: impl Into<String>,
+        value: String,
+    ) -> Result<(), StorageError> {
+        let (tx, rx) = oneshot::channel();
+        self.cmd_tx
+            .send(StorageCommand::SetState {
+                key: key.into(),
+                value,
+                reply: tx,
+            })
+            .await?;
+        rx.await?
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::test_utils::custom_feed;
     use crate::time::now_unix;
 
     #[tokio::test]
@@ -493,6 +673,119 @@ mod tests {
         assert_eq!(loaded.ids, vec![100, 101, 102, 103, 104]);
     }
 
+    #[tokio::test]
+    async fn test_custom_feeds_cached_separately() {
+        let storage = Storage::open(StorageLocation::InMemory).unwrap();
+        let custom = Feed::custom(vec![custom_feed("Rust", "rust")]);
+        storage
+            .set_feed_sort(custom[0], StorySort::ScoreDesc)
+            .await
+            .unwrap();
+        assert_eq!(
+            storage.get_feed_sort(custom[0]).await,
+            Some(StorySort::ScoreDesc)
+        );
+        assert_eq!(storage.get_feed_sort(Feed::Top).await, None);
+    }
+
+    #[tokio::test]
+    async fn test_edited_custom_feed_drops_cache() {
+        let storage = Storage::open(StorageLocation::InMemory).unwrap();
+        let before = Feed::custom(vec![custom_feed("Rust", "rust")]);
+        storage.save_feed(before[0], &[1, 2, 3]).await.unwrap();
+        storage
+            .set_feed_sort(before[0], StorySort::ScoreDesc)
+            .await
+            .unwrap();
+
+        // Same name, different query
+        let after = Feed::custom(vec![custom_feed("Rust", "rustlang")]);
+        assert!(storage.get_feed(after[0]).await.unwrap().is_none());
+        assert_eq!(storage.get_feed_sort(after[0]).await, None);
+        assert!(storage.get_feed(before[0]).await.unwrap().is_some());
+    }
+
+    #[tokio::test]
+    async fn test_app_state_round_trip() {
+        let storage = Storage::open(StorageLocation::InMemory).unwrap();
+        assert_eq!(storage.get_state("last_feed").await.unwrap(), None);
+        storage
+            .set_state("last_feed", "best".to_string())
+            .await
+            .unwrap();
+        storage
+            .set_state("last_feed", "ask".to_string())
+            .await
+            .unwrap();
+        assert_eq!(
+            storage.get_state("last_feed").await.unwrap(),
+            Some("ask".to_string())
+        );
+    }
+
+    #[tokio::test]
+    async fn test_comment_view_state_round_trip() {
+        let storage = Storage::open(StorageLocation::InMemory).unwrap();
+        assert_eq!(storage.get_comment_view_state(123).await.unwrap(), None);
+
+        let state = CommentViewState {
+            collapsed: vec![1001, 1005],
+            selected: Some(1007),
+        };
+        storage
+            .save_comment_view_state(123, state.clone())
+            .await
+            .unwrap();
+        storage
+            .save_comment_view_state(456, CommentViewState::default())
+            .await
+            .unwrap();
+        assert_eq!(
+            storage.get_comment_view_state(123).await.unwrap(),
+            Some(state)
+        );
+        assert_eq!(
+            storage.get_comment_view_state(456).await.unwrap(),
+            Some(CommentViewState::default())
+        );
+
+        storage.clear_comment_view_state(123).await.unwrap();
+        assert_eq!(storage.get_comment_view_state(123).await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn test_comment_sort_kept_only_when_not_the_default() {
+        let storage = Storage::open(StorageLocation::InMemory).unwrap();
+        assert_eq!(storage.get_comment_sort(123).await.unwrap(), None);
+
+        storage
+            .save_comment_sort(123, "newest".to_string())
+            .await
+            .unwrap();
+        assert_eq!(
+            storage.get_comment_sort(123).await.unwrap(),
+            Some("newest".to_string())
+        );
+        assert_eq!(
+            storage.get_state(COMMENT_SORT_KEY).await.unwrap(),
+            Some("newest".to_string())
+        );
+
+        // Sorting another thread the default way keeps nothing for it
+        storage
+            .save_comment_sort(456, "newest".to_string())
+            .await
+            .unwrap();
+        assert_eq!(storage.get_comment_sort(456).await.unwrap(), None);
+
+        // Forgetting where a thread was left keeps its sort
+        storage.clear_comment_view_state(123).await.unwrap();
+        assert_eq!(
+            storage.get_comment_sort(123).await.unwrap(),
+            Some("newest".to_string())
+        );
+    }
+
     #[tokio::test]
     async fn test_nonexistent_story_returns_none() {
         let storage = Storage::open(StorageLocation::InMemory).unwrap();
@@ -616,6 +909,84 @@ mod tests {
         assert_eq!(loaded[0].id, 1001);
     }
 
+    #[tokio::test]
+    async fn test_comments_load_in_saved_order() {
+        let storage = Storage::open(StorageLocation::InMemory).unwrap();
+        let story = StorableStory {
+            id: 123,
+            title: "Test".to_string(),
+            url: None,
+            score: 1,
+            by: "u".to_string(),
+            time: 1700000000,
+            descendants: 3,
+            kids: vec![3003, 1001],
+            fetched_at: now_unix(),
+            read_at: None,
+            favorited_at: None,
+        };
+        storage.save_story(&story).await.unwrap();
+
+        let comment = |id, parent_id, depth| StorableComment {
+            id,
+            story_id: 123,
+            parent_id,
+            text: "Text".to_string(),
+            by: "user".to_string(),
+            time: 1700000000,
+            depth,
+            kids: vec![],
+            fetched_at: now_unix(),
+            favorited_at: None,
+        };
+        storage
+            .save_comments(123, &[comment(3003, None, 0), comment(1001, None, 0)])
+            .await
+            .unwrap();
+        // A reply shows up under the first comment
+        storage
+            .save_comments(
+                123,
+                &[
+                    comment(3003, None, 0),
+                    comment(4004, Some(3003), 1),
+                    comment(1001, None, 0),
+                ],
+            )
+            .await
+            .unwrap();
+
+        let ids: Vec<u64> = storage
+            .get_comments(123)
+            .await
+            .unwrap()
+            .iter()
+            .map(|c| c.id)
+            .collect();
+        assert_eq!(ids, vec![3003, 4004, 1001]);
+    }
+
+    #[tokio::test]
+    async fn test_mark_comments_read() {
+        let storage = Storage::open(StorageLocation::InMemory).unwrap();
+        assert!(storage.get_read_comments(123).await.unwrap().is_empty());
+
+        storage
+            .mark_comments_read(123, vec![1001, 1002])
+            .await
+            .unwrap();
+        storage
+            .mark_comments_read(123, vec![1002, 1003])
+            .await
+            .unwrap();
+        storage.mark_comments_read(456, vec![2001]).await.unwrap();
+
+        let mut read = storage.get_read_comments(123).await.unwrap();
+        read.sort_unstable();
+        assert_eq!(read, vec![1001, 1002, 1003]);
+        assert_eq!(storage.get_read_comments(456).await.unwrap(), vec![2001]);
+    }
+
     #[tokio::test]
     async fn test_mark_story_read() {
         let storage = Storage::open(StorageLocation::InMemory).unwrap();
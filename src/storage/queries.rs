@@ -5,13 +5,16 @@
 )]
 // SQLite uses i64 for all integers; HN IDs and timestamps are u64 but well within i64 range
 
-use rusqlite::{Connection, params, params_from_iter};
+use rusqlite::{Connection, OptionalExtension, params, params_from_iter};
 
 use crate::api::Feed;
 use crate::time::now_unix;
 
-use super::StorageError;
-use super::types::{CachedFeed, StorableComment, StorableStory, StorySort};
+use super::types::{CachedFeed, CommentViewState, StorableComment, StorableStory, StorySort};
+use super::{COMMENT_SORT_KEY, StorageError};
+
+/// How long a thread's view state is kept after it last changed.
+const VIEW_STATE_TTL: u64 = 90 * 24 * 60 * 60;
 
 fn kids_to_json(kids: &[u64]) -> String {
     serde_json::to_string(kids).unwrap_or_else(|_| "[]".to_string())
@@ -125,8 +128,8 @@ pub fn save_comments(
 
     // Use INSERT ... ON CONFLICT to preserve favorited_at
     let mut stmt = tx.prepare(
-        "INSERT INTO comments (id, story_id, parent_id, text, by, time, depth, kids, fetched_at, favorited_at)
-         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)
+        "INSERT INTO comments (id, story_id, parent_id, text, by, time, depth, kids, fetched_at, favorited_at, position)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)
          ON CONFLICT(id) DO UPDATE SET
             story_id = excluded.story_id,
             parent_id = excluded.parent_id,
@@ -136,9 +139,10 @@ pub fn save_comments(
             depth = excluded.depth,
             kids = excluded.kids,
             fetched_at = excluded.fetched_at,
-            favorited_at = COALESCE(comments.favorited_at, excluded.favorited_at)",
+            favorited_at = COALESCE(comments.favorited_at, excluded.favorited_at),
+            position = excluded.position",
     )?;
-    for comment in comments {
+    for (position, comment) in comments.iter().enumerate() {
         stmt.execute(params![
             comment.id as i64,
             comment.story_id as i64,
@@ -150,6 +154,7 @@ pub fn save_comments(
             kids_to_json(&comment.kids),
             comment.fetched_at as i64,
             comment.favorited_at.map(|t| t as i64),
+            position as i64,
         ])?;
     }
     drop(stmt);
@@ -173,7 +178,7 @@ pub fn get_comments(
 ) -> Result<Vec<StorableComment>, StorageError> {
     let mut stmt = conn.prepare(
         "SELECT id, story_id, parent_id, text, by, time, depth, kids, fetched_at, favorited_at
-         FROM comments WHERE story_id = ?1",
+         FROM comments WHERE story_id = ?1 ORDER BY position",
     )?;
 
     let rows = stmt.query_map(params![story_id as i64], |row| {
@@ -199,32 +204,8 @@ pub fn get_comments(
     Ok(comments)
 }
 
-const fn feed_type_str(feed: Feed) -> &'static str {
-    match feed {
-        Feed::Favorites => "favorites",
-        Feed::Top => "top",
-        Feed::New => "new",
-        Feed::Best => "best",
-        Feed::Ask => "ask",
-        Feed::Show => "show",
-        Feed::Jobs => "jobs",
-    }
-}
-
-fn str_to_feed(s: &str) -> Feed {
-    match s {
-        "favorites" => Feed::Favorites,
-        "new" => Feed::New,
-        "best" => Feed::Best,
-        "ask" => Feed::Ask,
-        "show" => Feed::Show,
-        "jobs" => Feed::Jobs,
-        _ => Feed::Top, // "top" or unknown
-    }
-}
-
 pub fn save_feed(conn: &Connection, feed: Feed, ids: &[u64]) -> Result<(), StorageError> {
-    let feed_type = feed_type_str(feed);
+    let feed_type = feed.cache_key();
     let now = now_unix() as i64;
     let tx = conn.unchecked_transaction()?;
     // Upsert feed metadata and get the ID
@@ -256,7 +237,7 @@ pub fn save_feed(conn: &Connection, feed: Feed, ids: &[u64]) -> Result<(), Stora
 }
 
 pub fn get_feed(conn: &Connection, feed: Feed) -> Result<Option<CachedFeed>, StorageError> {
-    let feed_type = feed_type_str(feed);
+    let feed_type = feed.cache_key();
     // Get feed metadata including sort
     let row: Option<(i64, i64, String)> = conn
         .query_row(
@@ -280,7 +261,7 @@ pub fn get_feed(conn: &Connection, feed: Feed) -> Result<Option<CachedFeed>, Sto
         return Ok(None);
     }
     Ok(Some(CachedFeed {
-        feed: str_to_feed(feed_type),
+        feed,
         ids,
         fetched_at: fetched_at as u64,
         sort: str_to_sort(&sort_str),
@@ -289,7 +270,7 @@ pub fn get_feed(conn: &Connection, feed: Feed) -> Result<Option<CachedFeed>, Sto
 
 /// Update the sort preference for a feed. Creates the feed record if it doesn't exist.
 pub fn set_feed_sort(conn: &Connection, feed: Feed, sort: StorySort) -> Result<(), StorageError> {
-    let feed_type = feed_type_str(feed);
+    let feed_type = feed.cache_key();
     conn.execute(
         "INSERT INTO feeds (feed_type, fetched_at, sort) VALUES (?1, 0, ?2)
          ON CONFLICT(feed_type) DO UPDATE SET sort = excluded.sort",
@@ -300,7 +281,7 @@ pub fn set_feed_sort(conn: &Connection, feed: Feed, sort: StorySort) -> Result<(
 
 /// Get just the sort preference for a feed, without loading story IDs.
 pub fn get_feed_sort(conn: &Connection, feed: Feed) -> Option<StorySort> {
-    let feed_type = feed_type_str(feed);
+    let feed_type = feed.cache_key();
     conn.query_row(
         "SELECT sort FROM feeds WHERE feed_type = ?1",
         params![feed_type],
@@ -310,6 +291,131 @@ pub fn get_feed_sort(conn: &Connection, feed: Feed) -> Option<StorySort> {
     .map(|s| str_to_sort(&s))
 }
 
+/// Get a value from the app state key/value store.
+pub fn get_state(conn: &Connection, key: &str) -> Result<Option<String>, StorageError> {
+    let value = conn
+        .query_row(
+            "SELECT value FROM app_state WHERE key = ?1",
+            params![key],
+            |row| row.get(0),
+        )
+        .optional()?;
+    Ok(value)
+}
+
+/// Set a value in the app state key/value store.
+pub fn set_state(conn: &Connection, key: &str, value: &str) -> Result<(), StorageError> {
+    conn.execute(
+        "INSERT INTO app_state (key, value) VALUES (?1, ?2)
+         ON CONFLICT(key) DO UPDATE SET value = excluded.value",
+        params![key, value],
+    )?;
+    Ok(())
+}
+
+/// Remember where a thread was left, replacing what was saved before.
+pub fn save_comment_view_state(
+    conn: &Connection,
+    story_id: u64,
+    state: &CommentViewState,
+) -> Result<(), StorageError> {
+    prune_comment_view_state(conn)?;
+    conn.execute(
+        "INSERT INTO comment_view_state (story_id, collapsed, selected_id, updated_at)
+         VALUES (?1, ?2, ?3, ?4)
+         ON CONFLICT(story_id) DO UPDATE SET
+            collapsed = excluded.collapsed,
+            selected_id = excluded.selected_id,
+            updated_at = excluded.updated_at",
+        params![
+            story_id as i64,
+            kids_to_json(&state.collapsed),
+            state.selected.map(|id| id as i64),
+            now_unix() as i64
+        ],
+    )?;
+    Ok(())
+}
+
+pub fn get_comment_view_state(
+    conn: &Connection,
+    story_id: u64,
+) -> Result<Option<CommentViewState>, StorageError> {
+    let state = conn
+        .query_row(
+            "SELECT collapsed, selected_id FROM comment_view_state WHERE story_id = ?1",
+            params![story_id as i64],
+            |row| {
+                Ok(CommentViewState {
+                    collapsed: json_to_kids(&row.get::<_, String>(0)?),
+                    selected: row.get::<_, Option<i64>>(1)?.map(|id| id as u64),
+                })
+            },
+        )
+        .optional()?;
+    Ok(state)
+}
+
+/// Forget where a thread was left. Its comment sort stays.
+pub fn clear_comment_view_state(conn: &Connection, story_id: u64) -> Result<(), StorageError> {
+    conn.execute(
+        "DELETE FROM comment_view_state WHERE story_id = ?1 AND sort IS NULL",
+        params![story_id as i64],
+    )?;
+    conn.execute(
+        "UPDATE comment_view_state SET collapsed = '[]', selected_id = NULL WHERE story_id = ?1",
+        params![story_id as i64],
+    )?;
+    Ok(())
+}
+
+/// Drop the view state of threads not opened for a while.
+fn prune_comment_view_state(conn: &Connection) -> Result<(), StorageError> {
+    let cutoff = now_unix().saturating_sub(VIEW_STATE_TTL);
+    conn.execute(
+        "DELETE FROM comment_view_state WHERE updated_at < ?1",
+        params![cutoff as i64],
+    )?;
+    Ok(())
+}
+
+/// Make `sort` the comment sort threads open with, and keep it for this
+/// one. A thread sorted the way the default already was follows the
+/// default from then on, so nothing is kept for it.
+pub fn save_comment_sort(conn: &Connection, story_id: u64, sort: &str) -> Result<(), StorageError> {
+    prune_comment_view_state(conn)?;
+    let tx = conn.unchecked_transaction()?;
+    if get_state(&tx, COMMENT_SORT_KEY)?.as_deref() == Some(sort) {
+        tx.execute(
+            "UPDATE comment_view_state SET sort = NULL WHERE story_id = ?1",
+            params![story_id as i64],
+        )?;
+    } else {
+        tx.execute(
+            "INSERT INTO comment_view_state (story_id, sort, updated_at) VALUES (?1, ?2, ?3)
+             ON CONFLICT(story_id) DO UPDATE SET
+                sort = excluded.sort,
+                updated_at = excluded.updated_at",
+            params![story_id as i64, sort, now_unix() as i64],
+        )?;
+        set_state(&tx, COMMENT_SORT_KEY, sort)?;
+    }
+    tx.commit()?;
+    Ok(())
+}
+
+/// The comment sort kept for a thread, if it has its own.
+pub fn get_comment_sort(conn: &Connection, story_id: u64) -> Result<Option<String>, StorageError> {
+    let sort = conn
+        .query_row(
+            "SELECT sort FROM comment_view_state WHERE story_id = ?1",
+            params![story_id as i64],
+            |row| row.get(0),
+        )
+        .optional()?;
+    Ok(sort.flatten())
+}
+
 pub fn mark_story_read(conn: &Connection, id: u64) -> Result<(), StorageError> {
     conn.execute(
         "UPDATE stories SET read_at = ?1 WHERE id = ?2 AND read_at IS NULL",
@@ -318,6 +424,36 @@ pub fn mark_story_read(conn: &Connection, id: u64) -> Result<(), StorageError> {
     Ok(())
 }
 
+/// Mark comments read, keeping when each was first read.
+pub fn mark_comments_read(
+    conn: &Connection,
+    story_id: u64,
+    ids: &[u64],
+) -> Result<(), StorageError> {
+    let tx = conn.unchecked_transaction()?;
+    let mut stmt = tx.prepare(
+        "INSERT OR IGNORE INTO comment_reads (comment_id, story_id, read_at)
+         VALUES (?1, ?2, ?3)",
+    )?;
+    let now = now_unix() as i64;
+    for &id in ids {
+        stmt.execute(params![id as i64, story_id as i64, now])?;
+    }
+    drop(stmt);
+    tx.commit()?;
+    Ok(())
+}
+
+pub fn get_read_comments(conn: &Connection, story_id: u64) -> Result<Vec<u64>, StorageError> {
+    let mut stmt = conn.prepare("SELECT comment_id FROM comment_reads WHERE story_id = ?1")?;
+    let rows = stmt.query_map(params![story_id as i64], |row| row.get::<_, i64>(0))?;
+    let mut ids = Vec::new();
+    for row in rows {
+        ids.push(row? as u64);
+    }
+    Ok(ids)
+}
+
 /// Toggle favorite status for a story. Returns the new `favorited_at` value (Some if favorited, None if unfavorited).
 pub fn toggle_story_favorite(conn: &Connection, id: u64) -> Result<Option<u64>, StorageError> {
     let existing: Option<i64> = conn
@@ -411,7 +547,7 @@ pub fn get_feed_stories_sorted(
     feed: Feed,
     sort: StorySort,
 ) -> Result<Option<SortedFeedResult>, StorageError> {
-    let feed_type = feed_type_str(feed);
+    let feed_type = feed.cache_key();
     // Get feed metadata
     let row: Option<(i64, i64)> = conn
         .query_row(
@@ -452,3 +588,25 @@ pub fn get_feed_stories_sorted(
         fetched_at: fetched_at as u64,
     }))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::migrations::run_migrations;
+
+    #[test]
+    fn test_old_comment_view_state_is_pruned() {
+        let conn = Connection::open_in_memory().unwrap();
+        run_migrations(&conn).unwrap();
+        let stale = now_unix() - VIEW_STATE_TTL - 1;
+        conn.execute(
+            "INSERT INTO comment_view_state (story_id, sort, updated_at) VALUES (1, 'newest', ?1)",
+            params![stale as i64],
+        )
+        .unwrap();
+
+        save_comment_view_state(&conn, 2, &CommentViewState::default()).unwrap();
+        assert_eq!(get_comment_sort(&conn, 1).unwrap(), None);
+        assert!(get_comment_view_state(&conn, 2).unwrap().is_some());
+    }
+}
@@ -0,0 +1,6 @@
+-- Key/value store for UI state that should survive restarts (e.g. last active feed)
+
+CREATE TABLE app_state (
+    key TEXT PRIMARY KEY,
+    value TEXT NOT NULL
+);
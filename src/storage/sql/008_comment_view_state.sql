@@ -0,0 +1,9 @@
+-- Where each thread was left, so it can be picked up again: the comments
+-- collapsed (JSON array of ids) and the one selected
+
+CREATE TABLE comment_view_state (
+    story_id INTEGER PRIMARY KEY,
+    collapsed TEXT NOT NULL DEFAULT '[]',
+    selected_id INTEGER,
+    updated_at INTEGER NOT NULL
+);
@@ -0,0 +1,9 @@
+-- Comments that have been read, for telling which are new in a thread
+
+CREATE TABLE comment_reads (
+    comment_id INTEGER PRIMARY KEY,
+    story_id INTEGER NOT NULL,
+    read_at INTEGER NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_comment_reads_story ON comment_reads(story_id);
@@ -0,0 +1,16 @@
+-- Cached comments keep the order they were fetched in, so they load in
+-- thread order as they are. Comments cached before have no order and are
+-- fetched again.
+ALTER TABLE comments ADD COLUMN position INTEGER NOT NULL DEFAULT 0;
+UPDATE comments SET fetched_at = 0;
+
+-- The comment sort picked for a thread goes with the rest of its view
+-- state, instead of one app_state key per story
+ALTER TABLE comment_view_state ADD COLUMN sort TEXT;
+INSERT INTO comment_view_state (story_id, sort, updated_at)
+    SELECT CAST(substr(key, 14) AS INTEGER), value, CAST(strftime('%s', 'now') AS INTEGER)
+    FROM app_state WHERE key LIKE 'comment\_sort:%' ESCAPE '\'
+    ON CONFLICT(story_id) DO UPDATE SET sort = excluded.sort;
+DELETE FROM app_state WHERE key LIKE 'comment\_sort:%' ESCAPE '\';
+
+CREATE INDEX idx_comment_view_state_updated_at ON comment_view_state(updated_at);
@@ -120,6 +120,13 @@ impl From<StorableComment> for Comment {
     }
 }
 
+/// Where a thread was left: the comments collapsed and the one selected.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CommentViewState {
+    pub collapsed: Vec<u64>,
+    pub selected: Option<u64>,
+}
+
 #[allow(dead_code)] // Used by future features
 #[derive(Debug, Clone)]
 pub struct CachedFeed {
@@ -1,14 +1,20 @@
 //! Test data builders for view testing.
 
+use std::cell::RefCell;
 use std::path::PathBuf;
 use std::sync::Arc;
 use std::time::Instant;
 
 use tokio::sync::mpsc;
 
-use crate::api::{Comment, Feed, HnClient, Story};
-use crate::app::{App, DebugState, LoadState, View};
+use crate::api::{Comment, CustomFeed, Feed, HnClient, Story, User};
+use crate::app::{App, DEFAULT_SPLIT_MIN_WIDTH, DebugState, LoadState, Preview, View};
+use crate::comment_reads::CommentReads;
 use crate::comment_tree::CommentTree;
+use crate::highlight::{HighlightRule, Highlights};
+use crate::keys::Keymaps;
+use crate::nav::NavStack;
+use crate::search::Search;
 use crate::storage::{Storage, StorageLocation};
 use crate::theme::{ResolvedTheme, ThemeVariant, default_for_variant};
 use crate::time::{Clock, fixed_clock};
@@ -224,9 +230,13 @@ pub struct TestAppBuilder {
     theme: ResolvedTheme,
     clock: Arc<dyn Clock>,
     viewport_height: Option<u16>,
+    viewport_width: Option<u16>,
+    preview: Option<u64>,
+    user: Option<User>,
     config_dir: Option<PathBuf>,
     stories_fetched_at: Option<u64>,
     comments_fetched_at: Option<u64>,
+    highlights: Vec<HighlightRule>,
 }
 
 impl Default for TestAppBuilder {
@@ -257,9 +267,13 @@ impl TestAppBuilder {
             theme: default_for_variant(ThemeVariant::Dark),
             clock: fixed_clock(TEST_NOW),
             viewport_height: None,
+            viewport_width: None,
+            preview: None,
+            user: None,
             config_dir: None,
             stories_fetched_at: None,
             comments_fetched_at: None,
+            highlights: Vec::new(),
         }
     }
 
@@ -334,6 +348,22 @@ impl TestAppBuilder {
         self
     }
 
+    pub fn viewport_width(mut self, width: u16) -> Self {
+        self.viewport_width = Some(width);
+        self
+    }
+
+    /// Mark the comments as the split layout's preview of `story_id`.
+    pub fn preview(mut self, story_id: u64) -> Self {
+        self.preview = Some(story_id);
+        self
+    }
+
+    pub fn user(mut self, user: User) -> Self {
+        self.user = Some(user);
+        self
+    }
+
     pub fn loading_more(mut self, loading: bool) -> Self {
         self.loading_more = loading;
         self
@@ -354,6 +384,11 @@ impl TestAppBuilder {
         self
     }
 
+    pub fn highlight(mut self, rule: HighlightRule) -> Self {
+        self.highlights.push(rule);
+        self
+    }
+
     pub fn build(self) -> App {
         let (result_tx, result_rx) = mpsc::channel(10);
 
@@ -377,11 +412,32 @@ impl TestAppBuilder {
             error: self.error,
         };
 
+        let mut preview = Preview::default();
+        preview.story_id = self.preview;
+        if self.preview.is_some() && self.view == View::Stories {
+            // The comments are the preview's rather than an open thread's
+            preview.comment_tree = std::mem::take(&mut comment_tree);
+            preview.fetched_at = self.comments_fetched_at;
+        }
+
         App {
             view: self.view,
+            nav: NavStack::default(),
             feed: self.feed,
             stories: self.stories,
+            stories_revision: 0,
+            feeds: Feed::all().to_vec(),
+            keymaps: Keymaps::default(),
+            pending_keys: None,
+            command_line: None,
+            command_history: Vec::new(),
+            search: Search::default(),
+            hints: None,
+            code_block: None,
             comment_tree,
+            focus_comment: None,
+            pending_item: None,
+            user: self.user,
             selected_index: self.selected_index,
             load,
             should_quit: false,
@@ -395,17 +451,51 @@ impl TestAppBuilder {
             generation: 0,
             debug: DebugState::new(),
             viewport_height: self.viewport_height,
+            viewport_width: self.viewport_width,
+            split_min_width: DEFAULT_SPLIT_MIN_WIDTH,
+            preview,
             theme_picker: None,
             config_dir: self.config_dir,
             flash_message: None,
             stories_fetched_at: self.stories_fetched_at,
             comments_fetched_at: self.comments_fetched_at,
             story_sort: crate::app::StorySort::default(),
+            comment_sort: crate::comment_tree::CommentSort::default(),
+            flat_comments: false,
+            comment_zoom: None,
+            show_ancestors: false,
+            comment_reads: CommentReads::default(),
+            unread_only: false,
             context_menu: None,
+            highlights: Highlights::new(self.highlights),
+            hit_areas: RefCell::default(),
+            last_click: None,
+            link_areas: RefCell::default(),
+            dirty: true,
+            comment_layout: RefCell::default(),
+            comments_on_screen: RefCell::default(),
         }
     }
 }
 
+/// The comments view for a story with just an id and title.
+pub fn comments_view(id: u64, title: &str) -> View {
+    View::Comments {
+        story: StoryBuilder::new().id(id).title(title).build(),
+    }
+}
+
+/// A custom feed searching stories for `query`, with no other filters.
+pub fn custom_feed(name: &str, query: &str) -> CustomFeed {
+    CustomFeed {
+        name: name.to_string(),
+        query: query.to_string(),
+        tags: None,
+        min_points: None,
+        domains: vec![],
+    }
+}
+
 pub fn sample_stories() -> Vec<Story> {
     vec![
         StoryBuilder::new()
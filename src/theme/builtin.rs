@@ -69,6 +69,7 @@ pub fn default_dark() -> Theme {
                 named("blue"),
                 named("red"),
             ],
+            comment_op: Some(named("lightred")),
             status_bar_bg: named("blue"),
             status_bar_fg: named("white"),
             spinner: named("yellow"),
@@ -109,6 +110,7 @@ pub fn default_light() -> Theme {
                 named("red"),
                 named("yellow"),
             ],
+            comment_op: Some(named("red")),
             status_bar_bg: named("blue"),
             status_bar_fg: named("white"),
             spinner: named("blue"),
@@ -149,6 +151,7 @@ pub fn monokai() -> Theme {
                 hex("#FD971F"),
                 hex("#F92672"),
             ],
+            comment_op: Some(hex("#FD971F")),
             status_bar_bg: hex("#A6E22E"),
             status_bar_fg: hex("#272822"),
             spinner: hex("#E6DB74"),
@@ -189,6 +192,7 @@ pub fn dracula() -> Theme {
                 hex("#FFB86C"),
                 hex("#FF79C6"),
             ],
+            comment_op: Some(hex("#FFB86C")),
             status_bar_bg: hex("#BD93F9"),
             status_bar_fg: hex("#282A36"),
             spinner: hex("#F1FA8C"),
@@ -229,6 +233,7 @@ pub fn nord() -> Theme {
                 hex("#81A1C1"),
                 hex("#BF616A"),
             ],
+            comment_op: Some(hex("#D08770")),
             status_bar_bg: hex("#5E81AC"),
             status_bar_fg: hex("#ECEFF4"),
             spinner: hex("#EBCB8B"),
@@ -269,6 +274,7 @@ pub fn gruvbox_dark() -> Theme {
                 hex("#8EC07C"),
                 hex("#FE8019"),
             ],
+            comment_op: Some(hex("#FE8019")),
             status_bar_bg: hex("#458588"),
             status_bar_fg: hex("#EBDBB2"),
             spinner: hex("#FABD2F"),
@@ -309,6 +315,7 @@ pub fn gruvbox_light() -> Theme {
                 hex("#427B58"),
                 hex("#AF3A03"),
             ],
+            comment_op: Some(hex("#AF3A03")),
             status_bar_bg: hex("#076678"),
             status_bar_fg: hex("#FBF1C7"),
             spinner: hex("#B57614"),
@@ -349,6 +356,7 @@ pub fn solarized_dark() -> Theme {
                 hex("#2AA198"),
                 hex("#CB4B16"),
             ],
+            comment_op: Some(hex("#CB4B16")),
             status_bar_bg: hex("#268BD2"),
             status_bar_fg: hex("#FDF6E3"),
             spinner: hex("#B58900"),
@@ -389,6 +397,7 @@ pub fn solarized_light() -> Theme {
                 hex("#2AA198"),
                 hex("#CB4B16"),
             ],
+            comment_op: Some(hex("#CB4B16")),
             status_bar_bg: hex("#268BD2"),
             status_bar_fg: hex("#FDF6E3"),
             spinner: hex("#B58900"),
@@ -429,6 +438,7 @@ pub fn catppuccin_mocha() -> Theme {
                 hex("#FAB387"),
                 hex("#F38BA8"),
             ],
+            comment_op: Some(hex("#FAB387")),
             status_bar_bg: hex("#CBA6F7"),
             status_bar_fg: hex("#1E1E2E"),
             spinner: hex("#F9E2AF"),
@@ -469,6 +479,7 @@ pub fn catppuccin_latte() -> Theme {
                 hex("#FE640B"),
                 hex("#D20F39"),
             ],
+            comment_op: Some(hex("#FE640B")),
             status_bar_bg: hex("#8839EF"),
             status_bar_fg: hex("#EFF1F5"),
             spinner: hex("#DF8E1D"),
@@ -509,6 +520,7 @@ pub fn tokyo_night() -> Theme {
                 hex("#7AA2F7"),
                 hex("#F7768E"),
             ],
+            comment_op: Some(hex("#FF9E64")),
             status_bar_bg: hex("#7AA2F7"),
             status_bar_fg: hex("#1A1B26"),
             spinner: hex("#E0AF68"),
@@ -108,6 +108,9 @@ pub struct ThemeColors {
     pub story_time: ThemeColor,
     pub comment_text: ThemeColor,
     pub comment_depth_colors: Vec<ThemeColor>,
+    /// Comments by the story's submitter. Falls back to `primary`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub comment_op: Option<ThemeColor>,
     pub status_bar_bg: ThemeColor,
     pub status_bar_fg: ThemeColor,
     pub spinner: ThemeColor,
@@ -135,6 +138,7 @@ pub struct ResolvedTheme {
     pub story_time: Color,
     pub comment_text: Color,
     pub comment_depth_colors: Vec<Color>,
+    pub comment_op: Color,
     pub status_bar_bg: Color,
     pub status_bar_fg: Color,
     pub spinner: Color,
@@ -186,6 +190,7 @@ impl ResolvedTheme {
                 .iter()
                 .map(|c| dim_color(*c))
                 .collect(),
+            comment_op: dim_color(self.comment_op),
             status_bar_bg: self.status_bar_bg,
             status_bar_fg: self.status_bar_fg,
             spinner: self.spinner,
@@ -203,6 +208,11 @@ impl ResolvedTheme {
         Style::default().fg(self.border)
     }
 
+    /// Border of the focused pane when stories and comments are side by side.
+    pub fn focused_border_style(&self) -> Style {
+        Style::default().fg(self.primary)
+    }
+
     pub fn selection_style(&self) -> Style {
         Style::default()
             .bg(self.selection_bg)
@@ -247,6 +257,27 @@ impl ResolvedTheme {
         Style::default().fg(self.comment_text).bg(self.selection_bg)
     }
 
+    /// Keywords in syntax highlighted code blocks.
+    pub fn code_keyword_style(&self) -> Style {
+        self.comment_code_style()
+            .fg(self.primary)
+            .add_modifier(Modifier::BOLD)
+    }
+
+    pub fn code_string_style(&self) -> Style {
+        self.comment_code_style().fg(self.success)
+    }
+
+    pub fn code_number_style(&self) -> Style {
+        self.comment_code_style().fg(self.warning)
+    }
+
+    pub fn code_comment_style(&self) -> Style {
+        self.comment_code_style()
+            .fg(self.foreground_dim)
+            .add_modifier(Modifier::ITALIC)
+    }
+
     pub fn comment_link_style(&self) -> Style {
         Style::default()
             .fg(self.info)
@@ -288,6 +319,41 @@ impl ResolvedTheme {
     pub fn story_time_style(&self) -> Style {
         Style::default().fg(self.story_time)
     }
+
+    /// Accent style for titles, domains, and authors matched by highlight rules.
+    pub fn highlight_style(&self) -> Style {
+        Style::default()
+            .fg(self.warning)
+            .add_modifier(Modifier::BOLD)
+    }
+
+    /// Author of comments by the story's submitter.
+    pub fn comment_op_style(&self) -> Style {
+        Style::default()
+            .fg(self.comment_op)
+            .add_modifier(Modifier::BOLD)
+    }
+
+    /// The badge next to the submitter's name.
+    pub fn comment_op_badge_style(&self) -> Style {
+        Style::default()
+            .fg(self.comment_op)
+            .add_modifier(Modifier::REVERSED | Modifier::BOLD)
+    }
+
+    /// Labels put in front of links while picking one.
+    pub fn hint_style(&self) -> Style {
+        Style::default()
+            .fg(self.primary)
+            .add_modifier(Modifier::REVERSED | Modifier::BOLD)
+    }
+
+    /// Text matching the current `/` search.
+    pub fn search_match_style(&self) -> Style {
+        Style::default()
+            .fg(self.warning)
+            .add_modifier(Modifier::REVERSED | Modifier::BOLD)
+    }
 }
 
 impl From<Theme> for ResolvedTheme {
@@ -317,6 +383,10 @@ impl From<Theme> for ResolvedTheme {
                 .iter()
                 .map(ThemeColor::to_color)
                 .collect(),
+            comment_op: c
+                .comment_op
+                .as_ref()
+                .map_or_else(|| c.primary.to_color(), ThemeColor::to_color),
             status_bar_bg: c.status_bar_bg.to_color(),
             status_bar_fg: c.status_bar_fg.to_color(),
             spinner: c.spinner.to_color(),
@@ -59,6 +59,14 @@ pub fn format_relative(timestamp: u64, now: DateTime<Utc>) -> String {
     )
 }
 
+/// Formats a Unix timestamp as a calendar date, e.g. "Nov 14, 2023".
+#[allow(clippy::cast_possible_wrap)] // Unix timestamps won't exceed i64::MAX until year 292 billion
+pub fn format_date(timestamp: u64) -> String {
+    Utc.timestamp_opt(timestamp as i64, 0)
+        .single()
+        .map_or_else(|| "?".to_string(), |t| t.format("%b %-d, %Y").to_string())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -83,4 +91,9 @@ mod tests {
         let timestamp = 1700000000 - 3 * 86400; // 3 days ago
         assert_eq!(format_relative(timestamp, now), "3d ago");
     }
+
+    #[test]
+    fn format_date_shows_calendar_date() {
+        assert_eq!(format_date(1700000000), "Nov 14, 2023");
+    }
 }
@@ -2,7 +2,10 @@ use std::io::{self, Stdout};
 
 use anyhow::Result;
 use crossterm::{
-    event::{Event as CrosstermEvent, EventStream},
+    event::{
+        DisableMouseCapture, EnableMouseCapture, Event as CrosstermEvent, EventStream,
+        MouseEventKind,
+    },
     execute,
     terminal::{EnterAlternateScreen, LeaveAlternateScreen, disable_raw_mode, enable_raw_mode},
 };
@@ -18,16 +21,22 @@ pub fn init() -> Result<Tui> {
     debug!("entering alternate screen");
     enable_raw_mode()?;
     let mut stdout = io::stdout();
-    execute!(stdout, EnterAlternateScreen)?;
+    execute!(stdout, EnterAlternateScreen, EnableMouseCapture)?;
     let backend = CrosstermBackend::new(stdout);
     let terminal = Terminal::new(backend)?;
     Ok(terminal)
 }
 
+/// Give the mouse back to the terminal, so text can be selected.
+pub fn release_mouse() -> Result<()> {
+    execute!(io::stdout(), DisableMouseCapture)?;
+    Ok(())
+}
+
 pub fn restore() -> Result<()> {
     debug!("leaving alternate screen");
     disable_raw_mode()?;
-    execute!(io::stdout(), LeaveAlternateScreen)?;
+    execute!(io::stdout(), DisableMouseCapture, LeaveAlternateScreen)?;
     Ok(())
 }
 
@@ -51,6 +60,15 @@ impl CrosstermEvents {
                     {
                         return Ok(Event::Key(key));
                     }
+                    // Movement and drags would only cause redraws
+                    CrosstermEvent::Mouse(mouse)
+                        if !matches!(
+                            mouse.kind,
+                            MouseEventKind::Moved | MouseEventKind::Drag(_)
+                        ) =>
+                    {
+                        return Ok(Event::Mouse(mouse));
+                    }
                     CrosstermEvent::Resize(_, _) => return Ok(Event::Resize),
                     _ => {}
                 }
@@ -0,0 +1,128 @@
+use ratatui::{
+    Frame,
+    layout::{Position, Rect},
+    text::{Line, Span},
+    widgets::{Clear, Paragraph},
+};
+use unicode_width::UnicodeWidthStr;
+
+use crate::app::App;
+use crate::command::{self, COMMANDS};
+
+/// Renders the `:` prompt over the status bar line at the bottom of `area`.
+pub fn render(frame: &mut Frame, app: &App, area: Rect) {
+    let Some(line) = &app.command_line else {
+        return;
+    };
+    if area.height == 0 {
+        return;
+    }
+    let theme = &app.theme;
+    let row = Rect {
+        y: area.bottom() - 1,
+        height: 1,
+        ..area
+    };
+
+    let prompt = format!(":{}", line.input);
+    let mut spans = vec![Span::styled(prompt.clone(), theme.comment_text_style())];
+    spans.push(Span::raw("  "));
+
+    // While cycling with Tab, show the candidates with the current one
+    // highlighted. Otherwise show what Tab would offer, or the usage of the
+    // command being typed.
+    if let Some((completions, selected)) = line.completions() {
+        for (i, completion) in completions.iter().enumerate() {
+            let text = completion.rsplit(' ').next().unwrap_or(completion);
+            let style = if i == selected {
+                theme.selection_style()
+            } else {
+                theme.dim_style()
+            };
+            spans.push(Span::styled(text.to_string(), style));
+            spans.push(Span::raw(" "));
+        }
+    } else if let Some(hint) = hint(&line.input, app) {
+        spans.push(Span::styled(hint, theme.dim_style()));
+    }
+
+    frame.render_widget(Clear, row);
+    frame.render_widget(Paragraph::new(Line::from(spans)), row);
+
+    #[allow(clippy::cast_possible_truncation)] // prompt is clamped to the row width
+    let cursor_x = (prompt.width() as u16).min(row.width.saturating_sub(1));
+    frame.set_cursor_position(Position::new(row.x + cursor_x, row.y));
+}
+
+fn hint(input: &str, app: &App) -> Option<String> {
+    let name = input.split_whitespace().next()?;
+    let typing_args = input.contains(char::is_whitespace);
+    if typing_args && let Some(spec) = COMMANDS.iter().find(|c| c.name == name) {
+        let args = command::complete(input, &app.feeds);
+        if args.is_empty() {
+            return Some(format!("{} - {}", spec.usage, spec.description));
+        }
+        let args: Vec<&str> = args
+            .iter()
+            .filter_map(|c| c.split_once(' ').map(|(_, arg)| arg))
+            .collect();
+        return Some(args.join(" "));
+    }
+    let names = command::complete(input, &app.feeds);
+    (!names.is_empty()).then(|| names.join(" "))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::Message;
+    use crate::test_utils::TestAppBuilder;
+    use crate::views::tests::render_to_string;
+
+    fn typed(input: &str) -> App {
+        let mut app = TestAppBuilder::new().build();
+        app.update(Message::OpenCommandLine);
+        for c in input.chars() {
+            app.update(Message::CommandLineInput(c));
+        }
+        app
+    }
+
+    fn last_line(output: &str) -> String {
+        output
+            .lines()
+            .last()
+            .unwrap_or_default()
+            .trim_end()
+            .to_string()
+    }
+
+    #[test]
+    fn shows_command_completions() {
+        let app = typed("s");
+        let output = render_to_string(60, 3, |frame| render(frame, &app, frame.area()));
+        assert_eq!(last_line(&output), ":s  sort user refresh");
+    }
+
+    #[test]
+    fn shows_usage_while_typing_arguments() {
+        let app = typed("user ");
+        let output = render_to_string(60, 3, |frame| render(frame, &app, frame.area()));
+        assert_eq!(
+            last_line(&output),
+            ":user   user <name> - open a user's profile"
+        );
+
+        let app = typed("sort ");
+        let output = render_to_string(60, 3, |frame| render(frame, &app, frame.area()));
+        assert_eq!(last_line(&output), ":sort   api score comments time");
+    }
+
+    #[test]
+    fn highlights_cycled_completion() {
+        let mut app = typed("th");
+        app.update(Message::CommandLineComplete);
+        let output = render_to_string(60, 3, |frame| render(frame, &app, frame.area()));
+        assert_eq!(last_line(&output), ":theme  theme");
+    }
+}
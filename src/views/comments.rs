@@ -1,4 +1,4 @@
-use std::sync::Arc;
+use std::collections::HashMap;
 
 use ratatui::{
     Frame,
@@ -8,27 +8,35 @@ use ratatui::{
     widgets::{Block, Borders, Paragraph as RatatuiParagraph},
 };
 use textwrap;
-use unicode_width::UnicodeWidthStr;
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
 
 use crate::api::Comment;
 use crate::app::{App, View};
-use crate::help::comments_help;
-use crate::keys::{comments_keymap, global_keymap};
+use crate::comment_tree::CommentSort;
+use crate::help::{comments_help, hints_help};
+use crate::hyperlink::TextLink;
+use crate::keys::view_keymap;
+use crate::mouse::ClickTarget;
 use crate::theme::ResolvedTheme;
-use crate::time::{Clock, format_relative};
-use crate::views::common::{render_error, render_with_timestamp};
-use crate::views::html::{InlineStyle, Paragraph, StyledSpan, parse_comment_html};
+use crate::time::format_relative;
+use crate::views::common::{
+    highlight_matches, pane_border_style, render_error, render_with_timestamp,
+};
+use crate::views::html::{
+    InlineStyle, Paragraph, ParagraphKind, StyledSpan, parse_comment_html, strip_html,
+};
 use crate::views::status_bar::StatusBar;
+use crate::views::syntax::{self, Highlighter, Token};
 use crate::views::tree::{
-    build_empty_line_prefix, build_meta_tree_prefix, build_text_prefix, compute_tree_context,
+    build_empty_line_prefix, build_meta_tree_prefix, build_text_prefix, meta_tree_prefix_width,
+    text_prefix_width, tree_context,
 };
 use crate::widgets::{CommentList, CommentListItem, CommentListState};
 
+const HIGHLIGHT_SYMBOL: &str = "▶ ";
+
 pub fn render(frame: &mut Frame, app: &App, area: Rect) {
-    let story_title = match &app.view {
-        View::Comments { story_title, .. } => story_title.clone(),
-        View::Stories => String::new(),
-    };
+    let story_title = app.comments_title().unwrap_or_default();
 
     let chunks = Layout::vertical([
         Constraint::Length(2), // Story title
@@ -38,8 +46,9 @@ pub fn render(frame: &mut Frame, app: &App, area: Rect) {
     .split(area);
 
     let theme = &app.theme;
-    render_header(frame, app, &story_title, chunks[0], theme);
-    render_comment_list(frame, app, chunks[1]);
+    render_header(frame, app, story_title, chunks[0], theme);
+    let list_area = render_ancestors(frame, app, chunks[1]);
+    render_comment_list(frame, app, list_area);
     render_status_bar(frame, app, chunks[2]);
 }
 
@@ -53,7 +62,7 @@ fn render_header(frame: &mut Frame, app: &App, title: &str, area: Rect, theme: &
             .add_modifier(Modifier::BOLD),
     )];
 
-    if app.load.should_show_spinner() {
+    if is_focused(app) && app.load.should_show_spinner() {
         spans.push(Span::raw("  "));
         spans.push(Span::styled(
             spinner_frame(app.load.loading_start),
@@ -62,121 +71,505 @@ fn render_header(frame: &mut Frame, app: &App, title: &str, area: Rect, theme: &
     }
 
     let title_line = Line::from(spans);
-    render_with_timestamp(
-        frame,
-        title_line,
-        app.comments_fetched_at,
-        app.clock.now(),
-        theme,
-        area,
+    let fetched_at = if is_focused(app) {
+        app.comments_fetched_at
+    } else {
+        app.preview.fetched_at
+    };
+    render_with_timestamp(frame, title_line, fetched_at, app.clock.now(), theme, area);
+
+    if let Some(root) = app.zoom_root()
+        && area.height > 1
+    {
+        let row = Rect::new(area.x, area.y + 1, area.width, 1);
+        let breadcrumb = zoom_breadcrumb(app, root, area.width as usize);
+        frame.render_widget(RatatuiParagraph::new(breadcrumb), row);
+    }
+}
+
+/// The authors from the top of the thread down to the zoomed comment,
+/// dropping the outermost ones when they don't fit.
+fn zoom_breadcrumb(app: &App, root: usize, width: usize) -> Line<'static> {
+    let theme = &app.theme;
+    let comments = app.comment_tree.comments();
+    let mut path = app.comment_tree.ancestors(root);
+    path.push(root);
+    let crumb = |i: usize| {
+        let comment = &comments[i];
+        let style = Style::default().fg(theme.depth_color(comment.depth));
+        let style = if i == root {
+            style.add_modifier(Modifier::BOLD)
+        } else {
+            style
+        };
+        [
+            Span::styled(" › ", theme.dim_style()),
+            Span::styled(comment.by.clone(), style),
+        ]
+    };
+    let mut start = 0;
+    loop {
+        let mut spans = vec![Span::styled("thread", theme.dim_style())];
+        if start > 0 {
+            spans.push(Span::styled(" › …", theme.dim_style()));
+        }
+        spans.extend(path[start..].iter().flat_map(|&i| crumb(i)));
+        let line = Line::from(spans);
+        if line.width() <= width || start + 1 == path.len() {
+            return line;
+        }
+        start += 1;
+    }
+}
+
+/// Lines of each parent's text shown in the ancestor panel.
+const ANCESTOR_TEXT_LINES: usize = 2;
+
+/// Show what the selected comment replies to above the list, taking up to
+/// half of `area`. Returns the area left for the list.
+#[allow(clippy::cast_possible_truncation)] // capped at half the area
+fn render_ancestors(frame: &mut Frame, app: &App, area: Rect) -> Rect {
+    let ancestors = app.selected_ancestors();
+    let budget = (area.height / 2).saturating_sub(2) as usize;
+    if ancestors.is_empty() || budget == 0 {
+        return area;
+    }
+    let width = area.width.saturating_sub(2) as usize;
+    let lines = ancestor_lines(app, &ancestors, width, budget);
+    let [panel, rest] = Layout::vertical([
+        Constraint::Length(lines.len() as u16 + 2),
+        Constraint::Min(0),
+    ])
+    .areas(area);
+    let context = RatatuiParagraph::new(lines).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .border_style(app.theme.dim_style())
+            .title("Replying to"),
     );
+    frame.render_widget(context, panel);
+    rest
 }
 
-fn render_comment_list(frame: &mut Frame, app: &App, area: Rect) {
+/// Each ancestor's author and first lines, nearest last. The outermost ones
+/// give way when they don't all fit in `budget` lines.
+fn ancestor_lines(
+    app: &App,
+    ancestors: &[&Comment],
+    width: usize,
+    budget: usize,
+) -> Vec<Line<'static>> {
+    // Nearest first, as those are kept
+    let groups: Vec<Vec<Line<'static>>> = ancestors
+        .iter()
+        .enumerate()
+        .rev()
+        .map(|(level, comment)| ancestor_group(app, comment, level, width))
+        .collect();
+    let mut used = 0;
+    let mut shown = 0;
+    for (n, group) in groups.iter().enumerate() {
+        let marker = usize::from(n + 1 < groups.len());
+        if used + group.len() + marker > budget {
+            break;
+        }
+        used += group.len();
+        shown += 1;
+    }
+    let shown = shown.max(1);
+    let hidden = groups.len() - shown;
+
+    let mut lines = Vec::new();
+    if hidden > 0 {
+        lines.push(Line::styled(
+            format!("… {hidden} more above"),
+            app.theme.dim_style(),
+        ));
+    }
+    lines.extend(groups.into_iter().take(shown).rev().flatten());
+    lines.truncate(budget);
+    lines
+}
+
+fn ancestor_group(app: &App, comment: &Comment, level: usize, width: usize) -> Vec<Line<'static>> {
     let theme = &app.theme;
+    let indent = " ".repeat((level * 2).min(width / 2));
+    let author_style = Style::default()
+        .fg(theme.depth_color(comment.depth))
+        .add_modifier(Modifier::BOLD);
+    let mut lines = vec![Line::from(vec![
+        Span::raw(indent.clone()),
+        Span::styled(comment.by.clone(), author_style),
+        Span::styled(" · ", theme.dim_style()),
+        Span::styled(
+            format_relative(comment.time, app.clock.now()),
+            theme.dim_style(),
+        ),
+    ])];
+    let text = strip_html(&comment.text);
+    // One column is kept for the marker on cut text
+    let text_width = width.saturating_sub(indent.len() + 3).max(1);
+    let wrapped = textwrap::wrap(&text, text_width);
+    let cut = wrapped.len() > ANCESTOR_TEXT_LINES;
+    for (i, line) in wrapped.iter().take(ANCESTOR_TEXT_LINES).enumerate() {
+        let mut spans = vec![
+            Span::raw(format!("{indent}  ")),
+            Span::raw(line.to_string()),
+        ];
+        if cut && i + 1 == ANCESTOR_TEXT_LINES {
+            spans.push(Span::styled("…", theme.dim_style()));
+        }
+        lines.push(Line::from(spans));
+    }
+    lines
+}
+
+/// Whether the comments have focus rather than being previewed next to the
+/// story list.
+const fn is_focused(app: &App) -> bool {
+    matches!(app.view, View::Comments { .. })
+}
+
+/// Show an error or the lack of comments in place of the list. Returns
+/// whether there was nothing to list.
+fn render_placeholder(frame: &mut Frame, app: &App, area: Rect) -> bool {
+    let theme = &app.theme;
+    let focused = is_focused(app);
+    let error = if focused {
+        app.load.error.as_ref()
+    } else {
+        app.preview.error.as_ref()
+    };
 
-    if let Some(err) = &app.load.error {
+    if let Some(err) = error {
         render_error(frame, err, theme, area);
-        return;
+        return true;
     }
 
-    if app.comment_tree.is_empty() {
-        let empty = RatatuiParagraph::new("No comments yet")
-            .style(theme.dim_style())
-            .block(
-                Block::default()
-                    .borders(Borders::ALL)
-                    .border_style(theme.border_style())
-                    .title("Comments"),
-            );
+    if app.shown_comment_tree().is_empty() {
+        let text = if !focused && app.preview.loading {
+            "Loading..."
+        } else {
+            "No comments yet"
+        };
+        let empty = RatatuiParagraph::new(text).style(theme.dim_style()).block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(pane_border_style(app, focused))
+                .title("Comments"),
+        );
         frame.render_widget(empty, area);
-        return;
+        return true;
     }
+    false
+}
 
+fn render_comment_list(frame: &mut Frame, app: &App, area: Rect) {
+    if render_placeholder(frame, app, area) {
+        return;
+    }
+    let theme = &app.theme;
+    let focused = is_focused(app);
     let content_width = area.width.saturating_sub(4) as usize;
     let visible_indices = app.visible_comment_indices();
-    let tree_context = compute_tree_context(app.comment_tree.comments(), &visible_indices);
-
-    let items: Vec<CommentListItem> = visible_indices
-        .iter()
-        .enumerate()
-        .map(|(vis_idx, &i)| {
-            let comment = app.comment_tree.get(i).unwrap();
-            let is_expanded = app.comment_tree.is_expanded(comment.id);
-            let has_more = &tree_context[vis_idx];
-            let lines = comment_to_lines(
-                comment,
-                content_width,
-                is_expanded,
-                theme,
-                has_more,
-                &app.clock,
-            );
-            CommentListItem::new(lines)
-        })
-        .collect();
+    let tree = app.shown_comment_tree();
+    let comments = tree.comments();
+    let search = if focused { app.search.pattern() } else { "" };
+    let story = app.comments_story();
+    let op = story.map_or("", |s| s.by.as_str());
+    let mut layout = app.comment_layout.borrow_mut();
+    let flat = app.flat_comments;
+    let zoom_depth = app.zoom_root().map_or(0, |root| comments[root].depth);
+    layout.prepare(story.map(|s| s.id), &theme.name, content_width);
+
+    // Only called for the comments around the viewport
+    let item = |vis_idx: usize| {
+        let i = visible_indices[vis_idx];
+        // The timeline has no tree lines, so every comment sits at the left,
+        // and a zoomed comment's replies are indented from it
+        let comment = &comments[i];
+        let depth = if flat { 0 } else { comment.depth - zoom_depth };
+        let is_expanded = !flat && tree.is_expanded(comment.id);
+        let has_more = if flat {
+            Vec::new()
+        } else {
+            tree_context(comments, &visible_indices, vis_idx).split_off(zoom_depth)
+        };
+        let hints = app
+            .hints
+            .as_ref()
+            .map(|hints| hints.labels_for(comment.id))
+            .unwrap_or_default();
+        let parent = tree.parent(i);
+        let reply = parent
+            .filter(|_| flat)
+            .map(|parent| layout.reply_snippet(parent).to_string());
+        let byline = Byline {
+            op,
+            parent,
+            flat,
+            reply: reply.as_deref(),
+            is_new: app.comment_reads.is_new(comment.id),
+        };
+        let code_block = app
+            .selected_code_block()
+            .filter(|&(id, _)| id == comment.id)
+            .map(|(_, block)| block);
+        // Hint labels and the code block marker come and go, so they skip
+        // the cache
+        let marked;
+        let body = if hints.is_empty() && code_block.is_none() {
+            layout.body(comment, depth, theme)
+        } else {
+            marked = wrap_body(comment, depth, content_width, theme, &hints, code_block);
+            &marked
+        };
+        let placement = Placement {
+            depth,
+            is_expanded,
+            has_more_at_depth: &has_more,
+        };
+        let mut anchors = Vec::new();
+        let lines = comment_to_lines(
+            app,
+            comment,
+            &placement,
+            &byline,
+            body,
+            search,
+            &mut anchors,
+        );
+        CommentListItem::new(lines).links(anchors)
+    };
 
-    let list = CommentList::new(items)
+    let list = CommentList::new(visible_indices.len(), item)
         .block(
             Block::default()
                 .borders(Borders::ALL)
-                .border_style(theme.border_style())
-                .title(format!("Comments ({})", app.comment_tree.len())),
+                .border_style(pane_border_style(app, focused))
+                .title(comments_title(app, visible_indices.len())),
         )
         .highlight_style(Style::default().bg(theme.selection_bg))
-        .highlight_symbol("▶ ");
+        .highlight_symbol(HIGHLIGHT_SYMBOL);
 
     let mut state = CommentListState::new();
-    state.select(Some(app.selected_index));
+    state.select(focused.then_some(app.selected_index));
 
     frame.render_stateful_widget(list, area, &mut state);
+    drop(layout);
+    record_hit_areas(app, &state, &visible_indices, zoom_depth);
+    // Only an open thread is being read, not one previewed
+    if focused {
+        *app.comments_on_screen.borrow_mut() = state
+            .item_areas()
+            .iter()
+            .filter_map(|item| visible_indices.get(item.index))
+            .map(|&i| comments[i].id)
+            .collect();
+    }
+    let mut link_areas = app.link_areas.borrow_mut();
+    for (area, url) in state.link_areas() {
+        link_areas.add(*area, url.as_str());
+    }
+}
+
+/// Names the pane, counting the comments it lists.
+fn comments_title(app: &App, shown: usize) -> Line<'static> {
+    let tree = app.shown_comment_tree();
+    let mut spans = vec![Span::raw(format!("Comments ({shown})"))];
+    // The timeline ignores the sort; otherwise show it when not HN's order
+    let sort = tree.sort();
+    let mode = if app.flat_comments {
+        Some("[timeline]".to_string())
+    } else {
+        (sort != CommentSort::Hn).then(|| format!("[sort: {}]", sort.label()))
+    };
+    let unread = app.unread_only.then(|| "[unread]".to_string());
+    for mode in mode.into_iter().chain(unread) {
+        spans.push(Span::raw(" "));
+        spans.push(Span::styled(mode, app.theme.active_tab_style()));
+    }
+    Line::from(spans)
+}
+
+/// Make comments clickable, and the `[+]`/`[-]` marker of those with replies.
+#[allow(clippy::cast_possible_truncation)] // prefix width fits on screen
+fn record_hit_areas(
+    app: &App,
+    state: &CommentListState,
+    visible_indices: &[usize],
+    zoom_depth: usize,
+) {
+    let mut hits = app.hit_areas.borrow_mut();
+    for item in state.item_areas() {
+        hits.add(item.area, ClickTarget::Comment(item.index));
+        let Some(comment) = visible_indices
+            .get(item.index)
+            .and_then(|&i| app.shown_comment_tree().get(i))
+        else {
+            continue;
+        };
+        if item.starts_on_screen && !comment.kids.is_empty() && !app.flat_comments {
+            let x = item.area.x
+                + HIGHLIGHT_SYMBOL.width() as u16
+                + meta_tree_prefix_width(comment.depth - zoom_depth) as u16;
+            let marker = Rect::new(x, item.area.y, 3, 1).intersection(item.area);
+            hits.add(marker, ClickTarget::CommentToggle(item.index));
+        }
+    }
+}
+
+/// Replies nest this deep before the parent's author is named.
+const REPLYING_TO_DEPTH: usize = 3;
+
+/// Who a comment's author is in the thread.
+struct Byline<'a> {
+    /// The story's submitter.
+    op: &'a str,
+    parent: Option<&'a Comment>,
+    /// In the timeline, where replies aren't nested under what they answer.
+    flat: bool,
+    /// In the timeline, the words of the parent quoted above the text.
+    reply: Option<&'a str>,
+    /// Posted since the thread was last read.
+    is_new: bool,
+}
+
+impl Byline<'_> {
+    fn is_op(&self, author: &str) -> bool {
+        !self.op.is_empty() && author == self.op
+    }
+}
+
+/// Where a comment sits in the list being drawn.
+struct Placement<'a> {
+    /// The depth shown at, which the timeline and zooming change.
+    depth: usize,
+    is_expanded: bool,
+    /// For each level above, whether more comments follow at it.
+    has_more_at_depth: &'a [bool],
 }
 
 fn comment_to_lines(
+    app: &App,
     comment: &Comment,
-    max_width: usize,
-    is_expanded: bool,
-    theme: &ResolvedTheme,
-    has_more_at_depth: &[bool],
-    clock: &Arc<dyn Clock>,
+    placement: &Placement,
+    byline: &Byline,
+    body: &Body,
+    search: &str,
+    anchors: &mut Vec<TextLink>,
 ) -> Vec<Line<'static>> {
+    let theme = &app.theme;
+    let &Placement {
+        depth,
+        is_expanded,
+        has_more_at_depth,
+    } = placement;
     let has_children = !comment.kids.is_empty();
     let show_children_connector = has_children && is_expanded;
     let depth_color = |d| theme.depth_color(d);
 
-    let meta_line = build_meta_line(comment, is_expanded, has_more_at_depth, theme, clock);
+    let meta_line = build_meta_line(app, comment, placement, byline, search);
     let text_lines = build_text_lines(
-        &comment.text,
-        comment.depth,
+        body,
+        depth,
         has_more_at_depth,
         show_children_connector,
-        max_width,
         theme,
+        search,
     );
     let separator_spans = build_empty_line_prefix(
-        comment.depth,
+        depth,
         has_more_at_depth,
         show_children_connector,
         depth_color,
     );
 
     let mut lines = vec![meta_line];
+    // Text starts below the meta line and the reply line, after the tree lines
+    let reply = byline
+        .reply
+        .zip(byline.parent)
+        .map(|(snippet, parent)| reply_line(parent, byline, snippet, theme));
+    lines.extend(reply);
+    let text_top = lines.len();
+    anchors.extend(body.anchors.iter().map(|link| TextLink {
+        line: link.line + text_top,
+        column: link.column + text_prefix_width(depth),
+        ..link.clone()
+    }));
     lines.extend(text_lines);
     lines.push(Line::from(separator_spans));
     lines
 }
 
+const REPLY_TO: &str = "↳ reply to ";
+
+/// Columns taken by the reply line before the parent's words.
+fn reply_prefix_width(parent: &Comment) -> usize {
+    text_prefix_width(0) + REPLY_TO.width() + 1 + parent.by.width()
+}
+
+/// As many of the parent's words as fit in `room` columns.
+fn reply_snippet(parent: &Comment, room: usize) -> String {
+    let text = strip_html(&parent.text);
+    let mut snippet = String::new();
+    if room <= 1 {
+        return snippet;
+    }
+    let mut left = room - 1;
+    for c in text.chars() {
+        let Some(rest) = left.checked_sub(c.width().unwrap_or(0)) else {
+            snippet.push('…');
+            break;
+        };
+        left = rest;
+        snippet.push(c);
+    }
+    snippet
+}
+
+/// Words of the parent shown in the timeline, where replies aren't nested
+/// under what they answer.
+fn reply_line(
+    parent: &Comment,
+    byline: &Byline,
+    snippet: &str,
+    theme: &ResolvedTheme,
+) -> Line<'static> {
+    let author_style = if byline.is_op(&parent.by) {
+        theme.comment_op_style()
+    } else {
+        Style::default().fg(theme.depth_color(parent.depth))
+    };
+    let mut spans = vec![
+        Span::raw(" ".repeat(text_prefix_width(0))),
+        Span::styled(REPLY_TO, theme.dim_style()),
+        Span::styled(format!("@{}", parent.by), author_style),
+    ];
+    if !snippet.is_empty() {
+        spans.push(Span::styled(format!(": {snippet}"), theme.dim_style()));
+    }
+    Line::from(spans)
+}
+
 fn build_meta_line(
+    app: &App,
     comment: &Comment,
-    is_expanded: bool,
-    has_more_at_depth: &[bool],
-    theme: &ResolvedTheme,
-    clock: &Arc<dyn Clock>,
+    placement: &Placement,
+    byline: &Byline,
+    search: &str,
 ) -> Line<'static> {
+    let theme = &app.theme;
+    let &Placement {
+        depth,
+        is_expanded,
+        has_more_at_depth,
+    } = placement;
     let has_children = !comment.kids.is_empty();
-    let color = theme.depth_color(comment.depth);
+    let color = theme.depth_color(depth);
     let depth_color = |d| theme.depth_color(d);
-    let tree_prefix_spans = build_meta_tree_prefix(comment.depth, has_more_at_depth, depth_color);
+    let tree_prefix_spans = build_meta_tree_prefix(depth, has_more_at_depth, depth_color);
 
     let expand_indicator = if has_children {
         if is_expanded {
@@ -189,14 +582,42 @@ fn build_meta_line(
     };
 
     let mut spans = tree_prefix_spans;
-    spans.push(expand_indicator);
-    spans.push(Span::styled(
-        comment.by.clone(),
-        Style::default().fg(color).add_modifier(Modifier::BOLD),
+    // The timeline shows every comment, so there is nothing to expand
+    if !byline.flat {
+        spans.push(expand_indicator);
+    }
+    let is_op = byline.is_op(&comment.by);
+    let author_style = if app.highlights.author(&comment.by) {
+        theme.highlight_style()
+    } else if is_op {
+        theme.comment_op_style()
+    } else {
+        Style::default().fg(color).add_modifier(Modifier::BOLD)
+    };
+    spans.extend(highlight_matches(
+        vec![Span::styled(comment.by.clone(), author_style)],
+        search,
+        theme.search_match_style(),
     ));
+    if is_op {
+        spans.push(Span::raw(" "));
+        spans.push(Span::styled(" OP ", theme.comment_op_badge_style()));
+    }
+    // Deep in a chain the tree lines no longer show who is being answered
+    if depth >= REPLYING_TO_DEPTH
+        && let Some(parent) = byline.parent
+    {
+        let parent_style = if byline.is_op(&parent.by) {
+            theme.comment_op_style()
+        } else {
+            Style::default().fg(theme.depth_color(parent.depth))
+        };
+        spans.push(Span::styled(" → ", theme.dim_style()));
+        spans.push(Span::styled(parent.by.clone(), parent_style));
+    }
     spans.push(Span::styled(" · ", theme.dim_style()));
     spans.push(Span::styled(
-        format_relative(comment.time, clock.now()),
+        format_relative(comment.time, app.clock.now()),
         theme.dim_style(),
     ));
 
@@ -208,6 +629,11 @@ fn build_meta_line(
         ));
     }
 
+    if byline.is_new {
+        spans.push(Span::styled(" · ", theme.dim_style()));
+        spans.push(Span::styled("new", Style::default().fg(theme.success)));
+    }
+
     if comment.is_favorited() {
         spans.push(Span::styled(
             " \u{2728}",
@@ -218,15 +644,130 @@ fn build_meta_line(
     Line::from(spans)
 }
 
+/// Comment text wrapped to fit next to its tree lines.
+#[derive(Debug)]
+struct Body {
+    /// The text this was wrapped from, to notice edits.
+    text: String,
+    lines: Vec<Line<'static>>,
+    anchors: Vec<TextLink>,
+}
+
+/// Comment bodies wrapped by earlier frames, so scrolling and redraws don't
+/// parse and wrap every comment again.
+///
+/// Only the text, depth and width decide how a body wraps; the tree lines,
+/// collapse marker and search matches depend on neighbours and the prompt,
+/// and go around the cached lines each frame.
+#[derive(Debug, Default)]
+pub struct LayoutCache {
+    story: Option<u64>,
+    theme: String,
+    width: usize,
+    /// Keyed by id and the depth shown at, which the timeline and zooming
+    /// change.
+    bodies: HashMap<(u64, usize), Body>,
+    /// What the timeline quotes of each parent, keyed by its id.
+    snippets: HashMap<u64, Snippet>,
+}
+
+/// The start of a parent's text, cut to fit its reply line.
+#[derive(Debug)]
+struct Snippet {
+    /// The text this was cut from, to notice edits.
+    text: String,
+    words: String,
+}
+
+impl LayoutCache {
+    /// Forget bodies wrapped for another story, theme or width.
+    fn prepare(&mut self, story: Option<u64>, theme: &str, width: usize) {
+        if self.story != story || self.theme != theme || self.width != width {
+            self.bodies.clear();
+            self.snippets.clear();
+            self.story = story;
+            self.theme = theme.to_string();
+            self.width = width;
+        }
+    }
+
+    fn body(&mut self, comment: &Comment, depth: usize, theme: &ResolvedTheme) -> &Body {
+        let width = self.width;
+        let body = self
+            .bodies
+            .entry((comment.id, depth))
+            .or_insert_with(|| wrap_body(comment, depth, width, theme, &[], None));
+        if body.text != comment.text {
+            *body = wrap_body(comment, depth, width, theme, &[], None);
+        }
+        body
+    }
+
+    fn reply_snippet(&mut self, parent: &Comment) -> &str {
+        let room = self.width.saturating_sub(reply_prefix_width(parent) + 2);
+        let cut = || Snippet {
+            text: parent.text.clone(),
+            words: reply_snippet(parent, room),
+        };
+        let snippet = self.snippets.entry(parent.id).or_insert_with(cut);
+        if snippet.text != parent.text {
+            *snippet = cut();
+        }
+        &snippet.words
+    }
+}
+
+fn wrap_body(
+    comment: &Comment,
+    depth: usize,
+    max_width: usize,
+    theme: &ResolvedTheme,
+    hints: &[Option<&str>],
+    code_block: Option<usize>,
+) -> Body {
+    let mut paragraphs = parse_comment_html(&comment.text);
+    label_links(&mut paragraphs, hints);
+    let available_width = max_width.saturating_sub(text_prefix_width(depth)).max(20);
+    let code_blocks = paragraphs.iter().filter(|p| p.is_code_block()).count();
+    let mut code_index = 0;
+    let mut lines = Vec::new();
+    let mut anchors = Vec::new();
+    for (i, para) in paragraphs.iter().enumerate() {
+        // Add blank line between paragraphs (except before first), keeping
+        // list items together
+        let in_list = i > 0 && para.is_list_item() && paragraphs[i - 1].is_list_item();
+        if i > 0 && !in_list {
+            lines.push(Line::default());
+        }
+        if para.is_code_block() {
+            if code_block == Some(code_index) {
+                lines.push(code_block_label(para, code_index, code_blocks, theme));
+            }
+            code_index += 1;
+        }
+        let mut para_anchors = Vec::new();
+        let para_lines = render_paragraph(para, available_width, theme, &[], &mut para_anchors);
+        anchors.extend(para_anchors.into_iter().map(|link| TextLink {
+            line: link.line + lines.len(),
+            ..link
+        }));
+        lines.extend(para_lines);
+    }
+    Body {
+        text: comment.text.clone(),
+        lines,
+        anchors,
+    }
+}
+
 fn build_text_lines(
-    text: &str,
+    body: &Body,
     depth: usize,
     has_more_at_depth: &[bool],
     show_children_connector: bool,
-    max_width: usize,
     theme: &ResolvedTheme,
+    search: &str,
 ) -> Vec<Line<'static>> {
-    let paragraphs = parse_comment_html(text);
     let depth_color = |d| theme.depth_color(d);
     let prefix = build_text_prefix(
         depth,
@@ -234,48 +775,42 @@ fn build_text_lines(
         show_children_connector,
         depth_color,
     );
-    let prefix_width: usize = prefix.iter().map(|s| s.content.width()).sum();
-    let available_width = max_width.saturating_sub(prefix_width).max(20);
-    let mut lines = Vec::new();
-    for (i, para) in paragraphs.iter().enumerate() {
-        // Add blank line between paragraphs (except before first)
-        if i > 0 {
-            lines.push(Line::from(prefix.clone()));
-        }
-        let para_lines = render_paragraph(para, available_width, theme, &prefix);
-        lines.extend(para_lines);
-    }
-    lines
+    body.lines
+        .iter()
+        .map(|line| {
+            // Only the comment text is searched, not the tree prefix
+            let mut spans = prefix.clone();
+            spans.extend(highlight_matches(
+                line.spans.clone(),
+                search,
+                theme.search_match_style(),
+            ));
+            Line::from(spans)
+        })
+        .collect()
 }
 
-fn render_paragraph(
+pub fn render_paragraph(
     para: &Paragraph,
     width: usize,
     theme: &ResolvedTheme,
     prefix: &[Span<'static>],
+    anchors: &mut Vec<TextLink>,
 ) -> Vec<Line<'static>> {
-    if para.is_code_block {
-        // Code blocks: render each line with code style, no wrapping
-        return para
-            .spans
-            .iter()
-            .flat_map(|span| {
-                span.text.lines().map(|line| {
-                    let mut line_spans = prefix.to_vec();
-                    line_spans.push(Span::styled(line.to_string(), theme.comment_code_style()));
-                    Line::from(line_spans)
-                })
-            })
-            .collect();
-    }
-    // Build styled spans for this paragraph
-    let base_style = if para.is_quote {
-        theme.comment_quote_style()
-    } else {
-        theme.comment_text_style()
+    // Quotes get a visual quote indicator, and list items their marker
+    // with wrapped lines hanging under the text
+    let (base_style, lead) = match &para.kind {
+        ParagraphKind::CodeBlock => return code_block_lines(para, width, theme, prefix),
+        ParagraphKind::Quote => (
+            theme.comment_quote_style(),
+            ("> ".to_string(), "> ".to_string()),
+        ),
+        ParagraphKind::ListItem { marker } => (
+            theme.comment_text_style(),
+            (format!("{marker} "), " ".repeat(marker.width() + 1)),
+        ),
+        ParagraphKind::Text => (theme.comment_text_style(), (String::new(), String::new())),
     };
-    // For quotes, add a visual quote indicator
-    let quote_prefix = if para.is_quote { "> " } else { "" };
     // Expand links to show URL inline
     let expanded_spans = expand_links(&para.spans);
     // Wrap styled content
@@ -285,10 +820,100 @@ fn render_paragraph(
         theme,
         prefix,
         base_style,
-        quote_prefix,
+        (&lead.0, &lead.1),
+        anchors,
     )
 }
 
+/// Code block lines, highlighted and otherwise kept as they are: nothing
+/// wraps, and lines too wide for the pane are cut off with a marker.
+fn code_block_lines(
+    para: &Paragraph,
+    width: usize,
+    theme: &ResolvedTheme,
+    prefix: &[Span<'static>],
+) -> Vec<Line<'static>> {
+    let code = code_text(para);
+    let mut highlighter = Highlighter::new(syntax::detect(&code));
+    code.lines()
+        .map(|line| {
+            let line = line.replace('\t', "    ");
+            let cut = line.width() > width;
+            let mut room = if cut { width.saturating_sub(1) } else { width };
+            let mut spans = prefix.to_vec();
+            for (token, text) in highlighter.line(&line) {
+                let shown: String = text
+                    .chars()
+                    .take_while(|c| {
+                        let w = c.width().unwrap_or(0);
+                        room.checked_sub(w).is_some_and(|left| {
+                            room = left;
+                            true
+                        })
+                    })
+                    .collect();
+                let done = shown.len() < text.len();
+                spans.push(Span::styled(shown, code_style(token, theme)));
+                if done {
+                    break;
+                }
+            }
+            if cut {
+                spans.push(Span::styled("…", theme.dim_style()));
+            }
+            Line::from(spans)
+        })
+        .collect()
+}
+
+fn code_text(para: &Paragraph) -> String {
+    para.spans.iter().map(|span| span.text.as_str()).collect()
+}
+
+fn code_style(token: Token, theme: &ResolvedTheme) -> Style {
+    match token {
+        Token::Plain => theme.comment_code_style(),
+        Token::Keyword => theme.code_keyword_style(),
+        Token::String => theme.code_string_style(),
+        Token::Number => theme.code_number_style(),
+        Token::Comment => theme.code_comment_style(),
+    }
+}
+
+/// Marks the code block picked with `c`, for `y` to copy.
+fn code_block_label(
+    para: &Paragraph,
+    index: usize,
+    count: usize,
+    theme: &ResolvedTheme,
+) -> Line<'static> {
+    let language = syntax::detect(&code_text(para))
+        .map(|language| format!(" · {}", language.name()))
+        .unwrap_or_default();
+    Line::from(Span::styled(
+        format!("── code {}/{count}{language} · y to copy", index + 1),
+        theme.dim_style(),
+    ))
+}
+
+/// Put each link's hint label in front of it while a link is being picked.
+fn label_links(paragraphs: &mut [Paragraph], hints: &[Option<&str>]) {
+    if hints.is_empty() {
+        return;
+    }
+    let mut hints = hints.iter();
+    for para in paragraphs {
+        for span in std::mem::take(&mut para.spans) {
+            if matches!(span.style, InlineStyle::Link { .. })
+                && let Some(Some(label)) = hints.next()
+            {
+                para.spans.push(StyledSpan::hint(label));
+            }
+            para.spans.push(span);
+        }
+    }
+}
+
 fn expand_links(spans: &[StyledSpan]) -> Vec<StyledSpan> {
     spans
         .iter()
@@ -310,7 +935,8 @@ fn wrap_styled_paragraph(
     theme: &ResolvedTheme,
     prefix: &[Span<'static>],
     base_style: Style,
-    quote_prefix: &str,
+    (first_lead, lead): (&str, &str),
+    anchors: &mut Vec<TextLink>,
 ) -> Vec<Line<'static>> {
     if spans.is_empty() {
         return vec![];
@@ -325,8 +951,8 @@ fn wrap_styled_paragraph(
     if full_text.trim().is_empty() {
         return vec![];
     }
-    // Account for quote prefix in available width
-    let effective_width = width.saturating_sub(quote_prefix.len()).max(10);
+    // Account for the quote or list marker in available width
+    let effective_width = width.saturating_sub(lead.width()).max(10);
     // Wrap the text
     let wrapped = textwrap::wrap(&full_text, effective_width);
     let mut lines = Vec::new();
@@ -336,9 +962,10 @@ fn wrap_styled_paragraph(
         let line_end = char_offset + line_len;
         // Build spans for this wrapped line
         let mut line_spans: Vec<Span<'static>> = prefix.to_vec();
-        // Add quote prefix if applicable
-        if !quote_prefix.is_empty() {
-            line_spans.push(Span::styled(quote_prefix.to_string(), base_style));
+        // Add the quote or list marker if applicable
+        let line_lead = if lines.is_empty() { first_lead } else { lead };
+        if !line_lead.is_empty() {
+            line_spans.push(Span::styled(line_lead.to_string(), base_style));
         }
         // Find which source spans contribute to this line
         let mut pos = char_offset;
@@ -360,6 +987,14 @@ fn wrap_styled_paragraph(
                 let span_len = slice_end - slice_start;
                 let text_slice = &styled_span.text[span_offset..span_offset + span_len];
                 let style = style_for_span(styled_span, theme, base_style);
+                if let InlineStyle::Link { url } = &styled_span.style {
+                    anchors.push(TextLink {
+                        line: lines.len(),
+                        column: line_spans.iter().map(Span::width).sum(),
+                        width: text_slice.width(),
+                        url: url.clone(),
+                    });
+                }
                 line_spans.push(Span::styled(text_slice.to_string(), style));
                 pos = slice_end;
             }
@@ -386,13 +1021,30 @@ fn style_for_span(span: &StyledSpan, theme: &ResolvedTheme, base_style: Style) -
         InlineStyle::Italic => theme.comment_italic_style(),
         InlineStyle::Code => theme.comment_code_style(),
         InlineStyle::Link { .. } => theme.comment_link_style(),
+        InlineStyle::Hint => theme.hint_style(),
     }
 }
 
 fn render_status_bar(frame: &mut Frame, app: &App, area: Rect) {
     use super::spinner::spinner_frame;
 
-    let keymap = global_keymap().extend(comments_keymap());
+    // Keys, matches and messages go with the focused pane
+    if !is_focused(app) {
+        StatusBar::new(&app.theme)
+            .label("Comments")
+            .render(frame, area);
+        return;
+    }
+    if let Some(hints) = &app.hints {
+        let help_text = hints_help().format(&app.keymaps.hints, false);
+        StatusBar::new(&app.theme)
+            .label(hints.action.label())
+            .pending_keys((!hints.typed.is_empty()).then_some(hints.typed.as_str()))
+            .help(&help_text)
+            .render(frame, area);
+        return;
+    }
+    let keymap = view_keymap(app);
     let help_text = comments_help().format(&keymap, false);
 
     let loading_text = if app.load.loading {
@@ -404,9 +1056,13 @@ fn render_status_bar(frame: &mut Frame, app: &App, area: Rect) {
         None
     };
 
+    let pending_keys = app.pending_keys_text();
+    let search_status = app.search_status_text();
     let mut status_bar = StatusBar::new(&app.theme)
         .label("Comments")
-        .position(app.selected_index + 1, app.comment_tree.len())
+        .pending_keys(pending_keys.as_deref())
+        .position(app.selected_index + 1, app.visible_comment_indices().len())
+        .search_status(search_status.as_deref())
         .help(&help_text)
         .flash(app.flash_text());
 
@@ -420,20 +1076,114 @@ fn render_status_bar(frame: &mut Frame, app: &App, area: Rect) {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::app::View;
-    use crate::test_utils::{CommentBuilder, TestAppBuilder, sample_comments};
+    use crate::app::Message;
+    use crate::hints::{HintAction, Hints};
+    use crate::test_utils::{
+        CommentBuilder, StoryBuilder, TestAppBuilder, comments_view, sample_comments,
+    };
     use crate::views::tests::render_to_string;
 
     #[test]
-    fn test_comments_view_renders_thread() {
+    fn test_hint_labels_sit_in_front_of_links() {
+        let mut app = TestAppBuilder::new()
+            .with_comments(vec![
+                CommentBuilder::new()
+                    .id(1)
+                    .text(r#"see <a href="https://a.example">here</a>"#)
+                    .build(),
+            ])
+            .view(comments_view(1, "Story"))
+            .build();
+        app.hints = Some(Hints::new(
+            HintAction::Open,
+            vec![(1, "https://a.example".to_string())],
+        ));
+
+        let output = render_to_string(80, 10, |frame| {
+            render(frame, &app, frame.area());
+        });
+
+        assert!(output.contains("see [s]here (https://a.example)"));
+        assert!(output.contains("Open link"));
+    }
+
+    #[test]
+    fn test_comments_view_rewraps_cached_text() {
+        let mut app = TestAppBuilder::new()
+            .with_comments(vec![
+                CommentBuilder::new()
+                    .id(1)
+                    .text(r#"one two three four five six seven <a href="https://a.example">eight</a>"#)
+                    .build(),
+            ])
+            .view(comments_view(1, "Story"))
+            .build();
+        let wide = |app: &App| render_to_string(80, 10, |frame| render(frame, app, frame.area()));
+
+        assert!(wide(&app).contains("one two three four five six"));
+        let narrow = render_to_string(30, 12, |frame| render(frame, &app, frame.area()));
+        assert!(!narrow.contains("one two three four five six"));
+
+        // Labels shown while picking a link don't stay behind
+        app.hints = Some(Hints::new(
+            HintAction::Open,
+            vec![(1, "https://a.example".to_string())],
+        ));
+        assert!(wide(&app).contains("seven [s]eight"));
+        app.hints = None;
+        assert!(wide(&app).contains("seven eight"));
+    }
+
+    #[test]
+    fn test_comments_view_records_links() {
+        use crate::views::tests::{find_text, render_to_buffer};
+
+        let app = TestAppBuilder::new()
+            .with_comments(vec![
+                CommentBuilder::new()
+                    .id(1)
+                    .text(r#"see <a href="https://a.example/long">a.example/lo...</a> too"#)
+                    .build(),
+            ])
+            .view(comments_view(1, "Story"))
+            .build();
+        let buffer = render_to_buffer(80, 10, |frame| {
+            render(frame, &app, frame.area());
+        });
+
+        let (x, y) = find_text(&buffer, "a.example/lo...").unwrap();
+        assert_eq!(
+            app.link_areas.borrow().links(),
+            [(Rect::new(x, y, 15, 1), "https://a.example/long".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_comments_view_marks_op() {
+        use crate::views::tests::{find_text, render_to_buffer};
+
         let app = TestAppBuilder::new()
             .with_comments(sample_comments())
             .view(View::Comments {
-                story_id: 1,
-                story_title: "Test Story Title".to_string(),
-                story_index: 0,
-                story_scroll: 0,
+                story: StoryBuilder::new().id(1).author("author").build(),
             })
+            .expanded(vec![100, 101])
+            .build();
+        let buffer = render_to_buffer(80, 24, |frame| {
+            render(frame, &app, frame.area());
+        });
+
+        let (x, y) = find_text(&buffer, "author  OP ").unwrap();
+        assert_eq!(buffer[(x, y)].fg, app.theme.comment_op);
+        assert!(buffer[(x + 7, y)].modifier.contains(Modifier::REVERSED));
+        assert!(find_text(&buffer, "commenter2  OP ").is_none());
+    }
+
+    #[test]
+    fn test_comments_view_renders_thread() {
+        let app = TestAppBuilder::new()
+            .with_comments(sample_comments())
+            .view(comments_view(1, "Test Story Title"))
             .expanded(vec![100]) // Expand first comment to show replies
             .build();
 
@@ -444,6 +1194,126 @@ mod tests {
         insta::assert_snapshot!(output);
     }
 
+    #[test]
+    fn test_comments_view_timeline() {
+        let mut app = TestAppBuilder::new()
+            .with_comments(sample_comments())
+            .view(comments_view(1, "Test Story Title"))
+            .build();
+        app.flat_comments = true;
+
+        let output = render_to_string(80, 30, |frame| {
+            render(frame, &app, frame.area());
+        });
+
+        insta::assert_snapshot!(output);
+    }
+
+    #[test]
+    fn test_timeline_marks_new_comments_and_records_those_shown() {
+        let mut app = TestAppBuilder::new()
+            .with_comments(sample_comments())
+            .view(comments_view(1, "Test Story Title"))
+            .build();
+        app.flat_comments = true;
+        app.comment_reads.load([100, 101, 103, 104]);
+        app.comment_reads.settle(app.comment_tree.comments());
+
+        let output = render_to_string(80, 30, |frame| {
+            render(frame, &app, frame.area());
+        });
+
+        let new: Vec<&str> = output.lines().filter(|l| l.contains("· new")).collect();
+        assert_eq!(new.len(), 1);
+        assert!(new[0].contains("author ·"));
+        let mut shown = app.comments_on_screen.take();
+        shown.sort_unstable();
+        assert_eq!(shown, [100, 101, 102, 103, 104]);
+    }
+
+    #[test]
+    fn test_comments_view_zoomed() {
+        let mut app = TestAppBuilder::new()
+            .with_comments(sample_comments())
+            .view(comments_view(1, "Test Story Title"))
+            .build();
+        app.comment_zoom = Some(101);
+
+        let output = render_to_string(80, 16, |frame| {
+            render(frame, &app, frame.area());
+        });
+
+        insta::assert_snapshot!(output);
+    }
+
+    #[test]
+    fn test_comments_view_ancestor_panel() {
+        let mut comments = sample_comments();
+        comments[0].text = "This is a great project! I love the vim keybindings. \
+            The rest of this comment goes on for long enough to be cut off."
+            .into();
+        let mut app = TestAppBuilder::new()
+            .with_comments(comments)
+            .view(comments_view(1, "Test Story Title"))
+            .selected(2)
+            .build();
+        app.show_ancestors = true;
+
+        let output = render_to_string(60, 24, |frame| {
+            render(frame, &app, frame.area());
+        });
+
+        insta::assert_snapshot!(output);
+    }
+
+    #[test]
+    fn test_ancestor_lines_keep_the_nearest_within_budget() {
+        let app = TestAppBuilder::new().build();
+        let ancestors: Vec<Comment> = (0..4)
+            .map(|depth| {
+                CommentBuilder::new()
+                    .id(depth as u64 + 1)
+                    .author(&format!("user{depth}"))
+                    .text("Short")
+                    .depth(depth)
+                    .build()
+            })
+            .collect();
+        let ancestors: Vec<&Comment> = ancestors.iter().collect();
+        let text =
+            |lines: &[Line]| -> Vec<String> { lines.iter().map(ToString::to_string).collect() };
+
+        let all = ancestor_lines(&app, &ancestors, 60, 20);
+        assert_eq!(all.len(), 8);
+        assert!(text(&all)[0].starts_with("user0"));
+
+        // Two authors and their text, below the marker for the two left out
+        let lines = text(&ancestor_lines(&app, &ancestors, 60, 5));
+        assert_eq!(lines.len(), 5);
+        assert_eq!(lines[0], "… 2 more above");
+        assert!(lines[1].trim_start().starts_with("user2"));
+        assert!(lines[3].trim_start().starts_with("user3"));
+
+        // Only the nearest when even one doesn't fit, and never past the budget
+        let lines = text(&ancestor_lines(&app, &ancestors, 60, 2));
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0], "… 3 more above");
+    }
+
+    #[test]
+    fn test_ancestor_text_fits_a_narrow_panel() {
+        let app = TestAppBuilder::new().build();
+        let parent = CommentBuilder::new()
+            .text("Words that go on well past the width of the panel")
+            .depth(3)
+            .build();
+        let lines = ancestor_group(&app, &parent, 3, 14);
+        let text = &lines[1..];
+        assert_eq!(text.len(), ANCESTOR_TEXT_LINES);
+        assert!(text.iter().all(|line| line.width() <= 14));
+        assert!(text[1].to_string().ends_with('…'));
+    }
+
     #[test]
     fn test_comments_view_depth_indentation() {
         let comments = vec![
@@ -479,12 +1349,7 @@ mod tests {
 
         let app = TestAppBuilder::new()
             .with_comments(comments)
-            .view(View::Comments {
-                story_id: 1,
-                story_title: "Deep Thread".to_string(),
-                story_index: 0,
-                story_scroll: 0,
-            })
+            .view(comments_view(1, "Deep Thread"))
             .expanded(vec![1, 2, 3])
             .build();
 
@@ -522,12 +1387,7 @@ mod tests {
         let app = TestAppBuilder::new()
             .with_comments(comments)
             .all_collapsed()
-            .view(View::Comments {
-                story_id: 1,
-                story_title: "Collapsed Test".to_string(),
-                story_index: 0,
-                story_scroll: 0,
-            })
+            .view(comments_view(1, "Collapsed Test"))
             .build();
 
         let output = render_to_string(80, 24, |frame| {
@@ -566,12 +1426,7 @@ mod tests {
 
         let app = TestAppBuilder::new()
             .with_comments(comments)
-            .view(View::Comments {
-                story_id: 1,
-                story_title: "Multiple Top-Level".to_string(),
-                story_index: 0,
-                story_scroll: 0,
-            })
+            .view(comments_view(1, "Multiple Top-Level"))
             .build();
 
         let output = render_to_string(80, 24, |frame| {
@@ -614,12 +1469,7 @@ mod tests {
 
         let app = TestAppBuilder::new()
             .with_comments(comments)
-            .view(View::Comments {
-                story_id: 1,
-                story_title: "Nested Collapse Test".to_string(),
-                story_index: 0,
-                story_scroll: 0,
-            })
+            .view(comments_view(1, "Nested Collapse Test"))
             .expanded(vec![1])
             .build();
 
@@ -633,12 +1483,7 @@ mod tests {
     #[test]
     fn test_comments_view_empty() {
         let app = TestAppBuilder::new()
-            .view(View::Comments {
-                story_id: 1,
-                story_title: "Empty Story".to_string(),
-                story_index: 0,
-                story_scroll: 0,
-            })
+            .view(comments_view(1, "Empty Story"))
             .build();
 
         let output = render_to_string(80, 24, |frame| {
@@ -672,12 +1517,7 @@ mod tests {
         // Render collapsed
         let collapsed_app = TestAppBuilder::new()
             .with_comments(comments.clone())
-            .view(View::Comments {
-                story_id: 1,
-                story_title: "Wrap Test".to_string(),
-                story_index: 0,
-                story_scroll: 0,
-            })
+            .view(comments_view(1, "Wrap Test"))
             .build();
 
         let collapsed_output = render_to_string(80, 16, |frame| {
@@ -687,12 +1527,7 @@ mod tests {
         // Render expanded
         let expanded_app = TestAppBuilder::new()
             .with_comments(comments)
-            .view(View::Comments {
-                story_id: 1,
-                story_title: "Wrap Test".to_string(),
-                story_index: 0,
-                story_scroll: 0,
-            })
+            .view(comments_view(1, "Wrap Test"))
             .expanded(vec![1])
             .build();
 
@@ -707,12 +1542,7 @@ mod tests {
     #[test]
     fn test_comments_view_loading() {
         let app = TestAppBuilder::new()
-            .view(View::Comments {
-                story_id: 1,
-                story_title: "Loading Story".to_string(),
-                story_index: 0,
-                story_scroll: 0,
-            })
+            .view(comments_view(1, "Loading Story"))
             .loading()
             .build();
 
@@ -726,12 +1556,7 @@ mod tests {
     #[test]
     fn test_comments_view_error() {
         let app = TestAppBuilder::new()
-            .view(View::Comments {
-                story_id: 1,
-                story_title: "Error Story".to_string(),
-                story_index: 0,
-                story_scroll: 0,
-            })
+            .view(comments_view(1, "Error Story"))
             .error("Network error: connection refused")
             .build();
 
@@ -767,12 +1592,7 @@ mod tests {
 
         let app = TestAppBuilder::new()
             .with_comments(comments)
-            .view(View::Comments {
-                story_id: 1,
-                story_title: "Partial Render Test".to_string(),
-                story_index: 0,
-                story_scroll: 0,
-            })
+            .view(comments_view(1, "Partial Render Test"))
             .selected(0)
             .build();
 
@@ -793,12 +1613,7 @@ mod tests {
 
         let app = TestAppBuilder::new()
             .with_comments(sample_comments())
-            .view(View::Comments {
-                story_id: 1,
-                story_title: "Test Story with Timestamp".to_string(),
-                story_index: 0,
-                story_scroll: 0,
-            })
+            .view(comments_view(1, "Test Story with Timestamp"))
             .comments_fetched_at(fetched_at)
             .selected(0)
             .build();
@@ -835,12 +1650,7 @@ mod tests {
 
         let app = TestAppBuilder::new()
             .with_comments(comments)
-            .view(View::Comments {
-                story_id: 1,
-                story_title: "Rich Text Test".to_string(),
-                story_index: 0,
-                story_scroll: 0,
-            })
+            .view(comments_view(1, "Rich Text Test"))
             .build();
 
         let output = render_to_string(80, 20, |frame| {
@@ -861,12 +1671,7 @@ mod tests {
 
         let app = TestAppBuilder::new()
             .with_comments(comments)
-            .view(View::Comments {
-                story_id: 1,
-                story_title: "Code Block Test".to_string(),
-                story_index: 0,
-                story_scroll: 0,
-            })
+            .view(comments_view(1, "Code Block Test"))
             .build();
 
         let output = render_to_string(80, 15, |frame| {
@@ -876,6 +1681,55 @@ mod tests {
         insta::assert_snapshot!(output);
     }
 
+    #[test]
+    fn test_comments_code_block_keeps_lines_whole() {
+        let comments = vec![
+            CommentBuilder::new()
+                .id(1)
+                .text("<pre><code>  let x = 1;\n\tif x { println!(\"a rather long line that goes past the edge\"); }</code></pre>")
+                .build(),
+        ];
+        let mut app = TestAppBuilder::new()
+            .with_comments(comments)
+            .view(comments_view(1, "Story"))
+            .build();
+
+        let output = render_to_string(60, 10, |frame| {
+            render(frame, &app, frame.area());
+        });
+        assert!(output.contains("        let x = 1;"));
+        assert!(output.contains("          if x { println!(\"a rather long line that goes p…│"));
+
+        app.update(Message::CycleCodeBlock);
+        let output = render_to_string(60, 10, |frame| {
+            render(frame, &app, frame.area());
+        });
+        assert!(output.contains("── code 1/1 · rust · y to copy"));
+    }
+
+    #[test]
+    fn test_comments_list_items_hang_under_their_marker() {
+        let comments = vec![
+            CommentBuilder::new()
+                .id(1)
+                .text("Two things:<p>- the first one is long enough to wrap onto another line<p>- second")
+                .build(),
+        ];
+        let app = TestAppBuilder::new()
+            .with_comments(comments)
+            .view(comments_view(1, "Story"))
+            .build();
+
+        let output = render_to_string(50, 12, |frame| {
+            render(frame, &app, frame.area());
+        });
+        assert!(output.contains(
+            "│      • the first one is long enough to wrap    │\n\
+             │        onto another line                       │\n\
+             │      • second                                  │"
+        ));
+    }
+
     #[test]
     fn test_comments_multiple_paragraphs() {
         let comments = vec![CommentBuilder::new()
@@ -887,12 +1741,7 @@ mod tests {
 
         let app = TestAppBuilder::new()
             .with_comments(comments)
-            .view(View::Comments {
-                story_id: 1,
-                story_title: "Paragraph Test".to_string(),
-                story_index: 0,
-                story_scroll: 0,
-            })
+            .view(comments_view(1, "Paragraph Test"))
             .build();
 
         let output = render_to_string(80, 15, |frame| {
@@ -901,4 +1750,105 @@ mod tests {
 
         insta::assert_snapshot!(output);
     }
+
+    #[test]
+    fn test_comments_highlight_matching_author() {
+        use crate::highlight::HighlightRule;
+        use crate::views::tests::{find_text, render_to_buffer};
+
+        let app = TestAppBuilder::new()
+            .with_comments(sample_comments())
+            .view(comments_view(1, "Highlight Test"))
+            .highlight(HighlightRule::Author("commenter1".to_string()))
+            .build();
+
+        let buffer = render_to_buffer(80, 24, |frame| {
+            render(frame, &app, frame.area());
+        });
+
+        let pos = find_text(&buffer, "commenter1").unwrap();
+        assert_eq!(
+            Some(buffer.cell(pos).unwrap().fg),
+            app.theme.highlight_style().fg
+        );
+    }
+
+    #[test]
+    fn test_comments_search_highlights_matches() {
+        use crate::app::Message;
+        use crate::search::SearchDirection;
+        use crate::views::tests::{find_text, render_to_buffer};
+        use ratatui::style::Modifier;
+
+        let mut app = TestAppBuilder::new()
+            .with_comments(sample_comments())
+            .view(comments_view(1, "Search Test"))
+            .build();
+        app.update(Message::OpenSearch(SearchDirection::Forward));
+        for c in "windows".chars() {
+            app.update(Message::SearchInput(c));
+        }
+        app.update(Message::SubmitSearch);
+
+        let buffer = render_to_buffer(80, 30, |frame| {
+            render(frame, &app, frame.area());
+        });
+
+        let (x, y) = find_text(&buffer, "Windows?").unwrap();
+        let cell = buffer.cell((x, y)).unwrap();
+        assert!(cell.modifier.contains(Modifier::REVERSED));
+        let after = buffer.cell((x + 7, y)).unwrap();
+        assert!(!after.modifier.contains(Modifier::REVERSED));
+        assert!(find_text(&buffer, "match 1/1").is_some());
+    }
+
+    #[test]
+    fn test_comments_view_records_click_targets() {
+        use crate::mouse::ClickTarget;
+        use crate::views::tests::{find_text, render_to_buffer};
+
+        let app = TestAppBuilder::new()
+            .with_comments(sample_comments())
+            .view(comments_view(1, "Click Test"))
+            .build();
+        let buffer = render_to_buffer(80, 40, |frame| {
+            render(frame, &app, frame.area());
+        });
+
+        let hits = app.hit_areas.borrow();
+        let (x, y) = find_text(&buffer, "[-] commenter2").unwrap();
+        assert_eq!(hits.target_at(x, y), Some(ClickTarget::CommentToggle(1)));
+        assert_eq!(
+            hits.target_at(x + 2, y),
+            Some(ClickTarget::CommentToggle(1))
+        );
+        assert_eq!(hits.target_at(x + 5, y), Some(ClickTarget::Comment(1)));
+        // Text lines belong to the comment too
+        assert_eq!(hits.target_at(x + 5, y + 1), Some(ClickTarget::Comment(1)));
+        // Comments without replies have nothing to toggle
+        let (x, y) = find_text(&buffer, "[ ] windowsuser").unwrap();
+        assert_eq!(hits.target_at(x, y), Some(ClickTarget::Comment(3)));
+    }
+
+    #[test]
+    fn test_comments_preview_shows_story_without_selection() {
+        use crate::test_utils::sample_stories;
+
+        let stories = sample_stories();
+        let id = stories[2].id;
+        let app = TestAppBuilder::new()
+            .with_stories(stories)
+            .with_comments(sample_comments())
+            .preview(id)
+            .viewport_width(200)
+            .build();
+        let output = render_to_string(80, 40, |frame| {
+            render(frame, &app, frame.area());
+        });
+
+        assert!(output.lines().next().unwrap().contains("Ask HN"));
+        assert!(!output.contains(HIGHLIGHT_SYMBOL));
+        // Key hints belong to the focused story list
+        assert!(!output.contains("F1:help"));
+    }
 }
@@ -7,6 +7,8 @@ use ratatui::{
     widgets::{Block, Borders, Paragraph},
 };
 
+use crate::app::App;
+use crate::search;
 use crate::theme::ResolvedTheme;
 use crate::time::format_relative;
 
@@ -21,6 +23,15 @@ pub fn render_error(frame: &mut Frame, error: &str, theme: &ResolvedTheme, area:
     frame.render_widget(widget, area);
 }
 
+/// Border style for a pane, picking out the focused one in the split layout.
+pub fn pane_border_style(app: &App, focused: bool) -> Style {
+    if focused && app.is_split() {
+        app.theme.focused_border_style()
+    } else {
+        app.theme.border_style()
+    }
+}
+
 /// Render a line with an optional right-aligned timestamp showing when data was fetched.
 /// If `fetched_at` is Some, shows "loaded Xm ago" (dimmed if <5m, normal if >=5m).
 #[allow(clippy::cast_sign_loss, clippy::cast_possible_truncation)] // timestamp fits in u64; width fits in u16
@@ -52,3 +63,41 @@ pub fn render_with_timestamp(
         frame.render_widget(Paragraph::new(content_line), area);
     }
 }
+
+/// Split spans so the parts matching the search `query` get `style` on top
+/// of their own. Matches spanning two spans aren't highlighted.
+pub fn highlight_matches(
+    spans: Vec<Span<'static>>,
+    query: &str,
+    style: Style,
+) -> Vec<Span<'static>> {
+    if query.is_empty() {
+        return spans;
+    }
+    let mut result = Vec::with_capacity(spans.len());
+    for span in spans {
+        let ranges = search::find(&span.content, query);
+        if ranges.is_empty() {
+            result.push(span);
+            continue;
+        }
+        let mut pos = 0;
+        for range in ranges {
+            if range.start > pos {
+                result.push(Span::styled(
+                    span.content[pos..range.start].to_string(),
+                    span.style,
+                ));
+            }
+            result.push(Span::styled(
+                span.content[range.clone()].to_string(),
+                span.style.patch(style),
+            ));
+            pos = range.end;
+        }
+        if pos < span.content.len() {
+            result.push(Span::styled(span.content[pos..].to_string(), span.style));
+        }
+    }
+    result
+}
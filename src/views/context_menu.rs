@@ -6,7 +6,6 @@ use ratatui::{
 
 use crate::app::App;
 use crate::help::context_menu_help;
-use crate::keys::context_menu_keymap;
 
 pub fn render(frame: &mut Frame, app: &App, area: Rect) {
     let Some(menu) = &app.context_menu else {
@@ -23,6 +22,7 @@ pub fn render(frame: &mut Frame, app: &App, area: Rect) {
 
     // Clear the area behind the popup
     frame.render_widget(Clear, popup_area);
+    app.link_areas.borrow_mut().cover(popup_area);
 
     // Split popup into list and help areas
     let chunks = Layout::vertical([
@@ -53,8 +53,7 @@ pub fn render(frame: &mut Frame, app: &App, area: Rect) {
     frame.render_stateful_widget(list, chunks[0], &mut state);
 
     // Help line
-    let keymap = context_menu_keymap();
-    let help_text = context_menu_help().format(&keymap, true);
+    let help_text = context_menu_help().format(&app.keymaps.context_menu, true);
     let help = Paragraph::new(help_text).style(theme.dim_style());
     frame.render_widget(help, chunks[1]);
 }
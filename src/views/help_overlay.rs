@@ -9,8 +9,8 @@ use ratatui::{
 };
 
 use crate::app::{App, View};
-use crate::help::{HelpItem, comments_overlay_items, stories_overlay_items};
-use crate::keys::{Keymap, comments_keymap, global_keymap, stories_keymap};
+use crate::help::{comments_overlay_items, stories_overlay_items, user_overlay_items};
+use crate::keys::view_keymap;
 
 #[allow(clippy::cast_possible_truncation)] // popup dimensions fit in u16
 pub fn render(frame: &mut Frame, app: &App, area: Rect) {
@@ -30,16 +30,12 @@ pub fn render(frame: &mut Frame, app: &App, area: Rect) {
     let theme = &app.theme;
 
     // Get view-specific items and keymap
-    let (items, keymap): (Vec<HelpItem>, Keymap) = match &app.view {
-        View::Stories => (
-            stories_overlay_items(),
-            global_keymap().extend(stories_keymap()),
-        ),
-        View::Comments { .. } => (
-            comments_overlay_items(),
-            global_keymap().extend(comments_keymap()),
-        ),
+    let items = match &app.view {
+        View::Stories => stories_overlay_items(),
+        View::Comments { .. } => comments_overlay_items(),
+        View::User { .. } => user_overlay_items(),
     };
+    let keymap = view_keymap(app);
 
     // Format items for display
     let formatted: Vec<(String, &str)> = items
@@ -62,6 +58,7 @@ pub fn render(frame: &mut Frame, app: &App, area: Rect) {
 
     // Clear the area behind the popup
     frame.render_widget(Clear, popup_area);
+    app.link_areas.borrow_mut().cover(popup_area);
 
     // Alternating row background
     let alt_row_style = Style::default().bg(theme.selection_bg);
@@ -111,8 +108,7 @@ const fn centered_rect(width: u16, height: u16, area: Rect) -> Rect {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::app::View;
-    use crate::test_utils::TestAppBuilder;
+    use crate::test_utils::{TestAppBuilder, comments_view};
     use crate::views::tests::render_to_string;
 
     #[test]
@@ -129,12 +125,7 @@ mod tests {
     #[test]
     fn test_help_overlay_comments() {
         let app = TestAppBuilder::new()
-            .view(View::Comments {
-                story_id: 1,
-                story_title: "Test".to_string(),
-                story_index: 0,
-                story_scroll: 0,
-            })
+            .view(comments_view(1, "Test"))
             .help_overlay()
             .build();
 
@@ -145,6 +136,25 @@ mod tests {
         insta::assert_snapshot!(output);
     }
 
+    #[test]
+    fn test_help_overlay_covers_links() {
+        use ratatui::layout::Rect;
+
+        let app = TestAppBuilder::new().help_overlay().build();
+        let mut links = app.link_areas.borrow_mut();
+        links.add(Rect::new(20, 10, 20, 1), "https://under.example");
+        links.add(Rect::new(2, 0, 20, 1), "https://above.example");
+        drop(links);
+
+        render_to_string(80, 24, |frame| {
+            render(frame, &app, frame.area());
+        });
+
+        let links = app.link_areas.borrow();
+        assert_eq!(links.links().len(), 1);
+        assert_eq!(links.links()[0].1, "https://above.example");
+    }
+
     #[test]
     fn test_help_overlay_hidden_when_closed() {
         let app = TestAppBuilder::new().build(); // help_overlay is false
@@ -7,13 +7,20 @@
 /// - `<pre><code>` - code blocks
 /// - `<a href="...">text</a>` - links
 /// - `>` at line start - quote blocks
+///
+/// Plain-text conventions are picked up too: `*emphasis*`, `` `code` ``,
+/// paragraphs indented by two spaces as code, and `-`, `*` or `1.` list items.
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum InlineStyle {
     Plain,
     Italic,
     Code,
-    Link { url: String },
+    Link {
+        url: String,
+    },
+    /// A link hint label, added in front of a link while picking one.
+    Hint,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -44,6 +51,13 @@ impl StyledSpan {
         }
     }
 
+    pub fn hint(label: &str) -> Self {
+        Self {
+            text: format!("[{label}]"),
+            style: InlineStyle::Hint,
+        }
+    }
+
     pub fn link(text: impl Into<String>, url: impl Into<String>) -> Self {
         Self {
             text: text.into(),
@@ -52,37 +66,61 @@ impl StyledSpan {
     }
 }
 
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParagraphKind {
+    Text,
+    Quote,
+    CodeBlock,
+    /// A list item, with its bullet or number, e.g. "•" or "2.".
+    ListItem {
+        marker: String,
+    },
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Paragraph {
     pub spans: Vec<StyledSpan>,
-    pub is_code_block: bool,
-    pub is_quote: bool,
+    pub kind: ParagraphKind,
 }
 
 impl Paragraph {
     pub const fn new(spans: Vec<StyledSpan>) -> Self {
         Self {
             spans,
-            is_code_block: false,
-            is_quote: false,
+            kind: ParagraphKind::Text,
         }
     }
 
     pub const fn code_block(spans: Vec<StyledSpan>) -> Self {
         Self {
             spans,
-            is_code_block: true,
-            is_quote: false,
+            kind: ParagraphKind::CodeBlock,
         }
     }
 
     pub const fn quote(spans: Vec<StyledSpan>) -> Self {
         Self {
             spans,
-            is_code_block: false,
-            is_quote: true,
+            kind: ParagraphKind::Quote,
         }
     }
+
+    pub fn list_item(marker: impl Into<String>, spans: Vec<StyledSpan>) -> Self {
+        Self {
+            spans,
+            kind: ParagraphKind::ListItem {
+                marker: marker.into(),
+            },
+        }
+    }
+
+    pub const fn is_code_block(&self) -> bool {
+        matches!(self.kind, ParagraphKind::CodeBlock)
+    }
+
+    pub const fn is_list_item(&self) -> bool {
+        matches!(self.kind, ParagraphKind::ListItem { .. })
+    }
 }
 
 /// Parse HN comment HTML into structured paragraphs with styled spans.
@@ -121,12 +159,46 @@ fn parse_text_part(part: &str) -> Option<Paragraph> {
             .trim_start();
         let spans = parse_inline_tags(quote_text);
         Some(Paragraph::quote(spans))
+    } else if let Some(code) = indented_code(&text) {
+        Some(Paragraph::code_block(vec![StyledSpan::code(code)]))
+    } else if let Some((marker, item)) = list_marker(trimmed) {
+        Some(Paragraph::list_item(marker, parse_inline_tags(item)))
     } else {
         let spans = parse_inline_tags(trimmed);
         Some(Paragraph::new(spans))
     }
 }
 
+/// Two or more lines all indented by two spaces or more, which is how code
+/// is written on HN when it doesn't arrive as `<pre>`. A single indented
+/// line is more likely prose that starts with a stray space or two.
+fn indented_code(text: &str) -> Option<String> {
+    let text = text.trim_start_matches(['\r', '\n']).trim_end();
+    let lines: Vec<&str> = text
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .collect();
+    let indented = lines.len() >= 2 && lines.iter().all(|line| line.starts_with("  "));
+    (indented && !text.contains('<')).then(|| decode_entities(text))
+}
+
+/// The marker and text of a list item: `- `, `* ` and `• ` become a
+/// bullet, and numbers like `1.` or `2)` are kept.
+fn list_marker(text: &str) -> Option<(String, &str)> {
+    if let Some(item) = ["- ", "* ", "• "]
+        .iter()
+        .find_map(|bullet| text.strip_prefix(bullet))
+    {
+        return Some(("•".to_string(), item.trim_start()));
+    }
+    let digits = text.find(|c: char| !c.is_ascii_digit())?;
+    let rest = &text[digits..];
+    let item = rest
+        .strip_prefix(". ")
+        .or_else(|| rest.strip_prefix(") "))?;
+    (digits > 0 && digits <= 3).then(|| (text[..=digits].to_string(), item.trim_start()))
+}
+
 fn extract_code_blocks(text: &str) -> Vec<Paragraph> {
     let mut result = Vec::new();
     let mut remaining = text;
@@ -143,11 +215,13 @@ fn extract_code_blocks(text: &str) -> Vec<Paragraph> {
         let after_pre = &remaining[pre_start + 5..];
         let pre_end = after_pre.find("</pre>").unwrap_or(after_pre.len());
         let code_content = &after_pre[..pre_end];
-        // Strip <code> tags if present
+        // Strip <code> tags if present, and blank lines around the code but
+        // not the indentation of its first line
         let code = code_content
             .trim_start_matches("<code>")
             .trim_end_matches("</code>")
-            .trim();
+            .trim_end()
+            .trim_start_matches(['\r', '\n']);
         if !code.is_empty() {
             result.push(Paragraph::code_block(vec![StyledSpan::code(code)]));
         }
@@ -268,15 +342,60 @@ fn parse_link(text: &str) -> Option<(String, String, usize)> {
 fn normalize_spans(spans: Vec<StyledSpan>) -> Vec<StyledSpan> {
     spans
         .into_iter()
-        .map(|mut s| {
+        .flat_map(|mut s| {
             // Decode HTML entities in all spans
             s.text = decode_entities(&s.text);
-            s
+            match s.style {
+                InlineStyle::Plain => parse_plain_markup(&s.text),
+                _ => vec![s],
+            }
         })
         .filter(|s| !s.text.is_empty())
         .collect()
 }
 
+/// Split `*emphasis*` and `` `code` `` out of plain text. An asterisk only
+/// opens emphasis at the start of a word and closes it at the end of one,
+/// so arithmetic and footnote markers stay as they are.
+fn parse_plain_markup(text: &str) -> Vec<StyledSpan> {
+    let mut spans = Vec::new();
+    let mut plain_start = 0;
+    let mut pos = 0;
+    while let Some(offset) = text[pos..].find(['*', '`']) {
+        let start = pos + offset;
+        let delimiter = &text[start..=start];
+        let closed = text[start + 1..].find(delimiter).and_then(|len| {
+            let inner = &text[start + 1..start + 1 + len];
+            let end = start + 1 + len + 1;
+            let fits = if delimiter == "*" {
+                let before = text[..start].chars().next_back();
+                let after = text[end..].chars().next();
+                !before.is_some_and(char::is_alphanumeric)
+                    && !after.is_some_and(char::is_alphanumeric)
+                    && !inner.starts_with(char::is_whitespace)
+                    && !inner.ends_with(char::is_whitespace)
+            } else {
+                true
+            };
+            (fits && !inner.is_empty()).then_some((inner, end))
+        });
+        let Some((inner, end)) = closed else {
+            pos = start + 1;
+            continue;
+        };
+        spans.push(StyledSpan::plain(&text[plain_start..start]));
+        spans.push(if delimiter == "*" {
+            StyledSpan::italic(inner)
+        } else {
+            StyledSpan::code(inner)
+        });
+        plain_start = end;
+        pos = end;
+    }
+    spans.push(StyledSpan::plain(&text[plain_start..]));
+    spans
+}
+
 fn decode_entities(text: &str) -> String {
     text.replace("&gt;", ">")
         .replace("&lt;", "<")
@@ -288,8 +407,7 @@ fn decode_entities(text: &str) -> String {
         .replace("&#34;", "\"")
 }
 
-/// Legacy function for backward compatibility - strips HTML to plain text.
-#[allow(dead_code)]
+/// Strips HTML to plain text, e.g. for searching comments.
 pub fn strip_html(html: &str) -> String {
     let paragraphs = parse_comment_html(html);
     paragraphs
@@ -308,6 +426,27 @@ pub fn strip_html(html: &str) -> String {
         .join(" ")
 }
 
+/// URLs of the links in comment HTML, in reading order.
+pub fn links(html: &str) -> Vec<String> {
+    parse_comment_html(html)
+        .into_iter()
+        .flat_map(|p| p.spans)
+        .filter_map(|span| match span.style {
+            InlineStyle::Link { url } => Some(url),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Text of the code blocks in comment HTML, in reading order.
+pub fn code_blocks(html: &str) -> Vec<String> {
+    parse_comment_html(html)
+        .into_iter()
+        .filter(Paragraph::is_code_block)
+        .map(|p| p.spans.into_iter().map(|span| span.text).collect())
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -379,7 +518,7 @@ mod tests {
     fn test_parse_quote() {
         let result = parse_comment_html("&gt; This is quoted text");
         assert_eq!(result.len(), 1);
-        assert!(result[0].is_quote);
+        assert_eq!(result[0].kind, ParagraphKind::Quote);
         assert_eq!(result[0].spans[0].text, "This is quoted text");
     }
 
@@ -387,7 +526,7 @@ mod tests {
     fn test_parse_code_block() {
         let result = parse_comment_html("<pre><code>fn main() {}</code></pre>");
         assert_eq!(result.len(), 1);
-        assert!(result[0].is_code_block);
+        assert!(result[0].is_code_block());
         assert_eq!(result[0].spans[0].text, "fn main() {}");
     }
 
@@ -396,9 +535,62 @@ mod tests {
         let html = "&gt; Quoted intro<p>Some <i>italic</i> and <code>code</code> here";
         let result = parse_comment_html(html);
         assert_eq!(result.len(), 2);
-        assert!(result[0].is_quote);
-        assert!(!result[1].is_quote);
-        assert!(!result[1].is_code_block);
+        assert_eq!(result[0].kind, ParagraphKind::Quote);
+        assert_eq!(result[1].kind, ParagraphKind::Text);
+    }
+
+    #[test]
+    fn test_parse_plain_text_emphasis_and_code() {
+        let result = parse_comment_html("it *really* is `x * y`, not 2*3*4 or * this *");
+        assert_eq!(
+            result[0].spans,
+            vec![
+                StyledSpan::plain("it "),
+                StyledSpan::italic("really"),
+                StyledSpan::plain(" is "),
+                StyledSpan::code("x * y"),
+                StyledSpan::plain(", not 2*3*4 or * this *"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_list_items() {
+        let result = parse_comment_html("Reasons:<p>- one<p>* two<p>3. three<p>2024. A year");
+        assert_eq!(result[0].kind, ParagraphKind::Text);
+        assert_eq!(
+            result[1],
+            Paragraph::list_item("•", vec![StyledSpan::plain("one")])
+        );
+        assert_eq!(
+            result[2],
+            Paragraph::list_item("•", vec![StyledSpan::plain("two")])
+        );
+        assert_eq!(
+            result[3],
+            Paragraph::list_item("3.", vec![StyledSpan::plain("three")])
+        );
+        assert_eq!(result[4].kind, ParagraphKind::Text);
+    }
+
+    #[test]
+    fn test_parse_indented_code() {
+        let result = parse_comment_html("Like so:<p>  x = 1\n    if x:&gt;<p>Done");
+        assert_eq!(
+            result[1],
+            Paragraph::code_block(vec![StyledSpan::code("  x = 1\n    if x:>")])
+        );
+        assert_eq!(result[2].kind, ParagraphKind::Text);
+    }
+
+    #[test]
+    fn test_indented_prose_is_not_code() {
+        let result = parse_comment_html("First.<p>  Then a paragraph that starts with a space.");
+        assert_eq!(result[1].kind, ParagraphKind::Text);
+        assert_eq!(
+            result[1].spans[0].text,
+            "Then a paragraph that starts with a space."
+        );
     }
 
     #[test]
@@ -408,6 +600,21 @@ mod tests {
         assert_eq!(text, "<script> & \"test\"");
     }
 
+    #[test]
+    fn test_links_in_order() {
+        let html = r#"See <a href="https://a.com">a</a><p>and <a href="https://b.com">b</a>"#;
+        assert_eq!(links(html), vec!["https://a.com", "https://b.com"]);
+        assert!(links("no links").is_empty());
+    }
+
+    #[test]
+    fn test_code_blocks_keep_indentation() {
+        let html =
+            "Try:<p><pre><code>\n  if x:\n      y()\n</code></pre><p><pre><code>z</code></pre>";
+        assert_eq!(code_blocks(html), vec!["  if x:\n      y()", "z"]);
+        assert!(code_blocks("<code>inline</code>").is_empty());
+    }
+
     #[test]
     fn test_strip_html_backward_compat() {
         assert_eq!(strip_html("<i>italic</i>"), "italic");
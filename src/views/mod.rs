@@ -1,14 +1,18 @@
+pub mod command_line;
 pub mod comments;
 pub mod common;
 pub mod context_menu;
 pub mod debug;
 pub mod help_overlay;
 pub mod html;
+pub mod search;
 pub mod spinner;
 pub mod status_bar;
 pub mod stories;
+pub mod syntax;
 pub mod theme_picker;
 pub mod tree;
+pub mod user;
 
 #[cfg(test)]
 pub mod tests;
@@ -0,0 +1,63 @@
+use ratatui::{
+    Frame,
+    layout::{Position, Rect},
+    text::{Line, Span},
+    widgets::{Clear, Paragraph},
+};
+use unicode_width::UnicodeWidthStr;
+
+use crate::app::App;
+
+/// Renders the `/` or `?` prompt over the status bar line at the bottom of
+/// `area`, followed by the match count for what has been typed so far.
+pub fn render(frame: &mut Frame, app: &App, area: Rect) {
+    let Some(prompt) = &app.search.prompt else {
+        return;
+    };
+    if area.height == 0 {
+        return;
+    }
+    let theme = &app.theme;
+    let row = Rect {
+        y: area.bottom() - 1,
+        height: 1,
+        ..area
+    };
+
+    let text = format!("{}{}", prompt.direction.prompt(), prompt.input);
+    let mut spans = vec![Span::styled(text.clone(), theme.comment_text_style())];
+    if let Some(status) = app.search_status_text() {
+        spans.push(Span::raw("  "));
+        spans.push(Span::styled(status, theme.dim_style()));
+    }
+
+    frame.render_widget(Clear, row);
+    frame.render_widget(Paragraph::new(Line::from(spans)), row);
+
+    #[allow(clippy::cast_possible_truncation)] // prompt is clamped to the row width
+    let cursor_x = (text.width() as u16).min(row.width.saturating_sub(1));
+    frame.set_cursor_position(Position::new(row.x + cursor_x, row.y));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::Message;
+    use crate::search::SearchDirection;
+    use crate::test_utils::{TestAppBuilder, sample_stories};
+    use crate::views::tests::render_to_string;
+
+    #[test]
+    fn shows_query_and_match_count() {
+        let mut app = TestAppBuilder::new().with_stories(sample_stories()).build();
+        app.update(Message::OpenSearch(SearchDirection::Backward));
+        for c in "zzz".chars() {
+            app.update(Message::SearchInput(c));
+        }
+        let output = render_to_string(40, 3, |frame| render(frame, &app, frame.area()));
+        assert_eq!(
+            output.lines().last().unwrap().trim_end(),
+            "?zzz  no matches"
+        );
+    }
+}
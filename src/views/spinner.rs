@@ -1,4 +1,7 @@
-use std::time::Instant;
+use std::time::{Duration, Instant};
+
+/// How long each spinner frame is shown.
+pub const SPINNER_INTERVAL: Duration = Duration::from_millis(80);
 
 const SPINNER_FRAMES: &[&str] = &["⠋", "⠙", "⠹", "⠸", "⠼", "⠴", "⠦", "⠧", "⠇", "⠏"];
 
@@ -8,6 +11,6 @@ pub fn spinner_frame(start: Option<Instant>) -> &'static str {
     };
 
     let elapsed = start.elapsed().as_millis();
-    let frame_index = (elapsed / 80) as usize % SPINNER_FRAMES.len();
+    let frame_index = (elapsed / SPINNER_INTERVAL.as_millis()) as usize % SPINNER_FRAMES.len();
     SPINNER_FRAMES[frame_index]
 }
@@ -11,7 +11,7 @@ use crate::theme::ResolvedTheme;
 /// Builder for rendering a consistent status bar across views.
 ///
 /// The status bar has a standard layout:
-/// `[Label] [Loading?] Position | Help Text`
+/// `[Label] [Loading?] Position | [Search?] | Help Text`
 pub struct StatusBar<'a> {
     theme: &'a ResolvedTheme,
     label: &'a str,
@@ -19,6 +19,8 @@ pub struct StatusBar<'a> {
     position: Option<(usize, usize)>,
     help_text: &'a str,
     flash_text: Option<&'a str>,
+    pending_keys: Option<&'a str>,
+    search_status: Option<&'a str>,
 }
 
 impl<'a> StatusBar<'a> {
@@ -30,6 +32,8 @@ impl<'a> StatusBar<'a> {
             position: None,
             help_text: "",
             flash_text: None,
+            pending_keys: None,
+            search_status: None,
         }
     }
 
@@ -58,12 +62,32 @@ impl<'a> StatusBar<'a> {
         self
     }
 
+    /// Show an incomplete key sequence or count, like vim's `showcmd`.
+    pub const fn pending_keys(mut self, keys: Option<&'a str>) -> Self {
+        self.pending_keys = keys;
+        self
+    }
+
+    /// Show the search match count, e.g. "match 3/17".
+    pub const fn search_status(mut self, status: Option<&'a str>) -> Self {
+        self.search_status = status;
+        self
+    }
+
     pub fn render(self, frame: &mut Frame, area: Rect) {
         let mut spans = vec![
             Span::styled(format!(" {} ", self.label), self.theme.status_bar_style()),
             Span::raw(" "),
         ];
 
+        if let Some(keys) = self.pending_keys {
+            spans.push(Span::styled(
+                keys.to_string(),
+                self.theme.active_tab_style(),
+            ));
+            spans.push(Span::raw(" | "));
+        }
+
         if let Some(loading) = self.loading_text {
             spans.push(Span::styled(
                 loading.to_string(),
@@ -80,6 +104,14 @@ impl<'a> StatusBar<'a> {
             spans.push(Span::raw(" | "));
         }
 
+        if let Some(status) = self.search_status {
+            spans.push(Span::styled(
+                status.to_string(),
+                self.theme.active_tab_style(),
+            ));
+            spans.push(Span::raw(" | "));
+        }
+
         if let Some(flash) = self.flash_text {
             spans.push(Span::styled(
                 flash.to_string(),
@@ -167,4 +199,32 @@ mod tests {
         assert!(output.contains("Test"));
         assert!(output.contains("q:quit"));
     }
+
+    #[test]
+    fn test_status_bar_pending_keys() {
+        let theme = default_for_variant(ThemeVariant::Dark);
+        let output = render_to_string(40, 1, |frame| {
+            StatusBar::new(&theme)
+                .label("Stories")
+                .pending_keys(Some("5g"))
+                .position(1, 30)
+                .render(frame, frame.area());
+        });
+
+        assert!(output.contains("Stories  5g | 1/30"));
+    }
+
+    #[test]
+    fn test_status_bar_search_status() {
+        let theme = default_for_variant(ThemeVariant::Dark);
+        let output = render_to_string(40, 1, |frame| {
+            StatusBar::new(&theme)
+                .label("Stories")
+                .position(3, 30)
+                .search_status(Some("match 3/17"))
+                .render(frame, frame.area());
+        });
+
+        assert!(output.contains("3/30 | match 3/17 |"));
+    }
 }
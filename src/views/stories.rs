@@ -1,22 +1,24 @@
-use std::sync::Arc;
-
 use ratatui::{
     Frame,
     layout::{Constraint, Layout, Rect},
     text::{Line, Span},
     widgets::{Block, Borders, List, ListItem, ListState},
 };
+use unicode_width::UnicodeWidthStr;
 
 use crate::api::{Feed, Story};
-use crate::app::{App, StorySort};
+use crate::app::{App, StorySort, View};
 use crate::help::stories_help;
-use crate::keys::{global_keymap, stories_keymap};
-
-use crate::theme::ResolvedTheme;
-use crate::time::{Clock, format_relative};
-use crate::views::common::{render_error, render_with_timestamp};
+use crate::keys::view_keymap;
+use crate::mouse::ClickTarget;
+use crate::time::format_relative;
+use crate::views::common::{
+    highlight_matches, pane_border_style, render_error, render_with_timestamp,
+};
 use crate::views::status_bar::StatusBar;
 
+const HIGHLIGHT_SYMBOL: &str = "▶ ";
+
 #[derive(Debug, Clone, Copy, Default)]
 struct ColumnWidths {
     score: usize,
@@ -79,26 +81,31 @@ pub fn render(frame: &mut Frame, app: &App, area: Rect) {
     render_status_bar(frame, app, chunks[2]);
 }
 
+#[allow(clippy::cast_possible_truncation)] // tab widths fit on screen
 fn render_feed_tabs(frame: &mut Frame, app: &App, area: Rect) {
     use super::spinner::spinner_frame;
 
     let theme = &app.theme;
-    let mut spans: Vec<Span> = Feed::all()
-        .iter()
-        .enumerate()
-        .flat_map(|(i, feed)| {
-            let style = if *feed == app.feed {
-                theme.active_tab_style()
-            } else {
-                theme.dim_style()
-            };
-            vec![
-                Span::styled(format!("[{}]", i + 1), theme.dim_style()),
-                Span::styled(feed.label(), style),
-                Span::raw("  "),
-            ]
-        })
-        .collect();
+    let mut hits = app.hit_areas.borrow_mut();
+    let mut spans: Vec<Span> = Vec::new();
+    let mut x = area.x;
+    for (i, feed) in app.feeds.iter().enumerate() {
+        let style = if *feed == app.feed {
+            theme.active_tab_style()
+        } else {
+            theme.dim_style()
+        };
+        let number = Span::styled(format!("[{}]", i + 1), theme.dim_style());
+        let label = Span::styled(feed.label(), style);
+        // Each `[n]Label` tab is clickable where it's drawn
+        let width = (number.width() + label.width()) as u16;
+        hits.add(
+            Rect::new(x, area.y, width, 1).intersection(area),
+            ClickTarget::Tab(*feed),
+        );
+        x = x.saturating_add(width + 2);
+        spans.extend([number, label, Span::raw("  ")]);
+    }
 
     if app.load.should_show_spinner() {
         spans.push(Span::styled(
@@ -126,75 +133,124 @@ fn render_feed_tabs(frame: &mut Frame, app: &App, area: Rect) {
     );
 }
 
+#[allow(clippy::cast_possible_truncation)] // widths on screen fit in u16
 fn render_story_list(frame: &mut Frame, app: &App, area: Rect) {
     let theme = &app.theme;
+    // Next to the comments, the list keeps the open story selected
+    let focused = app.view == View::Stories;
+    let selected = app.story_index();
 
-    if let Some(err) = &app.load.error {
+    if focused && let Some(err) = &app.load.error {
         render_error(frame, err, theme, area);
         return;
     }
 
     let widths = ColumnWidths::from_stories(&app.stories);
+    let search = if focused { app.search.pattern() } else { "" };
 
-    let items: Vec<ListItem> = app
+    let (items, title_widths): (Vec<ListItem>, Vec<usize>) = app
         .stories
         .iter()
         .enumerate()
         .map(|(i, story)| {
-            let is_selected = i == app.selected_index;
-            story_to_list_item(
-                story,
-                theme,
-                &app.clock,
-                app.feed,
-                widths,
-                is_selected,
-                app.story_sort,
-            )
+            let is_selected = i == selected;
+            story_to_list_item(app, story, widths, is_selected, search)
         })
-        .collect();
+        .unzip();
+    let heights: Vec<u16> = items.iter().map(|item| item.height() as u16).collect();
 
     let list = List::new(items)
         .block(
             Block::default()
                 .borders(Borders::TOP | Borders::BOTTOM)
-                .border_style(theme.border_style()),
+                .border_style(pane_border_style(app, focused)),
         )
         .highlight_style(theme.selection_style())
-        .highlight_symbol("▶ ");
+        .highlight_symbol(HIGHLIGHT_SYMBOL);
 
     let mut state = ListState::default();
-    state.select(Some(app.selected_index));
+    state.select(Some(selected));
     frame.render_stateful_widget(list, area, &mut state);
+
+    // Stories are drawn inside the top and bottom borders
+    let inner = Rect {
+        y: area.y + 1,
+        height: area.height.saturating_sub(2),
+        ..area
+    };
+    let mut hits = app.hit_areas.borrow_mut();
+    let mut links = app.link_areas.borrow_mut();
+    // Every item makes room for the highlight symbol while one is selected
+    let indent = if state.selected().is_some() {
+        HIGHLIGHT_SYMBOL.width() as u16
+    } else {
+        0
+    };
+    let mut row = inner.y;
+    for (index, &height) in heights.iter().enumerate().skip(state.offset()) {
+        if row >= inner.bottom() {
+            break;
+        }
+        let story = Rect::new(inner.x, row, inner.width, height).intersection(inner);
+        hits.add(story, ClickTarget::Story(index));
+        let title = Rect::new(
+            inner.x.saturating_add(indent),
+            row,
+            title_widths[index] as u16,
+            1,
+        );
+        links.add(title.intersection(inner), app.stories[index].content_url());
+        row = row.saturating_add(height);
+    }
 }
 
+/// The story's two lines, and the width of the title at the start of the
+/// first.
 fn story_to_list_item(
+    app: &App,
     story: &Story,
-    theme: &ResolvedTheme,
-    clock: &Arc<dyn Clock>,
-    feed: Feed,
     widths: ColumnWidths,
     is_selected: bool,
-    sort: StorySort,
-) -> ListItem<'static> {
+    search: &str,
+) -> (ListItem<'static>, usize) {
     use ratatui::style::Modifier;
 
-    let theme = if story.is_read() && feed != Feed::Favorites && !is_selected {
-        theme.dimmed()
+    let highlights = &app.highlights;
+    let sort = app.story_sort;
+    let theme = if story.is_read() && app.feed != Feed::Favorites && !is_selected {
+        app.theme.dimmed()
+    } else {
+        app.theme.clone()
+    };
+    let title_style = if highlights.title(&story.title) {
+        theme.highlight_style()
+    } else {
+        theme.story_title_style()
+    };
+    let domain = story.domain();
+    let domain_style = if highlights.domain(domain) {
+        theme.highlight_style()
+    } else {
+        theme.story_domain_style()
+    };
+    let author_style = if highlights.author(&story.by) {
+        theme.highlight_style()
     } else {
-        theme.clone()
+        theme.story_author_style()
     };
-    let mut title_spans = vec![Span::styled(story.title.clone(), theme.story_title_style())];
+    let mut title_spans = highlight_matches(
+        vec![Span::styled(story.title.clone(), title_style)],
+        search,
+        theme.search_match_style(),
+    );
+    let title_width = title_spans.iter().map(Span::width).sum();
     if story.is_favorited() {
         title_spans.push(Span::styled(
             " \u{2728}",
             ratatui::style::Style::default().fg(theme.warning),
         ));
     }
-    title_spans.push(Span::styled(
-        format!(" ({})", story.domain()),
-        theme.story_domain_style(),
-    ));
+    title_spans.push(Span::styled(format!(" ({domain})"), domain_style));
     let title_line = Line::from(title_spans);
     let author_display = if story.by.len() > ColumnWidths::MAX_AUTHOR {
         format!("{}...", &story.by[..ColumnWidths::MAX_AUTHOR - 3])
@@ -225,7 +281,7 @@ fn story_to_list_item(
         Span::styled(" | ", theme.dim_style()),
         Span::styled(
             format!("{:<width$}", author_display, width = widths.author),
-            theme.story_author_style(),
+            author_style,
         ),
         Span::styled(" | ", theme.dim_style()),
         Span::styled(
@@ -237,17 +293,29 @@ fn story_to_list_item(
             comments_style,
         ),
         Span::styled(" | ", theme.dim_style()),
-        Span::styled(format_relative(story.time, clock.now()), time_style),
+        Span::styled(format_relative(story.time, app.clock.now()), time_style),
     ]);
-    ListItem::new(vec![title_line, meta_line])
+    (ListItem::new(vec![title_line, meta_line]), title_width)
 }
 
 fn render_status_bar(frame: &mut Frame, app: &App, area: Rect) {
-    let keymap = global_keymap().extend(stories_keymap());
+    // Keys, matches and messages go with the focused pane
+    if app.view != View::Stories {
+        StatusBar::new(&app.theme)
+            .label(app.feed.label())
+            .position(app.story_index() + 1, app.stories.len())
+            .render(frame, area);
+        return;
+    }
+    let keymap = view_keymap(app);
     let help_text = stories_help().format(&keymap, false);
+    let pending_keys = app.pending_keys_text();
+    let search_status = app.search_status_text();
     StatusBar::new(&app.theme)
         .label(app.feed.label())
+        .pending_keys(pending_keys.as_deref())
         .position(app.selected_index + 1, app.stories.len())
+        .search_status(search_status.as_deref())
         .help(&help_text)
         .flash(app.flash_text())
         .render(frame, area);
@@ -287,6 +355,26 @@ mod tests {
         insta::assert_snapshot!(output);
     }
 
+    #[tokio::test]
+    async fn test_stories_view_next_to_comments_selects_open_story() {
+        use crate::app::Message;
+
+        let mut app = TestAppBuilder::new()
+            .with_stories(sample_stories())
+            .selected(1)
+            .viewport_width(200)
+            .build();
+        app.update(Message::OpenComments);
+
+        let output = render_to_string(80, 24, |frame| {
+            render(frame, &app, frame.area());
+        });
+
+        let selected = output.lines().find(|l| l.contains("▶")).unwrap();
+        assert!(selected.contains("Why Rust"));
+        assert!(output.lines().last().unwrap().contains("2/5"));
+    }
+
     #[test]
     fn test_stories_view_error_state() {
         let app = TestAppBuilder::new()
@@ -319,8 +407,8 @@ mod tests {
             render(frame, &app, frame.area());
         });
 
-        // Status bar shows compact help with ?:help
-        assert!(output.contains("?:help"));
+        // Status bar shows compact help with F1:help
+        assert!(output.contains("F1:help"));
     }
 
     #[test]
@@ -446,4 +534,74 @@ mod tests {
 
         insta::assert_snapshot!(output);
     }
+
+    #[test]
+    fn test_stories_view_highlights_matching_rules() {
+        use crate::highlight::HighlightRule;
+        use crate::views::tests::{find_text, render_to_buffer};
+
+        let app = TestAppBuilder::new()
+            .with_stories(sample_stories())
+            .highlight(HighlightRule::Title("rust".to_string()))
+            .highlight(HighlightRule::Domain("lwn.net".to_string()))
+            .highlight(HighlightRule::Author("sama".to_string()))
+            .build();
+
+        let buffer = render_to_buffer(100, 24, |frame| {
+            render(frame, &app, frame.area());
+        });
+
+        let accent = app.theme.highlight_style().fg;
+        let fg_at = |text: &str| {
+            let pos = find_text(&buffer, text).unwrap();
+            buffer.cell(pos).unwrap().fg
+        };
+        assert_eq!(Some(fg_at("Why Rust")), accent);
+        assert_eq!(Some(fg_at("(lwn.net)")), accent);
+        assert_eq!(Some(fg_at("sama")), accent);
+        assert_ne!(Some(fg_at("Show HN")), accent);
+        assert_ne!(Some(fg_at("(blog.example.com)")), accent);
+    }
+
+    #[test]
+    fn test_stories_view_records_click_targets() {
+        use crate::mouse::ClickTarget;
+
+        let app = TestAppBuilder::new().with_stories(sample_stories()).build();
+        render_to_string(80, 24, |frame| {
+            render(frame, &app, frame.area());
+        });
+
+        let hits = app.hit_areas.borrow();
+        // Tabs on the first row: "[1]Favs  [2]Top ..."
+        assert_eq!(
+            hits.target_at(1, 0),
+            Some(ClickTarget::Tab(Feed::Favorites))
+        );
+        assert_eq!(hits.target_at(9, 0), Some(ClickTarget::Tab(Feed::Top)));
+        assert_eq!(hits.target_at(7, 0), None);
+        // Stories take two rows each below the list border
+        assert_eq!(hits.target_at(10, 2), Some(ClickTarget::Story(0)));
+        assert_eq!(hits.target_at(10, 3), Some(ClickTarget::Story(0)));
+        assert_eq!(hits.target_at(10, 4), Some(ClickTarget::Story(1)));
+        assert_eq!(hits.target_at(10, 20), None);
+    }
+
+    #[test]
+    fn test_stories_view_records_title_links() {
+        use crate::views::tests::{find_text, render_to_buffer};
+
+        let app = TestAppBuilder::new().with_stories(sample_stories()).build();
+        let buffer = render_to_buffer(80, 24, |frame| {
+            render(frame, &app, frame.area());
+        });
+
+        let links = app.link_areas.borrow();
+        let title = "Why Rust is the Future of Systems Programming";
+        let (x, y) = find_text(&buffer, title).unwrap();
+        assert!(links.links().contains(&(
+            Rect::new(x, y, 45, 1),
+            "https://example.com/rust-future".to_string()
+        )));
+    }
 }
@@ -0,0 +1,476 @@
+//! Syntax highlighting for code blocks in comments.
+//!
+//! HN marks code only with `<pre>`, so the language is taken from a shebang
+//! or guessed from telltale words, and a small tokenizer picks out keywords,
+//! strings, numbers and comments. It's meant to make snippets easier to
+//! read, not to parse them.
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Language {
+    Rust,
+    Python,
+    JavaScript,
+    Go,
+    C,
+    Shell,
+    Sql,
+}
+
+impl Language {
+    const ALL: [Self; 7] = [
+        Self::Rust,
+        Self::Python,
+        Self::JavaScript,
+        Self::Go,
+        Self::C,
+        Self::Shell,
+        Self::Sql,
+    ];
+
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Rust => "rust",
+            Self::Python => "python",
+            Self::JavaScript => "javascript",
+            Self::Go => "go",
+            Self::C => "c",
+            Self::Shell => "shell",
+            Self::Sql => "sql",
+        }
+    }
+
+    const fn keywords(self) -> &'static [&'static str] {
+        match self {
+            Self::Rust => &[
+                "as", "async", "await", "break", "const", "continue", "crate", "dyn", "else",
+                "enum", "extern", "false", "fn", "for", "if", "impl", "in", "let", "loop", "match",
+                "mod", "move", "mut", "pub", "ref", "return", "self", "Self", "static", "struct",
+                "super", "trait", "true", "type", "unsafe", "use", "where", "while",
+            ],
+            Self::Python => &[
+                "and", "as", "assert", "async", "await", "break", "class", "continue", "def",
+                "del", "elif", "else", "except", "False", "finally", "for", "from", "global", "if",
+                "import", "in", "is", "lambda", "None", "nonlocal", "not", "or", "pass", "raise",
+                "return", "True", "try", "while", "with", "yield",
+            ],
+            Self::JavaScript => &[
+                "async",
+                "await",
+                "break",
+                "case",
+                "catch",
+                "class",
+                "const",
+                "continue",
+                "default",
+                "delete",
+                "do",
+                "else",
+                "export",
+                "extends",
+                "false",
+                "finally",
+                "for",
+                "function",
+                "if",
+                "import",
+                "in",
+                "instanceof",
+                "let",
+                "new",
+                "null",
+                "of",
+                "return",
+                "switch",
+                "this",
+                "throw",
+                "true",
+                "try",
+                "typeof",
+                "undefined",
+                "var",
+                "void",
+                "while",
+                "yield",
+            ],
+            Self::Go => &[
+                "break",
+                "case",
+                "chan",
+                "const",
+                "continue",
+                "default",
+                "defer",
+                "else",
+                "false",
+                "fallthrough",
+                "for",
+                "func",
+                "go",
+                "goto",
+                "if",
+                "import",
+                "interface",
+                "map",
+                "nil",
+                "package",
+                "range",
+                "return",
+                "select",
+                "struct",
+                "switch",
+                "true",
+                "type",
+                "var",
+            ],
+            Self::C => &[
+                "auto", "break", "case", "char", "const", "continue", "default", "do", "double",
+                "else", "enum", "extern", "float", "for", "goto", "if", "int", "long", "return",
+                "short", "signed", "sizeof", "static", "struct", "switch", "typedef", "union",
+                "unsigned", "void", "volatile", "while",
+            ],
+            Self::Shell => &[
+                "case", "do", "done", "elif", "else", "esac", "export", "fi", "for", "function",
+                "if", "in", "local", "return", "then", "until", "while",
+            ],
+            Self::Sql => &[
+                "and", "as", "by", "create", "delete", "from", "group", "having", "in", "insert",
+                "into", "join", "left", "limit", "not", "null", "on", "or", "order", "select",
+                "set", "table", "update", "values", "where", "with",
+            ],
+        }
+    }
+
+    /// Words that give a language away, and how strongly.
+    const fn markers(self) -> &'static [(&'static str, u32)] {
+        match self {
+            Self::Rust => &[
+                ("fn ", 2),
+                ("let mut ", 3),
+                ("::", 1),
+                ("-> ", 1),
+                ("impl ", 2),
+                ("&mut ", 3),
+                ("println!", 3),
+                ("Vec<", 2),
+                ("unwrap()", 3),
+            ],
+            Self::Python => &[
+                ("def ", 2),
+                ("import ", 1),
+                ("self.", 1),
+                ("elif ", 3),
+                ("print(", 1),
+                ("None", 1),
+                ("__init__", 3),
+                ("):\n", 2),
+            ],
+            Self::JavaScript => &[
+                ("function", 2),
+                ("const ", 1),
+                ("=> ", 1),
+                ("console.", 3),
+                ("===", 3),
+                ("document.", 3),
+                ("require(", 2),
+                ("undefined", 2),
+            ],
+            Self::Go => &[
+                ("func ", 3),
+                ("package ", 2),
+                (":= ", 2),
+                ("fmt.", 3),
+                ("err != nil", 3),
+                ("chan ", 2),
+            ],
+            Self::C => &[
+                ("#include", 4),
+                ("int main", 3),
+                ("printf(", 2),
+                ("malloc(", 3),
+                ("NULL", 2),
+                ("void ", 1),
+                ("char *", 2),
+            ],
+            Self::Shell => &[
+                ("$ ", 2),
+                ("sudo ", 3),
+                ("echo ", 2),
+                ("| grep", 3),
+                ("export ", 1),
+                ("apt ", 2),
+                ("brew ", 2),
+                ("; then", 3),
+            ],
+            Self::Sql => &[
+                ("SELECT ", 3),
+                ("FROM ", 1),
+                ("WHERE ", 2),
+                ("INSERT INTO", 3),
+                ("CREATE TABLE", 3),
+                ("JOIN ", 2),
+            ],
+        }
+    }
+
+    const fn line_comment(self) -> &'static str {
+        match self {
+            Self::Python | Self::Shell => "#",
+            Self::Sql => "--",
+            Self::Rust | Self::JavaScript | Self::Go | Self::C => "//",
+        }
+    }
+
+    const fn block_comments(self) -> bool {
+        !matches!(self, Self::Python | Self::Shell)
+    }
+
+    fn is_keyword(self, word: &str) -> bool {
+        if self == Self::Sql {
+            let word = word.to_ascii_lowercase();
+            self.keywords().contains(&word.as_str())
+        } else {
+            self.keywords().contains(&word)
+        }
+    }
+}
+
+/// The language of a code block: named by a shebang, or else the one whose
+/// telltale words show up most. `None` when nothing stands out.
+pub fn detect(code: &str) -> Option<Language> {
+    let first = code.lines().find(|l| !l.trim().is_empty())?.trim();
+    if let Some(interpreter) = first.strip_prefix("#!") {
+        return if interpreter.contains("python") {
+            Some(Language::Python)
+        } else if interpreter.contains("node") || interpreter.contains("deno") {
+            Some(Language::JavaScript)
+        } else if interpreter.ends_with("sh") {
+            Some(Language::Shell)
+        } else {
+            None
+        };
+    }
+    Language::ALL
+        .into_iter()
+        .map(|language| {
+            let score: u32 = language
+                .markers()
+                .iter()
+                .filter(|(marker, _)| code.contains(marker))
+                .map(|(_, weight)| weight)
+                .sum();
+            (score, language)
+        })
+        // Ties go to the language listed first
+        .rev()
+        .max_by_key(|&(score, _)| score)
+        .filter(|&(score, _)| score >= 3)
+        .map(|(_, language)| language)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Token {
+    Plain,
+    Keyword,
+    String,
+    Number,
+    Comment,
+}
+
+/// Splits code into tokens a line at a time, carrying block comments over
+/// from one line to the next.
+#[derive(Debug)]
+pub struct Highlighter {
+    language: Option<Language>,
+    in_comment: bool,
+}
+
+impl Highlighter {
+    pub const fn new(language: Option<Language>) -> Self {
+        Self {
+            language,
+            in_comment: false,
+        }
+    }
+
+    /// The pieces of `line`, in order, with neighbours of the same kind
+    /// joined up.
+    pub fn line<'a>(&mut self, line: &'a str) -> Vec<(Token, &'a str)> {
+        let Some(language) = self.language else {
+            return vec![(Token::Plain, line)];
+        };
+        // Token kinds with the end of each, joined with the one before when
+        // of the same kind
+        let mut tokens: Vec<(Token, usize)> = Vec::new();
+        let mut pos = 0;
+        while pos < line.len() {
+            let rest = &line[pos..];
+            let (token, len) = if self.in_comment {
+                let len = rest.find("*/").map_or(rest.len(), |i| {
+                    self.in_comment = false;
+                    i + 2
+                });
+                (Token::Comment, len)
+            } else if rest.starts_with(language.line_comment()) {
+                (Token::Comment, rest.len())
+            } else if language.block_comments() && rest.starts_with("/*") {
+                self.in_comment = true;
+                (Token::Comment, 2)
+            } else if let Some(len) = string_len(rest, language) {
+                (Token::String, len)
+            } else if rest.starts_with(|c: char| c.is_ascii_digit()) {
+                (Token::Number, number_len(rest))
+            } else if rest.starts_with(is_word_char) {
+                let len = rest.find(|c| !is_word_char(c)).unwrap_or(rest.len());
+                if language.is_keyword(&rest[..len]) {
+                    (Token::Keyword, len)
+                } else {
+                    (Token::Plain, len)
+                }
+            } else {
+                (Token::Plain, rest.chars().next().map_or(1, char::len_utf8))
+            };
+            pos += len;
+            match tokens.last_mut() {
+                Some((last, end)) if *last == token => *end = pos,
+                _ => tokens.push((token, pos)),
+            }
+        }
+        let mut start = 0;
+        tokens
+            .into_iter()
+            .map(|(token, end)| {
+                let text = &line[start..end];
+                start = end;
+                (token, text)
+            })
+            .collect()
+    }
+}
+
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+fn number_len(text: &str) -> usize {
+    text.find(|c: char| !is_word_char(c) && c != '.')
+        .unwrap_or(text.len())
+}
+
+/// Length of the string literal at the start of `text`, up to its closing
+/// quote. A quote that isn't closed on the line is left alone, so Rust
+/// lifetimes and apostrophes in prose stay plain.
+fn string_len(text: &str, language: Language) -> Option<usize> {
+    let quote = text.chars().next()?;
+    let quotes: &[char] = match language {
+        Language::JavaScript | Language::Shell | Language::Go => &['"', '\'', '`'],
+        _ => &['"', '\''],
+    };
+    if !quotes.contains(&quote) {
+        return None;
+    }
+    // In Rust a quote starts a lifetime unless it's a character literal
+    if language == Language::Rust && quote == '\'' && !text[1..].starts_with('\\') {
+        let c = text[1..].chars().next()?;
+        let len = 1 + c.len_utf8();
+        return text[len..].starts_with('\'').then_some(len + 1);
+    }
+    let mut escaped = false;
+    for (i, c) in text.char_indices().skip(1) {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' => escaped = true,
+            c if c == quote => return Some(i + c.len_utf8()),
+            _ => {}
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_from_shebang() {
+        assert_eq!(
+            detect("#!/usr/bin/env python3\nx = 1"),
+            Some(Language::Python)
+        );
+        assert_eq!(detect("\n#!/bin/bash\nls"), Some(Language::Shell));
+        assert_eq!(detect("#!/usr/bin/perl\nprint 1"), None);
+    }
+
+    #[test]
+    fn test_detect_from_telltale_words() {
+        assert_eq!(
+            detect("fn main() {\n    let mut x = Vec::new();\n}"),
+            Some(Language::Rust)
+        );
+        assert_eq!(
+            detect("def f(self):\n    return None"),
+            Some(Language::Python)
+        );
+        assert_eq!(
+            detect("if err != nil {\n    return err\n}"),
+            Some(Language::Go)
+        );
+        assert_eq!(detect("$ sudo apt install foo"), Some(Language::Shell));
+        assert_eq!(detect("SELECT id FROM users"), Some(Language::Sql));
+        assert_eq!(detect("just some\n  indented text"), None);
+    }
+
+    #[test]
+    fn test_tokenize_line() {
+        let mut highlighter = Highlighter::new(Some(Language::Rust));
+        assert_eq!(
+            highlighter.line(r#"let x = "a \" b"; // 42"#),
+            vec![
+                (Token::Keyword, "let"),
+                (Token::Plain, " x = "),
+                (Token::String, r#""a \" b""#),
+                (Token::Plain, "; "),
+                (Token::Comment, "// 42"),
+            ]
+        );
+        assert_eq!(
+            highlighter.line("fn f<'a>(x: &'a str) -> u8 { 0x1f }"),
+            vec![
+                (Token::Keyword, "fn"),
+                (Token::Plain, " f<'a>(x: &'a str) -> u8 { "),
+                (Token::Number, "0x1f"),
+                (Token::Plain, " }"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_block_comments_span_lines() {
+        let mut highlighter = Highlighter::new(Some(Language::C));
+        assert_eq!(
+            highlighter.line("int x; /* one"),
+            vec![
+                (Token::Keyword, "int"),
+                (Token::Plain, " x; "),
+                (Token::Comment, "/* one"),
+            ]
+        );
+        assert_eq!(
+            highlighter.line("two */ return"),
+            vec![
+                (Token::Comment, "two */"),
+                (Token::Plain, " "),
+                (Token::Keyword, "return"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_unknown_language_is_plain() {
+        let mut highlighter = Highlighter::new(None);
+        assert_eq!(
+            highlighter.line("let x = 1"),
+            vec![(Token::Plain, "let x = 1")]
+        );
+    }
+}
@@ -4,13 +4,38 @@ use ratatui::backend::TestBackend;
 use ratatui::buffer::Buffer;
 
 pub fn render_to_string<F>(width: u16, height: u16, render_fn: F) -> String
+where
+    F: FnOnce(&mut Frame),
+{
+    buffer_to_string(&render_to_buffer(width, height, render_fn))
+}
+
+/// Renders into a test buffer, for assertions on cell styles.
+pub fn render_to_buffer<F>(width: u16, height: u16, render_fn: F) -> Buffer
 where
     F: FnOnce(&mut Frame),
 {
     let backend = TestBackend::new(width, height);
     let mut terminal = Terminal::new(backend).unwrap();
     terminal.draw(render_fn).unwrap();
-    buffer_to_string(terminal.backend().buffer())
+    terminal.backend().buffer().clone()
+}
+
+/// Finds the position of the first cell where `text` starts.
+pub fn find_text(buffer: &Buffer, text: &str) -> Option<(u16, u16)> {
+    for y in 0..buffer.area.height {
+        let mut line = String::new();
+        let mut columns = Vec::new();
+        for x in 0..buffer.area.width {
+            let symbol = buffer.cell((x, y)).unwrap().symbol();
+            columns.extend(std::iter::repeat_n(x, symbol.len()));
+            line.push_str(symbol);
+        }
+        if let Some(byte_idx) = line.find(text) {
+            return Some((columns[byte_idx], y));
+        }
+    }
+    None
 }
 
 fn buffer_to_string(buffer: &Buffer) -> String {
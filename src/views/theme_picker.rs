@@ -6,7 +6,6 @@ use ratatui::{
 
 use crate::app::App;
 use crate::help::theme_picker_help;
-use crate::keys::theme_picker_keymap;
 
 pub fn render(frame: &mut Frame, app: &App, area: Rect) {
     let Some(picker) = &app.theme_picker else {
@@ -22,6 +21,7 @@ pub fn render(frame: &mut Frame, app: &App, area: Rect) {
 
     // Clear the area behind the popup
     frame.render_widget(Clear, popup_area);
+    app.link_areas.borrow_mut().cover(popup_area);
 
     // Split popup into list and help areas
     let chunks = Layout::vertical([
@@ -52,8 +52,7 @@ pub fn render(frame: &mut Frame, app: &App, area: Rect) {
     frame.render_stateful_widget(list, chunks[0], &mut state);
 
     // Help line
-    let keymap = theme_picker_keymap();
-    let help_text = theme_picker_help().format(&keymap, true);
+    let help_text = theme_picker_help().format(&app.keymaps.theme_picker, true);
     let help = Paragraph::new(help_text).style(theme.dim_style());
     frame.render_widget(help, chunks[1]);
 }
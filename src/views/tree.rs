@@ -6,32 +6,27 @@ use ratatui::{style::Style, text::Span};
 
 use crate::api::Comment;
 
-/// Compute tree context for visible comments.
+/// Compute tree context for a visible comment.
 ///
-/// For each visible comment, returns a vector of booleans indicating whether
-/// there are more siblings at each depth level. This is used to determine
-/// whether to draw │ (continuation) or leave blank at each indentation level.
-pub fn compute_tree_context(comments: &[Comment], visible_indices: &[usize]) -> Vec<Vec<bool>> {
-    visible_indices
-        .iter()
-        .enumerate()
-        .map(|(vis_idx, &actual_idx)| {
-            let depth = comments[actual_idx].depth;
-
-            (0..=depth)
-                .map(|check_depth| {
-                    for &future_idx in &visible_indices[vis_idx + 1..] {
-                        let future_depth = comments[future_idx].depth;
-                        if future_depth == check_depth {
-                            return true;
-                        }
-                        if future_depth < check_depth {
-                            return false;
-                        }
-                    }
-                    false
-                })
-                .collect()
+/// Returns a vector of booleans indicating whether there are more siblings
+/// at each depth level below the comment at `vis_idx`. This is used to
+/// determine whether to draw │ (continuation) or leave blank at each
+/// indentation level.
+pub fn tree_context(comments: &[Comment], visible_indices: &[usize], vis_idx: usize) -> Vec<bool> {
+    let depth = comments[visible_indices[vis_idx]].depth;
+
+    (0..=depth)
+        .map(|check_depth| {
+            for &future_idx in &visible_indices[vis_idx + 1..] {
+                let future_depth = comments[future_idx].depth;
+                if future_depth == check_depth {
+                    return true;
+                }
+                if future_depth < check_depth {
+                    return false;
+                }
+            }
+            false
         })
         .collect()
 }
@@ -78,6 +73,17 @@ where
     spans
 }
 
+/// Display width of the prefix from [`build_meta_tree_prefix`], i.e. where
+/// the collapse marker starts.
+pub const fn meta_tree_prefix_width(depth: usize) -> usize {
+    depth * 4
+}
+
+/// Display width of the prefix from [`build_text_prefix`].
+pub const fn text_prefix_width(depth: usize) -> usize {
+    (depth + 1) * 4
+}
+
 /// Build the tree prefix for comment text lines.
 ///
 /// Similar to meta prefix but extends one level deeper to show
@@ -151,6 +157,12 @@ mod tests {
     use super::*;
     use crate::test_utils::CommentBuilder;
 
+    fn compute_tree_context(comments: &[Comment], visible_indices: &[usize]) -> Vec<Vec<bool>> {
+        (0..visible_indices.len())
+            .map(|vis_idx| tree_context(comments, visible_indices, vis_idx))
+            .collect()
+    }
+
     #[test]
     fn test_compute_tree_context_single_comment() {
         let comments = vec![CommentBuilder::new().id(1).depth(0).build()];
@@ -215,6 +227,17 @@ mod tests {
         assert_eq!(spans_to_string(&spans), " └─ ");
     }
 
+    #[test]
+    fn test_meta_tree_prefix_width_matches_prefix() {
+        for depth in 0..4 {
+            let width: usize = build_meta_tree_prefix(depth, &[false, true, false, true], white)
+                .iter()
+                .map(Span::width)
+                .sum();
+            assert_eq!(width, meta_tree_prefix_width(depth));
+        }
+    }
+
     #[test]
     fn test_build_text_prefix_with_children() {
         let spans = build_text_prefix(0, &[false], true, white);
@@ -0,0 +1,183 @@
+use ratatui::{
+    Frame,
+    layout::{Constraint, Layout, Rect},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph, Wrap},
+};
+
+use crate::api::User;
+use crate::app::{App, View};
+use crate::help::user_help;
+use crate::keys::view_keymap;
+use crate::theme::ResolvedTheme;
+use crate::time::format_date;
+use crate::views::comments::render_paragraph;
+use crate::views::common::{pane_border_style, render_error};
+use crate::views::html::parse_comment_html;
+use crate::views::status_bar::StatusBar;
+
+pub fn render(frame: &mut Frame, app: &App, area: Rect) {
+    let chunks = Layout::vertical([
+        Constraint::Length(2), // User name
+        Constraint::Min(0),    // Profile
+        Constraint::Length(1), // Status bar
+    ])
+    .split(area);
+
+    render_header(frame, app, chunks[0]);
+    render_profile(frame, app, chunks[1]);
+    render_status_bar(frame, app, chunks[2]);
+}
+
+fn render_header(frame: &mut Frame, app: &App, area: Rect) {
+    use super::spinner::spinner_frame;
+
+    let theme = &app.theme;
+    let name = match &app.view {
+        View::User { name } => name.as_str(),
+        _ => "",
+    };
+    let mut spans = vec![Span::styled(
+        name.to_string(),
+        Style::default()
+            .fg(theme.story_title)
+            .add_modifier(Modifier::BOLD),
+    )];
+    if app.load.should_show_spinner() {
+        spans.push(Span::raw("  "));
+        spans.push(Span::styled(
+            spinner_frame(app.load.loading_start),
+            theme.spinner_style(),
+        ));
+    }
+    frame.render_widget(Paragraph::new(Line::from(spans)), area);
+}
+
+fn render_profile(frame: &mut Frame, app: &App, area: Rect) {
+    let theme = &app.theme;
+    if let Some(err) = &app.load.error {
+        render_error(frame, err, theme, area);
+        return;
+    }
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(pane_border_style(app, true))
+        .title("Profile");
+    let Some(user) = &app.user else {
+        let loading = Paragraph::new("Loading...")
+            .style(theme.dim_style())
+            .block(block);
+        frame.render_widget(loading, area);
+        return;
+    };
+
+    let width = area.width.saturating_sub(4) as usize;
+    let profile = Paragraph::new(profile_lines(user, width, theme))
+        .block(block)
+        .wrap(Wrap { trim: false });
+    frame.render_widget(profile, area);
+}
+
+/// Karma, join date and submission count, then the user's about text.
+fn profile_lines(user: &User, width: usize, theme: &ResolvedTheme) -> Vec<Line<'static>> {
+    let field = |label: &str, value: String| {
+        Line::from(vec![
+            Span::styled(format!("{label:<12}"), theme.dim_style()),
+            Span::styled(value, theme.comment_text_style()),
+        ])
+    };
+    let width = width.max(20);
+    let mut lines = vec![
+        field("karma", user.karma.to_string()),
+        field("joined", format_date(user.created)),
+        field("submitted", user.submitted.len().to_string()),
+    ];
+    for para in parse_comment_html(user.about.as_deref().unwrap_or_default()) {
+        lines.push(Line::default());
+        lines.extend(render_paragraph(&para, width, theme, &[], &mut Vec::new()));
+    }
+    lines
+}
+
+fn render_status_bar(frame: &mut Frame, app: &App, area: Rect) {
+    let keymap = view_keymap(app);
+    let help_text = user_help().format(&keymap, false);
+    let pending_keys = app.pending_keys_text();
+    StatusBar::new(&app.theme)
+        .label("User")
+        .pending_keys(pending_keys.as_deref())
+        .help(&help_text)
+        .flash(app.flash_text())
+        .render(frame, area);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::TestAppBuilder;
+    use crate::views::tests::render_to_string;
+
+    fn pg() -> User {
+        User {
+            id: "pg".to_string(),
+            created: 1160418092,
+            karma: 157316,
+            about: Some(
+                "Bug fixer.<p>See <a href=\"http://paulgraham.com\">my site</a>".to_string(),
+            ),
+            submitted: vec![1, 2, 3],
+        }
+    }
+
+    #[test]
+    fn test_user_view_renders_profile() {
+        let app = TestAppBuilder::new()
+            .view(View::User {
+                name: "pg".to_string(),
+            })
+            .user(pg())
+            .build();
+
+        let output = render_to_string(80, 16, |frame| {
+            render(frame, &app, frame.area());
+        });
+
+        insta::assert_snapshot!(output);
+    }
+
+    #[test]
+    fn test_user_view_fits_a_narrow_pane() {
+        let mut user = pg();
+        user.about = Some("<pre><code>fn main() { println!(\"hi\"); }</code></pre>".to_string());
+        let app = TestAppBuilder::new()
+            .view(View::User {
+                name: "pg".to_string(),
+            })
+            .user(user)
+            .build();
+
+        let output = render_to_string(3, 10, |frame| {
+            render(frame, &app, frame.area());
+        });
+
+        assert!(!output.is_empty());
+    }
+
+    #[test]
+    fn test_user_view_shows_loading() {
+        let app = TestAppBuilder::new()
+            .view(View::User {
+                name: "pg".to_string(),
+            })
+            .build();
+
+        let output = render_to_string(80, 8, |frame| {
+            render(frame, &app, frame.area());
+        });
+
+        assert!(output.contains("pg"));
+        assert!(output.contains("Loading..."));
+    }
+}
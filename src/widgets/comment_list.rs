@@ -2,7 +2,11 @@
 //!
 //! Unlike Ratatui's standard List widget which skips items that don't fit
 //! entirely, this widget renders partial items at viewport boundaries,
-//! filling the available space without gaps.
+//! filling the available space without gaps. Items are built on demand, and
+//! only those around the viewport are, so long threads cost no more per
+//! frame than short ones.
+
+use std::collections::VecDeque;
 
 use ratatui::{
     buffer::Buffer,
@@ -12,10 +16,24 @@ use ratatui::{
     widgets::{Block, StatefulWidget, Widget},
 };
 
+use crate::hyperlink::TextLink;
+
 /// State for the `CommentList` widget.
 #[derive(Default)]
 pub struct CommentListState {
     selected: Option<usize>,
+    item_areas: Vec<ItemArea>,
+    link_areas: Vec<(Rect, String)>,
+}
+
+/// Where an item ended up on screen after rendering.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ItemArea {
+    pub index: usize,
+    /// The visible part of the item.
+    pub area: Rect,
+    /// False when the item's first line is scrolled off the top.
+    pub starts_on_screen: bool,
 }
 
 impl CommentListState {
@@ -26,16 +44,36 @@ impl CommentListState {
     pub const fn select(&mut self, index: Option<usize>) {
         self.selected = index;
     }
+
+    /// Items drawn by the last render, for mouse hit testing.
+    pub fn item_areas(&self) -> &[ItemArea] {
+        &self.item_areas
+    }
+
+    /// Links drawn by the last render, clipped to the list.
+    pub fn link_areas(&self) -> &[(Rect, String)] {
+        &self.link_areas
+    }
 }
 
 /// A single item in the comment list, containing multiple lines.
 pub struct CommentListItem<'a> {
     lines: Vec<Line<'a>>,
+    links: Vec<TextLink>,
 }
 
 impl<'a> CommentListItem<'a> {
     pub const fn new(lines: Vec<Line<'a>>) -> Self {
-        Self { lines }
+        Self {
+            lines,
+            links: Vec::new(),
+        }
+    }
+
+    /// Where links sit in the item's lines.
+    pub fn links(mut self, links: Vec<TextLink>) -> Self {
+        self.links = links;
+        self
     }
 
     pub const fn height(&self) -> usize {
@@ -44,19 +82,26 @@ impl<'a> CommentListItem<'a> {
 }
 
 /// A list widget that renders partial items at viewport boundaries.
-pub struct CommentList<'a> {
-    items: Vec<CommentListItem<'a>>,
+pub struct CommentList<'a, F> {
+    len: usize,
+    item: F,
     block: Option<Block<'a>>,
     highlight_style: Style,
     highlight_symbol: &'a str,
 }
 
-impl<'a> CommentList<'a> {
-    pub fn new(items: Vec<CommentListItem<'a>>) -> Self {
+impl<'a, F> CommentList<'a, F>
+where
+    F: FnMut(usize) -> CommentListItem<'a>,
+{
+    /// A list of `len` items, built by `item` from their index when they
+    /// come near the viewport.
+    pub const fn new(len: usize, item: F) -> Self {
         Self {
-            items,
+            len,
+            item,
             block: None,
-            highlight_style: Style::default(),
+            highlight_style: Style::new(),
             highlight_symbol: "",
         }
     }
@@ -77,11 +122,14 @@ impl<'a> CommentList<'a> {
     }
 }
 
-impl StatefulWidget for CommentList<'_> {
+impl<'a, F> StatefulWidget for CommentList<'a, F>
+where
+    F: FnMut(usize) -> CommentListItem<'a>,
+{
     type State = CommentListState;
 
     #[allow(clippy::option_if_let_else, clippy::cast_possible_truncation)] // side effect makes map_or awkward; symbol width fits in u16
-    fn render(self, area: Rect, buf: &mut Buffer, state: &mut Self::State) {
+    fn render(mut self, area: Rect, buf: &mut Buffer, state: &mut Self::State) {
         let inner = match &self.block {
             Some(block) => {
                 let inner = block.inner(area);
@@ -91,26 +139,39 @@ impl StatefulWidget for CommentList<'_> {
             None => area,
         };
 
-        if inner.width == 0 || inner.height == 0 || self.items.is_empty() {
+        state.item_areas.clear();
+        state.link_areas.clear();
+        if inner.width == 0 || inner.height == 0 || self.len == 0 {
             return;
         }
 
-        let item_heights: Vec<usize> = self.items.iter().map(CommentListItem::height).collect();
         let viewport_height = inner.height as usize;
-        let line_offset = state.selected.map_or(0, |s| {
-            calculate_centering_offset(s, &item_heights, viewport_height)
+        let selected = state.selected.map(|s| s.min(self.len - 1));
+        let (first, items) = build_window(
+            &mut self.item,
+            self.len,
+            selected.unwrap_or(0),
+            viewport_height,
+        );
+        let item_heights: Vec<usize> = items.iter().map(CommentListItem::height).collect();
+        let line_offset = selected.map_or(0, |s| {
+            calculate_centering_offset(s - first, &item_heights, viewport_height)
         });
+        state.item_areas = visible_item_areas(&item_heights, line_offset, inner);
+        for item in &mut state.item_areas {
+            item.index += first;
+        }
 
         let symbol_width = self.highlight_symbol.chars().count() as u16;
         let mut current_line = 0;
         let mut y = inner.top();
         let mut selected_first_line_y: Option<u16> = None;
 
-        for (item_idx, item) in self.items.iter().enumerate() {
+        for (item_idx, item) in (first..).zip(&items) {
             let is_selected = state.selected == Some(item_idx);
             let mut is_first_line_of_item = true;
 
-            for line in &item.lines {
+            for (line_idx, line) in item.lines.iter().enumerate() {
                 if current_line < line_offset {
                     current_line += 1;
                     is_first_line_of_item = false;
@@ -142,6 +203,22 @@ impl StatefulWidget for CommentList<'_> {
                 let content_x = inner.left() + symbol_width;
                 let content_width = inner.width.saturating_sub(symbol_width);
                 buf.set_line(content_x, y, line, content_width);
+                let row = Rect {
+                    x: content_x,
+                    y,
+                    width: content_width,
+                    height: 1,
+                };
+                let links = item.links.iter().filter(|link| link.line == line_idx);
+                state.link_areas.extend(links.filter_map(|link| {
+                    let area = Rect {
+                        x: content_x.saturating_add(link.column as u16),
+                        width: link.width as u16,
+                        ..row
+                    }
+                    .intersection(row);
+                    (!area.is_empty()).then(|| (area, link.url.clone()))
+                }));
                 y += 1;
                 current_line += 1;
                 is_first_line_of_item = false;
@@ -150,6 +227,67 @@ impl StatefulWidget for CommentList<'_> {
     }
 }
 
+/// Build the item at `anchor` and enough items on either side to fill the
+/// viewport however it's scrolled to center the anchor. Centering within this
+/// window gives the same offset as over the whole list. Returns the index of
+/// the first item built.
+fn build_window<'a>(
+    item: &mut impl FnMut(usize) -> CommentListItem<'a>,
+    len: usize,
+    anchor: usize,
+    viewport_height: usize,
+) -> (usize, Vec<CommentListItem<'a>>) {
+    let mut items = VecDeque::from([item(anchor)]);
+    let mut first = anchor;
+    let mut above = 0;
+    while first > 0 && above < viewport_height {
+        first -= 1;
+        let built = item(first);
+        above += built.height();
+        items.push_front(built);
+    }
+    let mut below = 0;
+    for index in anchor + 1..len {
+        if below >= viewport_height {
+            break;
+        }
+        let built = item(index);
+        below += built.height();
+        items.push_back(built);
+    }
+    (first, items.into())
+}
+
+/// Screen areas of the items overlapping the viewport that starts
+/// `line_offset` lines into the list.
+#[allow(clippy::cast_possible_truncation)] // bounded by the viewport height
+fn visible_item_areas(item_heights: &[usize], line_offset: usize, inner: Rect) -> Vec<ItemArea> {
+    let viewport_end = line_offset + inner.height as usize;
+    let mut areas = Vec::new();
+    let mut top = 0;
+    for (index, &height) in item_heights.iter().enumerate() {
+        let start = top.max(line_offset);
+        let end = (top + height).min(viewport_end);
+        if start < end {
+            areas.push(ItemArea {
+                index,
+                area: Rect {
+                    x: inner.x,
+                    y: inner.y + (start - line_offset) as u16,
+                    width: inner.width,
+                    height: (end - start) as u16,
+                },
+                starts_on_screen: top >= line_offset,
+            });
+        }
+        top += height;
+        if top >= viewport_end {
+            break;
+        }
+    }
+    areas
+}
+
 fn calculate_centering_offset(
     selected: usize,
     item_heights: &[usize],
@@ -203,6 +341,81 @@ mod tests {
         assert_eq!(offset, 5);
     }
 
+    #[test]
+    fn test_visible_item_areas_clip_to_viewport() {
+        let heights = vec![5, 5, 5];
+        let inner = Rect::new(1, 1, 20, 8);
+        // Viewport shows lines 3..11: the end of item 0, all of item 1, the start of item 2
+        let areas = visible_item_areas(&heights, 3, inner);
+        assert_eq!(
+            areas,
+            vec![
+                ItemArea {
+                    index: 0,
+                    area: Rect::new(1, 1, 20, 2),
+                    starts_on_screen: false,
+                },
+                ItemArea {
+                    index: 1,
+                    area: Rect::new(1, 3, 20, 5),
+                    starts_on_screen: true,
+                },
+                ItemArea {
+                    index: 2,
+                    area: Rect::new(1, 8, 20, 1),
+                    starts_on_screen: true,
+                },
+            ]
+        );
+    }
+
+    fn item(height: usize) -> CommentListItem<'static> {
+        CommentListItem::new(vec![Line::default(); height])
+    }
+
+    #[test]
+    fn test_window_centers_like_the_whole_list() {
+        let heights = [3, 7, 2, 5, 4, 6, 3, 8, 2, 5, 4, 3];
+        for viewport in [4, 9, 15] {
+            for selected in 0..heights.len() {
+                let mut built = Vec::new();
+                let (first, items) = build_window(
+                    &mut |i| {
+                        built.push(i);
+                        item(heights[i])
+                    },
+                    heights.len(),
+                    selected,
+                    viewport,
+                );
+                let window: Vec<usize> = items.iter().map(CommentListItem::height).collect();
+                let above: usize = heights[..first].iter().sum();
+                assert_eq!(
+                    above + calculate_centering_offset(selected - first, &window, viewport),
+                    calculate_centering_offset(selected, &heights, viewport),
+                    "selected {selected}, viewport {viewport}"
+                );
+                assert_eq!(built.len(), items.len());
+            }
+        }
+    }
+
+    #[test]
+    fn test_window_skips_items_far_from_the_viewport() {
+        let mut built = Vec::new();
+        build_window(
+            &mut |i| {
+                built.push(i);
+                item(3)
+            },
+            2000,
+            1000,
+            10,
+        );
+        built.sort_unstable();
+        assert_eq!(built, (996..=1004).collect::<Vec<_>>());
+    }
+
     #[test]
     fn test_centering_offset_first_item() {
         let heights = vec![5, 5, 5];